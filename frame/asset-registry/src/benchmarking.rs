@@ -18,7 +18,9 @@
 
 use super::*;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::Get;
 use frame_system::{self, RawOrigin};
+use sp_std::vec;
 use tidefi_primitives::CurrencyId;
 
 const SEED: u32 = 0;
@@ -38,6 +40,17 @@ benchmarks! {
    set_status {
     let user = pre_set_auth::<T>();
    }: _(RawOrigin::Signed(user), CurrencyId::Tdfy, true)
+   set_chain_info {
+    let user = pre_set_auth::<T>();
+    let chain_info = AssetChainInfo {
+      origin_chain: b"Ethereum".to_vec(),
+      contract_address: Some(vec![0; T::StringLimit::get() as usize]),
+      minimum_deposit: 1_000,
+      minimum_withdrawal: 1_000,
+      withdrawal_fee: 100,
+      confirmations_required: 12,
+    };
+   }: _(RawOrigin::Signed(user), CurrencyId::Tdfy, Some(chain_info))
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
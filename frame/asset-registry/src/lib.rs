@@ -47,6 +47,31 @@ pub mod pallet {
     CurrencyMetadata,
   };
 
+  /// External chain information of a currency, managed by the asset registry account.
+  #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+  pub struct AssetChainInfo<BoundedString> {
+    /// Chain the currency originates from. Ex: `Ethereum`
+    pub origin_chain: BoundedString,
+    /// Contract address of the currency on its origin chain, `None` for a native currency
+    pub contract_address: Option<BoundedString>,
+    /// Minimum amount of a deposit
+    pub minimum_deposit: Balance,
+    /// Minimum amount of a withdrawal
+    pub minimum_withdrawal: Balance,
+    /// Fee taken on the withdrawals, in the currency
+    pub withdrawal_fee: Balance,
+    /// Number of confirmations required on the origin chain before a deposit is minted
+    pub confirmations_required: u32,
+  }
+
+  pub type AssetChainInfoOf<T> = AssetChainInfo<BoundedVec<u8, <T as Config>::StringLimit>>;
+
+  /// Interface used by other pallets to read the external chain information of the currencies.
+  pub trait AssetChainInfoExt {
+    /// Whether the currency is bridged from an external chain.
+    fn is_bridged(currency_id: CurrencyId) -> bool;
+  }
+
   /// Asset registry configuration
   #[pallet::config]
   pub trait Config:
@@ -62,6 +87,10 @@ pub mod pallet {
     /// Weights
     type WeightInfo: WeightInfo;
 
+    /// The maximum length of the origin chain and contract address
+    #[pallet::constant]
+    type StringLimit: Get<u32>;
+
     /// Tidechain currency wrapper
     type CurrencyTidefi: Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
       + Mutate<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
@@ -78,6 +107,12 @@ pub mod pallet {
   #[pallet::getter(fn account_id)]
   pub type AssetRegistryAccountId<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+  /// External chain information of the currencies
+  #[pallet::storage]
+  #[pallet::getter(fn chain_info)]
+  pub type AssetsChainInfo<T: Config> =
+    StorageMap<_, Blake2_128Concat, CurrencyId, AssetChainInfoOf<T>, OptionQuery>;
+
   /// Genesis configuration
   #[pallet::genesis_config]
   pub struct GenesisConfig<T: Config> {
@@ -135,6 +170,8 @@ pub mod pallet {
     Registered(CurrencyId),
     /// Asset was updated. \[currency_id, is_enabled\]
     StatusChanged(CurrencyId, bool),
+    /// Asset external chain information was updated. \[currency_id\]
+    ChainInfoChanged(CurrencyId),
   }
 
   // Errors inform users that something went wrong.
@@ -150,6 +187,8 @@ pub mod pallet {
     AssetAlreadyRegistered,
     /// Invalid Currency Id
     CurrencyIdNotValid,
+    /// Origin chain or contract address is too long.
+    BadChainInfo,
   }
 
   #[pallet::call]
@@ -249,6 +288,47 @@ pub mod pallet {
 
       Ok(())
     }
+
+    /// Set or remove the external chain information of an asset.
+    ///
+    /// - `currency_id`: The currency ID to update
+    /// - `chain_info`: Origin chain, contract address, minimum amounts, withdrawal fee and
+    /// confirmations required, `None` to remove them
+    ///
+    /// Emits `ChainInfoChanged` event when successful.
+    ///
+    /// Weight: `O(1)`
+    #[pallet::weight(<T as Config>::WeightInfo::set_chain_info())]
+    pub fn set_chain_info(
+      origin: OriginFor<T>,
+      currency_id: CurrencyId,
+      chain_info: Option<AssetChainInfo<Vec<u8>>>,
+    ) -> DispatchResult {
+      // 1. Make sure it's signed from the asset-registry owner
+      ensure!(
+        Some(ensure_signed(origin)?) == Self::account_id(),
+        Error::<T>::AccessDenied
+      );
+
+      // 2. Make sure the currency is already registered
+      ensure!(
+        Self::is_currency_exist(currency_id),
+        Error::<T>::AssetNotRegistered
+      );
+
+      // 3. Update the chain information
+      match chain_info {
+        Some(chain_info) => {
+          AssetsChainInfo::<T>::insert(currency_id, Self::bounded_chain_info(chain_info)?)
+        }
+        None => AssetsChainInfo::<T>::remove(currency_id),
+      }
+
+      // 4. Emit the chain information update
+      Self::deposit_event(<Event<T>>::ChainInfoChanged(currency_id));
+
+      Ok(())
+    }
   }
 
   impl<T: Config> Pallet<T> {
@@ -282,6 +362,38 @@ pub mod pallet {
       Ok(())
     }
 
+    fn bounded_chain_info(
+      chain_info: AssetChainInfo<Vec<u8>>,
+    ) -> Result<AssetChainInfoOf<T>, Error<T>> {
+      Ok(AssetChainInfo {
+        origin_chain: chain_info
+          .origin_chain
+          .try_into()
+          .map_err(|_| Error::<T>::BadChainInfo)?,
+        contract_address: chain_info
+          .contract_address
+          .map(TryInto::try_into)
+          .transpose()
+          .map_err(|_| Error::<T>::BadChainInfo)?,
+        minimum_deposit: chain_info.minimum_deposit,
+        minimum_withdrawal: chain_info.minimum_withdrawal,
+        withdrawal_fee: chain_info.withdrawal_fee,
+        confirmations_required: chain_info.confirmations_required,
+      })
+    }
+
+    /// External chain information of the currency, with unbounded strings.
+    pub fn get_chain_info(currency_id: CurrencyId) -> Option<AssetChainInfo<Vec<u8>>> {
+      Self::chain_info(currency_id).map(|chain_info| AssetChainInfo {
+        origin_chain: chain_info.origin_chain.into(),
+        contract_address: chain_info.contract_address.map(Into::into),
+        minimum_deposit: chain_info.minimum_deposit,
+        minimum_withdrawal: chain_info.minimum_withdrawal,
+        withdrawal_fee: chain_info.withdrawal_fee,
+        confirmations_required: chain_info.confirmations_required,
+      })
+    }
+
     pub fn is_currency_exist(currency_id: CurrencyId) -> bool {
       match currency_id {
         // TDFY always exist
@@ -380,4 +492,10 @@ pub mod pallet {
       }
     }
   }
+
+  impl<T: Config> AssetChainInfoExt for Pallet<T> {
+    fn is_bridged(currency_id: CurrencyId) -> bool {
+      AssetsChainInfo::<T>::contains_key(currency_id)
+    }
+  }
 }
@@ -168,6 +168,7 @@ impl pallet_asset_registry::Config for Test {
   type Event = Event;
   type WeightInfo = crate::weights::SubstrateWeight<Test>;
   type AssetRegistryPalletId = AssetRegistryPalletId;
+  type StringLimit = ConstU32<255>;
   // Wrapped currency
   type CurrencyTidefi = Adapter<AccountId>;
 }
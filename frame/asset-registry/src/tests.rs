@@ -14,7 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::mock::{new_test_ext, AssetRegistry};
+use crate::{
+  mock::{new_test_ext, AssetRegistry, Origin, Test},
+  AssetChainInfo, Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use tidefi_primitives::CurrencyId;
 
 #[test]
 pub fn check_genesis_config() {
@@ -28,3 +33,83 @@ pub fn check_genesis_config() {
     )
   });
 }
+
+mod set_chain_info {
+  use super::*;
+
+  const REGISTRY_ACCOUNT_ID: u64 = 0;
+  const ETH_CURRENCY_ID: CurrencyId = CurrencyId::Wrapped(1000);
+
+  fn chain_info() -> AssetChainInfo<Vec<u8>> {
+    AssetChainInfo {
+      origin_chain: b"Ethereum".to_vec(),
+      contract_address: None,
+      minimum_deposit: 1_000,
+      minimum_withdrawal: 2_000,
+      withdrawal_fee: 100,
+      confirmations_required: 12,
+    }
+  }
+
+  #[test]
+  fn succeeds() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(AssetRegistry::set_chain_info(
+        Origin::signed(REGISTRY_ACCOUNT_ID),
+        ETH_CURRENCY_ID,
+        Some(chain_info())
+      ));
+      assert_eq!(
+        AssetRegistry::get_chain_info(ETH_CURRENCY_ID),
+        Some(chain_info())
+      );
+
+      assert_ok!(AssetRegistry::set_chain_info(
+        Origin::signed(REGISTRY_ACCOUNT_ID),
+        ETH_CURRENCY_ID,
+        None
+      ));
+      assert!(AssetRegistry::get_chain_info(ETH_CURRENCY_ID).is_none());
+    });
+  }
+
+  #[test]
+  fn fails_when_signer_is_not_registry_account() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        AssetRegistry::set_chain_info(Origin::signed(1), ETH_CURRENCY_ID, Some(chain_info())),
+        Error::<Test>::AccessDenied
+      );
+    });
+  }
+
+  #[test]
+  fn fails_when_asset_is_not_registered() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        AssetRegistry::set_chain_info(
+          Origin::signed(REGISTRY_ACCOUNT_ID),
+          CurrencyId::Wrapped(42),
+          Some(chain_info())
+        ),
+        Error::<Test>::AssetNotRegistered
+      );
+    });
+  }
+
+  #[test]
+  fn fails_when_contract_address_is_too_long() {
+    new_test_ext().execute_with(|| {
+      let mut chain_info = chain_info();
+      chain_info.contract_address = Some(vec![0; 256]);
+      assert_noop!(
+        AssetRegistry::set_chain_info(
+          Origin::signed(REGISTRY_ACCOUNT_ID),
+          ETH_CURRENCY_ID,
+          Some(chain_info)
+        ),
+        Error::<Test>::BadChainInfo
+      );
+    });
+  }
+}
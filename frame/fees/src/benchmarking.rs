@@ -0,0 +1,69 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+//! Benchmarking setup for pallet-fees
+use crate::*;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::{Percent, Permill};
+use sp_std::vec::Vec;
+use tidefi_primitives::CurrencyId;
+
+benchmarks! {
+   set_fee_distribution {
+      let distribution = FeeDistributionShares {
+         stakers: Percent::from_percent(40),
+         treasury: Percent::from_percent(30),
+         sunrise: Percent::from_percent(20),
+      };
+   }: _(RawOrigin::Root, Some(distribution))
+
+   set_swap_fees {
+      let swap_fees = SwapFeeSchedule {
+         fee: Permill::from_parts(2500),
+         market_maker_fee: Permill::from_parts(1000),
+         market_maker_limit_fee: Permill::from_parts(500),
+      };
+   }: _(RawOrigin::Root, swap_fees)
+
+   set_pair_swap_fees {
+      let swap_fees = SwapFeeSchedule {
+         fee: Permill::from_parts(2000),
+         market_maker_fee: Permill::from_parts(800),
+         market_maker_limit_fee: Permill::from_parts(400),
+      };
+   }: _(RawOrigin::Root, CurrencyId::Tdfy, CurrencyId::Wrapped(2), Some(swap_fees))
+
+   set_volume_fee_tiers {
+      let volume_fee_tiers = (1..=10_u128)
+         .map(|tier| VolumeFeeTier {
+            minimum_volume: tier * 1_000_000_000_000_000,
+            discount: Percent::from_percent(tier as u8 * 5),
+         })
+         .collect::<Vec<_>>();
+   }: _(RawOrigin::Root, volume_fee_tiers)
+
+   set_pay_fees_in_tdfy {
+      let caller: T::AccountId = whitelisted_caller();
+   }: _(RawOrigin::Signed(caller), true)
+
+   set_tdfy_fee_discount {
+   }: _(RawOrigin::Root, Percent::from_percent(25))
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
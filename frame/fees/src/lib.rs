@@ -17,6 +17,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod migrations;
+mod session_manager;
 
 #[cfg(test)]
 mod mock;
@@ -24,8 +25,15 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::*;
+
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
+pub use session_manager::FeesSessionManager;
 
 pub(crate) const LOG_TARGET: &str = "tidefi::fees";
 
@@ -53,8 +61,11 @@ pub mod pallet {
     },
     PalletId,
   };
+  use frame_system::pallet_prelude::*;
+  use pallet_sunrise::SunrisePoolsFunding;
+  use pallet_tidefi_stake::FeeSessionsInfo;
   use sp_runtime::{
-    traits::{AccountIdConversion, Saturating},
+    traits::{AccountIdConversion, Saturating, Zero},
     Percent, Permill, SaturatedConversion,
   };
   use sp_std::vec;
@@ -66,7 +77,111 @@ pub mod pallet {
   /// The current storage version.
   const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
+  /// Maximum number of eras of account fees returned at once by `get_account_fees`.
+  pub const MAX_ACCOUNT_FEES_ERAS: EraIndex = 1_000;
+
+  /// Maximum number of expired sessions drained at the end of a session.
+  pub const MAX_DRAINED_SESSIONS: SessionIndex = 10;
+
   type BoundedAccountFees = BoundedVec<(CurrencyId, Fee), ConstU32<1_000>>;
+  type BoundedVolumeFeeTiers = BoundedVec<VolumeFeeTier, ConstU32<10>>;
+
+  /// Interface used by the oracle to lock the swap fee rate when a swap is created.
+  pub trait SwapFeesExt<AccountId> {
+    /// Fee rate of the global fee schedule.
+    fn base_swap_fee_rate(swap_type: SwapType, is_market_maker: bool) -> Permill;
+    /// Fee rate for an account swapping `currency_from` to `currency_to`, including the pair
+    /// fee schedule and the volume discount.
+    fn swap_fee_rate(
+      account_id: &AccountId,
+      currency_from: CurrencyId,
+      currency_to: CurrencyId,
+      swap_type: SwapType,
+      is_market_maker: bool,
+    ) -> Permill;
+    /// Weight of `swap_fee_rate` and `swap_fee_in_tdfy`, recomputing the trading volume of the
+    /// account from the fees history of the `VolumeTierEras` eras at most once per era.
+    fn swap_fee_rate_weight() -> Weight;
+    /// Calculate the swap fees with a locked fee rate.
+    fn calculate_swap_fees_with_rate(
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      fee_rate: Permill,
+    ) -> Fee;
+    /// Register the swap fees for an account with a locked fee rate, allocating the sunrise
+    /// rewards when `sunrise_rewardable`.
+    fn register_swap_fees_with_rate(
+      account_id: AccountId,
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      fee_rate: Permill,
+      sunrise_rewardable: bool,
+    ) -> Result<Fee, DispatchError>;
+    /// TDFY amount paid for a swap fee in `currency_id`, when the account pays its swap fees in
+    /// TDFY, discount included.
+    fn swap_fee_in_tdfy(
+      account_id: &AccountId,
+      currency_id: CurrencyId,
+      fee: Balance,
+    ) -> Option<Balance>;
+    /// Register the swap fees paid in TDFY for an account, allocating the sunrise rewards when
+    /// `sunrise_rewardable`.
+    fn register_swap_fees_in_tdfy(
+      account_id: AccountId,
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      fee_tdfy: Balance,
+      sunrise_rewardable: bool,
+    ) -> Result<Fee, DispatchError>;
+  }
+
+  /// Swap fee rates, by type of trader and order.
+  #[derive(
+    Clone, Copy, Encode, Decode, Default, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+  )]
+  pub struct SwapFeeSchedule {
+    /// Retail user swap fee
+    pub fee: Permill,
+    /// Market maker market order fee
+    pub market_maker_fee: Permill,
+    /// Market maker limit order fee
+    pub market_maker_limit_fee: Permill,
+  }
+
+  /// Discount applied on the swap fees of the accounts reaching a trading volume.
+  #[derive(
+    Clone, Copy, Encode, Decode, Default, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+  )]
+  pub struct VolumeFeeTier {
+    /// Minimum trading volume, in TDFY, over the `VolumeTierEras` previous eras
+    pub minimum_volume: Balance,
+    /// Discount applied on the swap fees
+    pub discount: Percent,
+  }
+
+  /// Share of the session fees allocated to each destination, the TDFY remainder is burned.
+  ///
+  /// For the other currencies, the sunrise share and the remainder are transferred to the treasury.
+  #[derive(
+    Clone, Copy, Encode, Decode, Default, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+  )]
+  pub struct FeeDistributionShares {
+    /// Share transferred to the staking pallet to pay the stakers rewards
+    pub stakers: Percent,
+    /// Share transferred to the treasury
+    pub treasury: Percent,
+    /// Share used to replenish the sunrise pools (TDFY only)
+    pub sunrise: Percent,
+  }
+
+  /// Amounts moved out of the fees account for a currency at the end of a session.
+  #[derive(Clone, Encode, Decode, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+  pub struct DistributedFees {
+    pub stakers: Balance,
+    pub treasury: Balance,
+    pub sunrise: Balance,
+    pub burned: Balance,
+  }
 
   #[pallet::config]
   /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -84,6 +199,10 @@ pub mod pallet {
     #[pallet::constant]
     type FeesPalletId: Get<PalletId>;
 
+    /// Treasury pallet ID, receiving the treasury share of the fees
+    #[pallet::constant]
+    type TreasuryPalletId: Get<PalletId>;
+
     /// Number of sessions per era
     #[pallet::constant]
     type SessionsPerEra: Get<SessionIndex>;
@@ -92,22 +211,47 @@ pub mod pallet {
     #[pallet::constant]
     type SessionsArchive: Get<SessionIndex>;
 
+    /// Number of eras of fees history (`EraTotalFees` and `AccountFees`) to keep in archive.
+    ///
+    /// The history is never pruned before `VolumeTierEras` and the sunrise `Cooldown` are over.
+    #[pallet::constant]
+    type EraArchive: Get<EraIndex>;
+
     /// Number of block per session
     #[pallet::constant]
     type BlocksPerSession: Get<Self::BlockNumber>;
 
-    /// Retail user swap fee
+    /// Drive the sessions and eras from `pallet_session`, through the `FeesSessionManager`,
+    /// instead of `BlocksPerSession` and `SessionsPerEra`
+    #[pallet::constant]
+    type UseSessionManager: Get<bool>;
+
+    /// Active era of the validators, a new fees era is started when it changes and the sessions
+    /// are driven by `pallet_session`
+    type ValidatorEra: Get<Option<EraIndex>>;
+
+    /// Retail user swap fee, used as long as no `SwapFees` has been set by the `ForceOrigin`
     #[pallet::constant]
     type FeeAmount: Get<Permill>;
 
-    /// Market maker market order fee
+    /// Market maker market order fee, used as long as no `SwapFees` has been set by the `ForceOrigin`
     #[pallet::constant]
     type MarketMakerFeeAmount: Get<Permill>;
 
-    /// Market maker limit order fee
+    /// Market maker limit order fee, used as long as no `SwapFees` has been set by the `ForceOrigin`
     #[pallet::constant]
     type MarketMakerLimitFeeAmount: Get<Permill>;
 
+    /// Number of previous eras used to compute the trading volume of an account for the volume
+    /// fee tiers
+    #[pallet::constant]
+    type VolumeTierEras: Get<EraIndex>;
+
+    /// Share of the session fees transferred to the staking pallet to pay the stakers rewards,
+    /// used as long as no `FeeDistribution` has been set by the `ForceOrigin`
+    #[pallet::constant]
+    type StakersFeeShare: Get<Percent>;
+
     /// Security traits
     type Security: SecurityExt<Self::AccountId, Self::BlockNumber>;
 
@@ -115,7 +259,7 @@ pub mod pallet {
     type Staking: StakingExt<Self::AccountId>;
 
     /// Tidefi sunrise traits
-    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>;
+    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber> + SunrisePoolsFunding;
 
     /// Tidechain currency wrapper
     type CurrencyTidefi: Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
@@ -124,6 +268,9 @@ pub mod pallet {
 
     /// The origin which may forcibly update the fee and distribution percentage
     type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
   }
 
   #[pallet::pallet]
@@ -181,6 +328,82 @@ pub mod pallet {
     ValueQuery,
   >;
 
+  /// Number of blocks of the last ended session.
+  #[pallet::storage]
+  #[pallet::getter(fn last_session_blocks_count)]
+  pub type LastSessionBlocksCount<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+  /// Number of blocks of the last ended era.
+  #[pallet::storage]
+  #[pallet::getter(fn last_era_blocks_count)]
+  pub type LastEraBlocksCount<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+  /// Validators era of the active fees era, when the sessions are driven by `pallet_session`.
+  #[pallet::storage]
+  #[pallet::getter(fn last_validator_era)]
+  pub type LastValidatorEra<T: Config> = StorageValue<_, EraIndex, OptionQuery>;
+
+  /// Oldest session not drained yet, all the sessions below have been deleted.
+  #[pallet::storage]
+  #[pallet::getter(fn oldest_stored_session)]
+  pub type OldestStoredSession<T: Config> = StorageValue<_, SessionIndex, ValueQuery>;
+
+  /// Oldest era with fees history not pruned yet.
+  #[pallet::storage]
+  #[pallet::getter(fn oldest_stored_era)]
+  pub type OldestStoredEra<T: Config> = StorageValue<_, EraIndex, ValueQuery>;
+
+  /// Distribution of the session fees between the stakers, the treasury and the sunrise pools.
+  /// The TDFY remainder is burned, the other currencies remainder goes to the treasury.
+  ///
+  /// When not set, only `StakersFeeShare` is transferred to the stakers.
+  #[pallet::storage]
+  #[pallet::getter(fn fee_distribution)]
+  pub type FeeDistribution<T: Config> = StorageValue<_, FeeDistributionShares>;
+
+  /// Global swap fee schedule.
+  ///
+  /// When not set, the `FeeAmount`, `MarketMakerFeeAmount` and `MarketMakerLimitFeeAmount`
+  /// constants are used.
+  #[pallet::storage]
+  #[pallet::getter(fn swap_fees)]
+  pub type SwapFees<T: Config> = StorageValue<_, SwapFeeSchedule>;
+
+  /// Swap fee schedule overriding the global schedule for a pair, stored for both directions.
+  #[pallet::storage]
+  #[pallet::getter(fn pair_swap_fees)]
+  pub type PairSwapFees<T: Config> = StorageDoubleMap<
+    _,
+    Blake2_128Concat,
+    CurrencyId,
+    Blake2_128Concat,
+    CurrencyId,
+    SwapFeeSchedule,
+  >;
+
+  /// Volume fee tiers, sorted by ascending minimum volume.
+  #[pallet::storage]
+  #[pallet::getter(fn volume_fee_tiers)]
+  pub type VolumeFeeTiers<T: Config> = StorageValue<_, BoundedVolumeFeeTiers, ValueQuery>;
+
+  /// Trading volume of the account over the `VolumeTierEras` previous eras, in TDFY, and the era
+  /// it was computed for.
+  #[pallet::storage]
+  #[pallet::getter(fn account_trading_volume)]
+  pub type AccountTradingVolume<T: Config> =
+    StorageMap<_, Blake2_128Concat, T::AccountId, (EraIndex, Balance)>;
+
+  /// Accounts paying their swap fees in TDFY.
+  #[pallet::storage]
+  #[pallet::getter(fn pay_fees_in_tdfy)]
+  pub type PayFeesInTdfy<T: Config> =
+    StorageMap<_, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+
+  /// Discount applied on the swap fees paid in TDFY.
+  #[pallet::storage]
+  #[pallet::getter(fn tdfy_fee_discount)]
+  pub type TdfyFeeDiscount<T: Config> = StorageValue<_, Percent, ValueQuery>;
+
   /// Genesis configuration
   #[pallet::genesis_config]
   pub struct GenesisConfig<T: Config> {
@@ -233,6 +456,38 @@ pub mod pallet {
       session_index: SessionIndex,
       session_fees_by_currency: Vec<(CurrencyId, Balance)>,
     },
+    /// The fee distribution has been updated
+    FeeDistributionUpdated {
+      distribution: Option<FeeDistributionShares>,
+    },
+    /// The global swap fee schedule has been updated
+    SwapFeesUpdated {
+      swap_fees: SwapFeeSchedule,
+    },
+    /// The swap fee schedule of a pair has been updated
+    PairSwapFeesUpdated {
+      currency_id_a: CurrencyId,
+      currency_id_b: CurrencyId,
+      swap_fees: Option<SwapFeeSchedule>,
+    },
+    /// The volume fee tiers has been updated
+    VolumeFeeTiersUpdated {
+      volume_fee_tiers: Vec<VolumeFeeTier>,
+    },
+    /// The account updated how its swap fees are paid
+    PayFeesInTdfyUpdated {
+      account_id: T::AccountId,
+      enabled: bool,
+    },
+    /// The discount on the swap fees paid in TDFY has been updated
+    TdfyFeeDiscountUpdated {
+      discount: Percent,
+    },
+    /// The session fees has been distributed
+    SessionFeesDistributed {
+      session_index: SessionIndex,
+      distributed_fees_by_currency: Vec<(CurrencyId, DistributedFees)>,
+    },
     EraStarted {
       era_index: EraIndex,
     },
@@ -250,6 +505,16 @@ pub mod pallet {
     BalanceOverflow,
     /// Invalid TDFY value in the order book
     InvalidTdfyValue,
+    /// The distribution shares exceed 100%
+    InvalidFeeDistribution,
+    /// The pair currencies should be different
+    InvalidPair,
+    /// The volume fee tiers should be sorted by ascending minimum volume
+    InvalidVolumeFeeTiers,
+    /// Too many volume fee tiers
+    TooManyVolumeFeeTiers,
+    /// The era range is reversed or exceeds `MAX_ACCOUNT_FEES_ERAS`
+    InvalidEraRange,
   }
 
   // hooks
@@ -259,200 +524,634 @@ pub mod pallet {
       migrations::migrate_to_v1::<T, Self>()
     }
 
+    /// Prune the old eras fees history when chain is idle
+    fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+      Self::prune_old_eras(remaining_weight)
+    }
+
+    /// Session and era transitions, with the session fees distribution
     fn on_initialize(_now: T::BlockNumber) -> Weight {
-      // just return the weight of the on_finalize.
-      T::DbWeight::get().reads(2)
+      if T::UseSessionManager::get() {
+        // the sessions are driven by `pallet_session`
+        return 0;
+      }
+
+      // `ActiveEra`, `CurrentBlockCount` and the era start in `on_finalize`
+      let weight = T::DbWeight::get().reads_writes(4, 1);
+
+      if let Some(active_era) = Self::current_era() {
+        if let Some(start_block) = active_era.start_block {
+          let real_block = T::Security::get_current_block_count();
+          // determine when the session
+          let session_start_block = match active_era.last_session_block {
+            Some(last_session_block) => last_session_block,
+            None => start_block,
+          };
+          let expected_end_block_for_session =
+            session_start_block.saturating_add(T::BlocksPerSession::get());
+
+          // end of session
+          if real_block == expected_end_block_for_session {
+            return weight.saturating_add(Self::end_session(active_era, real_block));
+          }
+        }
+      }
+
+      weight
     }
 
     fn on_finalize(_current_block: T::BlockNumber) {
+      if T::UseSessionManager::get() {
+        return;
+      }
+
+      // start the new era, the `Time` is set by now
       if let Some(mut active_era) = Self::current_era() {
-        let real_block = T::Security::get_current_block_count();
-        match active_era.start_block {
-          Some(start_block) => {
-            // determine when the session
-            let session_start_block = match active_era.last_session_block {
-              Some(last_session_block) => last_session_block,
-              None => start_block,
-            };
-            let expected_end_block_for_session =
-              session_start_block.saturating_add(T::BlocksPerSession::get());
-
-            // end of session
-            if real_block == expected_end_block_for_session {
-              let current_session = CurrentSession::<T>::get();
-
-              let expected_end_session_for_era = match active_era.start_session_index {
-                Some(start_session_index) => start_session_index,
-                None => current_session,
-              }
-              .saturating_add(T::SessionsPerEra::get());
+        if active_era.start_block.is_none() {
+          let real_block = T::Security::get_current_block_count();
+          let now_as_millis_u64 = T::UnixTime::now().as_millis().saturated_into::<u64>();
+          active_era.start = Some(now_as_millis_u64);
+          active_era.start_block = Some(real_block);
+          active_era.start_session_index = Some(CurrentSession::<T>::get());
+          Self::deposit_event(Event::<T>::EraStarted {
+            era_index: active_era.index,
+          });
+          ActiveEra::<T>::put(active_era);
+          log!(trace, "Initializing in block #{:?}", real_block);
+        }
+      }
+      // `on_finalize` weight is tracked in `on_initialize`
+    }
+  }
 
-              log!(
-                debug,
-                "Fees compound session #{} started in block #{:?}, and is now expired.",
-                current_session,
-                start_block
-              );
+  #[pallet::call]
+  impl<T: Config> Pallet<T> {
+    /// Set the distribution of the session fees.
+    ///
+    /// - `distribution`: Shares transferred to the stakers, the treasury and the sunrise pools,
+    ///   the TDFY remainder is burned. For the other currencies, the sunrise share and the
+    ///   remainder are transferred to the treasury. `None` restore the default `StakersFeeShare`.
+    ///
+    /// Emits `FeeDistributionUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::set_fee_distribution())]
+    pub fn set_fee_distribution(
+      origin: OriginFor<T>,
+      distribution: Option<FeeDistributionShares>,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+
+      match distribution {
+        Some(shares) => {
+          let total_shares = [shares.stakers, shares.treasury, shares.sunrise]
+            .iter()
+            .map(|share| share.deconstruct() as u16)
+            .sum::<u16>();
+          ensure!(total_shares <= 100, Error::<T>::InvalidFeeDistribution);
+          FeeDistribution::<T>::put(shares);
+        }
+        None => FeeDistribution::<T>::kill(),
+      }
 
-              // get current session total trade / currency
-              let session_fees_by_currency: Vec<(CurrencyId, Balance)> =
-                SessionTotalFees::<T>::iter_prefix(current_session)
-                  .map(|(currency_id, fee)| (currency_id, fee.fee))
-                  .collect();
-
-              // notify the staking pallet that we are done with this session
-              // the compute can be done for all stakers
-              if let Err(err) =
-                T::Staking::on_session_end(current_session, session_fees_by_currency.clone())
-              {
-                log!(error, "Can't notify staking pallet {:?}", err);
-              }
+      Self::deposit_event(Event::<T>::FeeDistributionUpdated { distribution });
+      Ok(().into())
+    }
 
-              // Emit end of session event on chain
-              Self::deposit_event(Event::<T>::SessionEnded {
-                era_index: active_era.index,
-                session_index: current_session,
-                session_fees_by_currency,
-              });
-
-              // increment our session
-              let new_session = current_session.saturating_add(1_u64);
-              CurrentSession::<T>::put(new_session);
-              StoredSessions::<T>::insert(current_session, ());
-              // record the session change for the era
-              active_era.last_session_block = Some(real_block);
-
-              if current_session == expected_end_session_for_era {
-                Self::deposit_event(Event::<T>::EraEnded {
-                  era_index: active_era.index,
-                });
-                // increment the era index
-                active_era.index = active_era.index.saturating_add(1);
-                // reset the era values
-                active_era.last_session_block = None;
-                active_era.start_block = None;
-                active_era.start_session_index = None;
-                active_era.start = None;
-              }
+    /// Set the global swap fee schedule.
+    ///
+    /// - `swap_fees`: Retail, market maker market order and market maker limit order fees.
+    ///
+    /// Emits `SwapFeesUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::set_swap_fees())]
+    pub fn set_swap_fees(
+      origin: OriginFor<T>,
+      swap_fees: SwapFeeSchedule,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+      SwapFees::<T>::put(swap_fees);
+      Self::deposit_event(Event::<T>::SwapFeesUpdated { swap_fees });
+      Ok(().into())
+    }
 
-              // update active era
-              ActiveEra::<T>::put(active_era);
+    /// Set the swap fee schedule of a pair, applied in both directions.
+    ///
+    /// - `currency_id_a`: First currency of the pair.
+    /// - `currency_id_b`: Second currency of the pair.
+    /// - `swap_fees`: Fee schedule of the pair, `None` restore the global fee schedule.
+    ///
+    /// Emits `PairSwapFeesUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::set_pair_swap_fees())]
+    pub fn set_pair_swap_fees(
+      origin: OriginFor<T>,
+      currency_id_a: CurrencyId,
+      currency_id_b: CurrencyId,
+      swap_fees: Option<SwapFeeSchedule>,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+      ensure!(currency_id_a != currency_id_b, Error::<T>::InvalidPair);
+
+      PairSwapFees::<T>::set(currency_id_a, currency_id_b, swap_fees);
+      PairSwapFees::<T>::set(currency_id_b, currency_id_a, swap_fees);
+
+      Self::deposit_event(Event::<T>::PairSwapFeesUpdated {
+        currency_id_a,
+        currency_id_b,
+        swap_fees,
+      });
+      Ok(().into())
+    }
 
-              // drain old sessions
-              Self::drain_old_sessions();
-            }
-          }
-          None => {
-            let now_as_millis_u64 = T::UnixTime::now().as_millis().saturated_into::<u64>();
-            active_era.start = Some(now_as_millis_u64);
-            active_era.start_block = Some(real_block);
-            active_era.start_session_index = Some(CurrentSession::<T>::get());
-            // This write only ever happens once, we don't include it in the weight in
-            // general
-            Self::deposit_event(Event::<T>::EraStarted {
-              era_index: active_era.index,
-            });
-            ActiveEra::<T>::put(active_era);
-            log!(trace, "Initializing in block #{:?}", real_block);
-          }
-        }
+    /// Set the volume fee tiers.
+    ///
+    /// - `volume_fee_tiers`: Discounts by minimum trading volume in TDFY, sorted by ascending
+    ///   minimum volume.
+    ///
+    /// Emits `VolumeFeeTiersUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::set_volume_fee_tiers())]
+    pub fn set_volume_fee_tiers(
+      origin: OriginFor<T>,
+      volume_fee_tiers: Vec<VolumeFeeTier>,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+      ensure!(
+        volume_fee_tiers
+          .windows(2)
+          .all(|tiers| tiers[0].minimum_volume < tiers[1].minimum_volume),
+        Error::<T>::InvalidVolumeFeeTiers
+      );
+
+      let bounded_volume_fee_tiers: BoundedVolumeFeeTiers = volume_fee_tiers
+        .clone()
+        .try_into()
+        .map_err(|_| Error::<T>::TooManyVolumeFeeTiers)?;
+      VolumeFeeTiers::<T>::put(bounded_volume_fee_tiers);
+
+      Self::deposit_event(Event::<T>::VolumeFeeTiersUpdated { volume_fee_tiers });
+      Ok(().into())
+    }
+
+    /// Pay the swap fees in TDFY, at the current exchange rate with the `TdfyFeeDiscount`,
+    /// instead of the sold currency.
+    ///
+    /// The setting is applied to the swaps created after the update.
+    ///
+    /// - `enabled`: Pay the swap fees in TDFY.
+    ///
+    /// Emits `PayFeesInTdfyUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::set_pay_fees_in_tdfy())]
+    pub fn set_pay_fees_in_tdfy(origin: OriginFor<T>, enabled: bool) -> DispatchResultWithPostInfo {
+      let account_id = ensure_signed(origin)?;
+
+      if enabled {
+        PayFeesInTdfy::<T>::insert(&account_id, true);
+      } else {
+        PayFeesInTdfy::<T>::remove(&account_id);
       }
-      // `on_finalize` weight is tracked in `on_initialize`
+
+      Self::deposit_event(Event::<T>::PayFeesInTdfyUpdated {
+        account_id,
+        enabled,
+      });
+      Ok(().into())
+    }
+
+    /// Set the discount applied on the swap fees paid in TDFY.
+    ///
+    /// - `discount`: Discount on the swap fees.
+    ///
+    /// Emits `TdfyFeeDiscountUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::set_tdfy_fee_discount())]
+    pub fn set_tdfy_fee_discount(
+      origin: OriginFor<T>,
+      discount: Percent,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+      TdfyFeeDiscount::<T>::put(discount);
+      Self::deposit_event(Event::<T>::TdfyFeeDiscountUpdated { discount });
+      Ok(().into())
     }
   }
 
   impl<T: Config> Pallet<T> {
-    // Delete all session where the index < current_session - T::SessionsArchive
-    pub(crate) fn drain_old_sessions() {
+    // End the current session of the active era, distribute the session fees and notify the
+    // staking pallet, return the weight consumed
+    fn end_session(
+      mut active_era: ActiveEraInfo<T::BlockNumber>,
+      real_block: T::BlockNumber,
+    ) -> Weight {
       let current_session = CurrentSession::<T>::get();
-      for (session, _) in StoredSessions::<T>::iter() {
-        if session < current_session.saturating_sub(T::SessionsArchive::get()) {
-          // delete the session
-          let _ = SessionTotalFees::<T>::clear_prefix(session, u32::MAX, None);
-          StoredSessions::<T>::remove(session);
-        }
+
+      let expected_end_session_for_era = match active_era.start_session_index {
+        Some(start_session_index) => start_session_index,
+        None => current_session,
       }
-    }
+      .saturating_add(T::SessionsPerEra::get());
 
-    // Initialize new era
-    pub fn start_era() {
-      ActiveEra::<T>::mutate(|active_era| {
-        let new_index = active_era
-          .as_ref()
-          .map(|info| info.index.saturating_add(1))
-          .unwrap_or(0);
-        *active_era = Some(ActiveEraInfo::<T::BlockNumber> {
-          index: new_index,
-          // Set new active era start in next `on_finalize`. To guarantee usage of `Time`
-          start_session_index: None,
-          start_block: None,
-          last_session_block: None,
-          start: None,
+      log!(
+        debug,
+        "Fees compound session #{} started in block #{:?}, and is now expired.",
+        current_session,
+        active_era.start_block
+      );
+
+      let weight = Self::close_session(current_session, active_era.index);
+
+      // record the session change for the era
+      Self::record_session_end(&active_era, real_block);
+      active_era.last_session_block = Some(real_block);
+
+      if current_session == expected_end_session_for_era {
+        Self::record_era_end(&active_era, real_block);
+        Self::deposit_event(Event::<T>::EraEnded {
+          era_index: active_era.index,
         });
-        new_index
+        // increment the era index
+        active_era.index = active_era.index.saturating_add(1);
+        // reset the era values, the new era is started in `on_finalize`
+        active_era.last_session_block = None;
+        active_era.start_block = None;
+        active_era.start_session_index = None;
+        active_era.start = None;
+      }
+
+      // update active era
+      ActiveEra::<T>::put(active_era);
+
+      // `ActiveEra`, `LastSessionBlocksCount` and `LastEraBlocksCount`
+      weight.saturating_add(T::DbWeight::get().writes(3))
+    }
+
+    // Distribute the fees of the session and notify the staking pallet, then move to the next
+    // session, return the weight consumed
+    fn close_session(current_session: SessionIndex, era_index: EraIndex) -> Weight {
+      // get current session total trade / currency
+      let session_fees_by_currency: Vec<(CurrencyId, Balance)> =
+        SessionTotalFees::<T>::iter_prefix(current_session)
+          .map(|(currency_id, fee)| (currency_id, fee.fee))
+          .collect();
+
+      // distribute the session fees to the stakers, treasury and sunrise pools
+      let distributed_fees_by_currency = Self::distribute_session_fees(&session_fees_by_currency);
+      Self::deposit_event(Event::<T>::SessionFeesDistributed {
+        session_index: current_session,
+        distributed_fees_by_currency,
       });
+
+      let weight = Self::session_fees_weight(session_fees_by_currency.len() as u32);
+
+      // notify the staking pallet that we are done with this session
+      // the compute can be done for all stakers
+      if let Err(err) =
+        T::Staking::on_session_end(current_session, session_fees_by_currency.clone())
+      {
+        log!(error, "Can't notify staking pallet {:?}", err);
+      }
+
+      // Emit end of session event on chain
+      Self::deposit_event(Event::<T>::SessionEnded {
+        era_index,
+        session_index: current_session,
+        session_fees_by_currency,
+      });
+
+      // increment our session
+      let new_session = current_session.saturating_add(1_u64);
+      CurrentSession::<T>::put(new_session);
+      StoredSessions::<T>::insert(current_session, ());
+
+      // `CurrentSession`, `FeeDistribution` and the staking `InterestCompoundLastSession`,
+      // `PendingStoredSessions`
+      weight
+        .saturating_add(T::DbWeight::get().reads_writes(2, 4))
+        .saturating_add(Self::drain_old_sessions())
     }
-  }
 
-  impl<T: Config> FeesExt<T::AccountId, T::BlockNumber> for Pallet<T> {
-    fn era_blocks_count() -> T::BlockNumber {
-      T::BlocksPerSession::get()
-        .saturating_mul(T::BlockNumber::from(T::SessionsPerEra::get() as u32))
+    // `pallet_session` ended the session `end_index`, close the current fees session
+    pub(crate) fn on_session_manager_end(end_index: SessionIndex) {
+      // `ActiveEra`
+      let mut weight = T::DbWeight::get().reads(1);
+      if let Some(active_era) = Self::current_era() {
+        let current_session = CurrentSession::<T>::get();
+        log!(
+          debug,
+          "Fees session #{} ended by the session manager (session #{}).",
+          current_session,
+          end_index
+        );
+        Self::record_session_end(&active_era, T::Security::get_current_block_count());
+        // `CurrentSession`, `CurrentBlockCount` and `LastSessionBlocksCount`
+        weight = weight
+          .saturating_add(Self::close_session(current_session, active_era.index))
+          .saturating_add(T::DbWeight::get().reads_writes(2, 1));
+      }
+
+      // the session manager hooks are not accounted by `pallet_session`
+      frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
     }
 
-    fn account_id() -> T::AccountId {
-      T::FeesPalletId::get().into_account_truncating()
+    // `pallet_session` started the session `start_index`, start a new era when the validators
+    // era changed, the fees sessions and eras keep their own increasing index
+    pub(crate) fn on_session_manager_start(start_index: SessionIndex) {
+      // `ActiveEra`, `ValidatorEra` and `LastValidatorEra`
+      let mut weight = T::DbWeight::get().reads(3);
+
+      if let Some(mut active_era) = Self::current_era() {
+        let real_block = T::Security::get_current_block_count();
+        let validator_era = T::ValidatorEra::get();
+        let era_changed = validator_era != LastValidatorEra::<T>::get();
+
+        if active_era.start_block.is_none() || era_changed {
+          if active_era.start_block.is_some() {
+            Self::record_era_end(&active_era, real_block);
+            Self::deposit_event(Event::<T>::EraEnded {
+              era_index: active_era.index,
+            });
+            active_era.index = active_era.index.saturating_add(1);
+          }
+          active_era = ActiveEraInfo::<T::BlockNumber> {
+            index: active_era.index,
+            start: Some(T::UnixTime::now().as_millis().saturated_into::<u64>()),
+            start_block: Some(real_block),
+            start_session_index: Some(CurrentSession::<T>::get()),
+            last_session_block: None,
+          };
+          LastValidatorEra::<T>::set(validator_era);
+          Self::deposit_event(Event::<T>::EraStarted {
+            era_index: active_era.index,
+          });
+        } else {
+          active_era.last_session_block = Some(real_block);
+        }
+        log!(
+          trace,
+          "Fees era #{} updated for the session #{}.",
+          active_era.index,
+          start_index
+        );
+
+        ActiveEra::<T>::put(active_era);
+        // `CurrentBlockCount`, `CurrentSession`, `ActiveEra`, `LastValidatorEra` and
+        // `LastEraBlocksCount`
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 3));
+      }
+
+      frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
     }
 
-    fn current_era() -> Option<ActiveEraInfo<T::BlockNumber>> {
-      ActiveEra::<T>::get()
+    // Record the number of blocks of the session ending at `real_block`
+    fn record_session_end(active_era: &ActiveEraInfo<T::BlockNumber>, real_block: T::BlockNumber) {
+      if let Some(session_start_block) = active_era.last_session_block.or(active_era.start_block) {
+        LastSessionBlocksCount::<T>::put(real_block.saturating_sub(session_start_block));
+      }
     }
 
-    fn calculate_swap_fees(
-      currency_id: CurrencyId,
-      total_amount_before_fees: Balance,
-      swap_type: SwapType,
-      is_market_maker: bool,
-    ) -> Fee {
-      let fee = if is_market_maker {
+    // Record the number of blocks of the era ending at `real_block`
+    fn record_era_end(active_era: &ActiveEraInfo<T::BlockNumber>, real_block: T::BlockNumber) {
+      if let Some(start_block) = active_era.start_block {
+        LastEraBlocksCount::<T>::put(real_block.saturating_sub(start_block));
+      }
+    }
+
+    // Number of blocks of a session, measured on the last ended session, or `BlocksPerSession`
+    // until a session ended
+    pub(crate) fn measured_session_blocks_count() -> T::BlockNumber {
+      Self::last_session_blocks_count()
+        .filter(|blocks_count| !blocks_count.is_zero())
+        .unwrap_or_else(T::BlocksPerSession::get)
+    }
+
+    // Weight of the distribution of the session fees and the staking notification, for the
+    // number of currencies with fees in the session
+    fn session_fees_weight(currencies: u32) -> Weight {
+      // `SessionTotalFees`, up to 3 transfers and 1 burn, the staking `SessionTotalFees`
+      T::DbWeight::get()
+        .reads_writes(13, 13)
+        .saturating_mul(currencies.into())
+    }
+
+    // Delete the sessions where the index < current_session - T::SessionsArchive, from the
+    // `OldestStoredSession`, up to `MAX_DRAINED_SESSIONS` at once as the session index can jump
+    // when the sessions are managed by `pallet_session`, return the weight consumed
+    pub(crate) fn drain_old_sessions() -> Weight {
+      let drain_before = CurrentSession::<T>::get().saturating_sub(T::SessionsArchive::get());
+      let oldest_stored_session = OldestStoredSession::<T>::get();
+      // `CurrentSession` and `OldestStoredSession`
+      let mut reads: u32 = 2;
+      let mut writes: u32 = 0;
+
+      let mut session = oldest_stored_session;
+      while session < drain_before
+        && session.saturating_sub(oldest_stored_session) < MAX_DRAINED_SESSIONS
+      {
+        reads = reads.saturating_add(1);
+        if StoredSessions::<T>::take(session).is_some() {
+          let removed = SessionTotalFees::<T>::clear_prefix(session, u32::MAX, None);
+          reads = reads.saturating_add(removed.loops);
+          writes = writes.saturating_add(1_u32.saturating_add(removed.unique));
+        }
+        session = session.saturating_add(1);
+      }
+
+      if session != oldest_stored_session {
+        OldestStoredSession::<T>::put(session);
+        writes = writes.saturating_add(1);
+      }
+
+      T::DbWeight::get().reads_writes(reads.into(), writes.into())
+    }
+
+    // Number of eras of fees history retained, at least `VolumeTierEras` and until the sunrise
+    // rewards of the era can be claimed, after the `Cooldown`
+    pub(crate) fn retained_eras() -> EraIndex {
+      let blocks_per_era = Self::era_blocks_count().saturated_into::<u64>().max(1);
+      let cooldown_eras = T::Sunrise::cooldown_blocks_count()
+        .saturated_into::<u64>()
+        .saturating_add(blocks_per_era - 1)
+        / blocks_per_era;
+
+      T::EraArchive::get()
+        .max(T::VolumeTierEras::get())
+        .max(cooldown_eras.saturating_add(1).saturated_into::<EraIndex>())
+    }
+
+    // Delete the fees history of the eras where the index < current_era - retained eras,
+    // within the `remaining_weight`, and return the weight consumed
+    pub(crate) fn prune_old_eras(remaining_weight: Weight) -> Weight {
+      let db_weight = T::DbWeight::get();
+      let write_weight = db_weight.writes(1).max(1);
+      // scan of the `EraTotalFees` and `AccountFees` prefixes of an era
+      let era_weight = db_weight.reads(2).max(1);
+      // `ActiveEra` and `OldestStoredEra`
+      let mut consumed_weight = db_weight.reads(2);
+
+      let current_era = match Self::current_era() {
+        Some(active_era) => active_era.index,
+        None => return consumed_weight,
+      };
+      let prune_before = current_era.saturating_sub(Self::retained_eras());
+      let oldest_stored_era = Self::oldest_stored_era();
+      let mut oldest_era = oldest_stored_era;
+
+      while oldest_era < prune_before {
+        if remaining_weight.saturating_sub(consumed_weight)
+          < era_weight.saturating_add(write_weight)
+        {
+          break;
+        }
+        consumed_weight = consumed_weight.saturating_add(era_weight);
+
+        // keep room for the `OldestStoredEra` update
+        let limit = (remaining_weight.saturating_sub(consumed_weight) / write_weight)
+          .saturating_sub(1)
+          .saturated_into::<u32>();
+        if limit.is_zero() {
+          break;
+        }
+
+        let removed_totals = EraTotalFees::<T>::clear_prefix(oldest_era, limit, None);
+        consumed_weight = consumed_weight.saturating_add(
+          db_weight.reads_writes(removed_totals.loops.into(), removed_totals.unique.into()),
+        );
+        if removed_totals.maybe_cursor.is_some() {
+          break;
+        }
+
+        let limit = limit.saturating_sub(removed_totals.unique);
+        if limit.is_zero() {
+          break;
+        }
+
+        let removed_accounts = AccountFees::<T>::clear_prefix(oldest_era, limit, None);
+        consumed_weight = consumed_weight.saturating_add(db_weight.reads_writes(
+          removed_accounts.loops.into(),
+          removed_accounts.unique.into(),
+        ));
+        if removed_accounts.maybe_cursor.is_some() {
+          break;
+        }
+
+        oldest_era = oldest_era.saturating_add(1);
+      }
+
+      if oldest_era != oldest_stored_era {
+        OldestStoredEra::<T>::put(oldest_era);
+        consumed_weight = consumed_weight.saturating_add(db_weight.writes(1));
+      }
+
+      consumed_weight
+    }
+
+    // Global swap fee schedule, fallback to the runtime constants
+    pub(crate) fn global_swap_fees() -> SwapFeeSchedule {
+      Self::swap_fees().unwrap_or_else(|| SwapFeeSchedule {
+        fee: T::FeeAmount::get(),
+        market_maker_fee: T::MarketMakerFeeAmount::get(),
+        market_maker_limit_fee: T::MarketMakerLimitFeeAmount::get(),
+      })
+    }
+
+    // Select the fee rate of the schedule
+    fn fee_rate(swap_fees: SwapFeeSchedule, swap_type: SwapType, is_market_maker: bool) -> Permill {
+      if is_market_maker {
         match swap_type {
-          SwapType::Limit => T::MarketMakerLimitFeeAmount::get(),
-          SwapType::Market => T::MarketMakerFeeAmount::get(),
+          SwapType::Limit => swap_fees.market_maker_limit_fee,
+          SwapType::Market => swap_fees.market_maker_fee,
         }
       } else {
-        T::FeeAmount::get()
-      } * total_amount_before_fees;
+        swap_fees.fee
+      }
+    }
 
-      Fee {
-        amount: total_amount_before_fees,
-        fee,
-        fee_tdfy: T::Sunrise::try_get_tdfy_value(currency_id, fee).unwrap_or_default(),
+    // Get the total fees of the era by currency, for quick RPC call
+    pub fn get_era_fees(era_index: EraIndex) -> Vec<(CurrencyId, Fee)> {
+      EraTotalFees::<T>::iter_prefix(era_index).collect()
+    }
+
+    // Get the fees of the account by currency for each era of the inclusive range with fees,
+    // for quick RPC call
+    pub fn get_account_fees(
+      account_id: &T::AccountId,
+      start_era: EraIndex,
+      end_era: EraIndex,
+    ) -> Result<Vec<(EraIndex, Vec<(CurrencyId, Fee)>)>, DispatchError> {
+      ensure!(
+        start_era <= end_era && end_era - start_era < MAX_ACCOUNT_FEES_ERAS,
+        Error::<T>::InvalidEraRange
+      );
+
+      Ok(
+        (start_era..=end_era)
+          .filter_map(|era_index| {
+            let account_fees = Self::account_fees(era_index, account_id);
+            if account_fees.is_empty() {
+              None
+            } else {
+              Some((era_index, account_fees.into_inner()))
+            }
+          })
+          .collect(),
+      )
+    }
+
+    /// Trading volume of the account, in TDFY, over the `VolumeTierEras` eras preceding the
+    /// current era.
+    ///
+    /// The volume is computed once per era from the `AccountFees` history and cached.
+    pub fn trading_volume(account_id: &T::AccountId) -> Balance {
+      let current_era = match Self::current_era() {
+        Some(current_era) => current_era.index,
+        None => return 0,
+      };
+
+      match AccountTradingVolume::<T>::get(account_id) {
+        Some((era_index, volume)) if era_index == current_era => volume,
+        _ => {
+          let volume = (current_era.saturating_sub(T::VolumeTierEras::get())..current_era)
+            .flat_map(|era_index| AccountFees::<T>::get(era_index, account_id))
+            .map(|(currency_id, fee)| {
+              T::Sunrise::try_get_tdfy_value(currency_id, fee.amount).unwrap_or_default()
+            })
+            .fold(0, |total: Balance, volume| total.saturating_add(volume));
+          AccountTradingVolume::<T>::insert(account_id, (current_era, volume));
+          volume
+        }
       }
     }
 
-    fn register_swap_fees(
+    /// Discount of the highest volume fee tier reached by the account.
+    pub fn volume_discount(account_id: &T::AccountId) -> Percent {
+      let volume_fee_tiers = Self::volume_fee_tiers();
+      if volume_fee_tiers.is_empty() {
+        return Percent::zero();
+      }
+
+      let volume = Self::trading_volume(account_id);
+      volume_fee_tiers
+        .iter()
+        .rev()
+        .find(|tier| tier.minimum_volume <= volume)
+        .map(|tier| tier.discount)
+        .unwrap_or_else(Percent::zero)
+    }
+
+    // Register the swap fees for the current era and session
+    fn do_register_swap_fees(
       account_id: T::AccountId,
       currency_id: CurrencyId,
-      total_amount_before_fees: Balance,
-      swap_type: SwapType,
-      is_market_maker: bool,
+      new_fee: Fee,
+      sunrise_rewardable: bool,
     ) -> Result<Fee, DispatchError> {
       let fee = match Self::current_era() {
         Some(current_era) => {
           let current_session = CurrentSession::<T>::get();
-          let new_fee = Self::calculate_swap_fees(
-            currency_id,
-            total_amount_before_fees,
-            swap_type,
-            is_market_maker,
-          );
 
-          T::Sunrise::try_allocate_rewards_for_swap(
-            &account_id,
-            current_era.index,
-            &new_fee,
-            currency_id,
-          )?;
+          if sunrise_rewardable {
+            T::Sunrise::try_allocate_rewards_for_swap(
+              &account_id,
+              current_era.index,
+              &new_fee,
+              currency_id,
+            )?;
+          }
 
           // Update fees pool for the current era / currency
           EraTotalFees::<T>::mutate_exists(
@@ -527,7 +1226,7 @@ pub mod pallet {
         }
         // No fees are taken as there is no active era
         None => Fee {
-          amount: total_amount_before_fees,
+          amount: new_fee.amount,
           fee: 0,
           fee_tdfy: 0,
         },
@@ -535,5 +1234,288 @@ pub mod pallet {
 
       Ok(fee)
     }
+
+    /// Treasury account, receiving the treasury share of the fees.
+    pub fn treasury_account_id() -> T::AccountId {
+      T::TreasuryPalletId::get().into_account_truncating()
+    }
+
+    // Distribute the session fees from the fees account, following the `FeeDistribution`.
+    // The sunrise share is only distributed for TDFY, as the pools are paid in TDFY, and is kept
+    // in the fees account for other currencies.
+    pub(crate) fn distribute_session_fees(
+      session_fees_by_currency: &[(CurrencyId, Balance)],
+    ) -> Vec<(CurrencyId, DistributedFees)> {
+      let distribution = Self::fee_distribution();
+      let stakers_share = Self::stakers_fee_share();
+      session_fees_by_currency
+        .iter()
+        .filter_map(|(currency_id, session_fee)| {
+          let currency_id = *currency_id;
+          let mut distributed_fees = DistributedFees {
+            stakers: Self::transfer_fee_share(
+              currency_id,
+              &T::Staking::account_id(),
+              stakers_share * *session_fee,
+            ),
+            ..Default::default()
+          };
+
+          if let Some(shares) = distribution {
+            let treasury_share = shares.treasury * *session_fee;
+            let sunrise_share = shares.sunrise * *session_fee;
+            let remainder = session_fee
+              .saturating_sub(stakers_share * *session_fee)
+              .saturating_sub(treasury_share)
+              .saturating_sub(sunrise_share);
+
+            if currency_id == CurrencyId::Tdfy {
+              distributed_fees.treasury =
+                Self::transfer_fee_share(currency_id, &Self::treasury_account_id(), treasury_share);
+
+              distributed_fees.sunrise =
+                Self::transfer_fee_share(currency_id, &T::Sunrise::account_id(), sunrise_share);
+              if distributed_fees.sunrise > 0 {
+                T::Sunrise::replenish_pools_left_over(distributed_fees.sunrise);
+              }
+
+              // burn the remainder of the session fees
+              let burn_amount = remainder.min(T::CurrencyTidefi::reducible_balance(
+                currency_id,
+                &Self::account_id(),
+                true,
+              ));
+              if burn_amount > 0 {
+                match T::CurrencyTidefi::burn_from(currency_id, &Self::account_id(), burn_amount) {
+                  Ok(burned) => distributed_fees.burned = burned,
+                  Err(err) => log!(
+                    error,
+                    "Unable to burn session fees for {:?}: {:?}",
+                    currency_id,
+                    err
+                  ),
+                }
+              }
+            } else {
+              // the sunrise pools are in TDFY and the wrapped assets can't be burned without
+              // breaking their backing, everything but the stakers share goes to the treasury
+              distributed_fees.treasury = Self::transfer_fee_share(
+                currency_id,
+                &Self::treasury_account_id(),
+                treasury_share
+                  .saturating_add(sunrise_share)
+                  .saturating_add(remainder),
+              );
+            }
+          }
+
+          if distributed_fees == DistributedFees::default() {
+            None
+          } else {
+            Some((currency_id, distributed_fees))
+          }
+        })
+        .collect()
+    }
+
+    // Transfer a share of the fees from the fees account, return the amount transferred
+    fn transfer_fee_share(
+      currency_id: CurrencyId,
+      destination: &T::AccountId,
+      amount: Balance,
+    ) -> Balance {
+      if amount == 0 {
+        return 0;
+      }
+
+      match T::CurrencyTidefi::transfer(currency_id, &Self::account_id(), destination, amount, true)
+      {
+        Ok(_) => amount,
+        Err(err) => {
+          log!(
+            error,
+            "Unable to transfer fees share for {:?} to {:?}: {:?}",
+            currency_id,
+            destination,
+            err
+          );
+          0
+        }
+      }
+    }
+
+    // Initialize new era
+    pub fn start_era() {
+      ActiveEra::<T>::mutate(|active_era| {
+        let new_index = active_era
+          .as_ref()
+          .map(|info| info.index.saturating_add(1))
+          .unwrap_or(0);
+        *active_era = Some(ActiveEraInfo::<T::BlockNumber> {
+          index: new_index,
+          // Set new active era start in next `on_finalize`. To guarantee usage of `Time`
+          start_session_index: None,
+          start_block: None,
+          last_session_block: None,
+          start: None,
+        });
+        new_index
+      });
+    }
+  }
+
+  impl<T: Config> FeesExt<T::AccountId, T::BlockNumber> for Pallet<T> {
+    fn era_blocks_count() -> T::BlockNumber {
+      // measured on the last ended era, or estimated from the sessions until an era ended
+      Self::last_era_blocks_count()
+        .filter(|blocks_count| !blocks_count.is_zero())
+        .unwrap_or_else(|| {
+          Self::measured_session_blocks_count()
+            .saturating_mul(T::BlockNumber::from(T::SessionsPerEra::get() as u32))
+        })
+    }
+
+    fn account_id() -> T::AccountId {
+      T::FeesPalletId::get().into_account_truncating()
+    }
+
+    fn current_era() -> Option<ActiveEraInfo<T::BlockNumber>> {
+      ActiveEra::<T>::get()
+    }
+
+    fn calculate_swap_fees(
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      swap_type: SwapType,
+      is_market_maker: bool,
+    ) -> Fee {
+      Self::calculate_swap_fees_with_rate(
+        currency_id,
+        total_amount_before_fees,
+        Self::base_swap_fee_rate(swap_type, is_market_maker),
+      )
+    }
+
+    fn register_swap_fees(
+      account_id: T::AccountId,
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      swap_type: SwapType,
+      is_market_maker: bool,
+    ) -> Result<Fee, DispatchError> {
+      Self::register_swap_fees_with_rate(
+        account_id,
+        currency_id,
+        total_amount_before_fees,
+        Self::base_swap_fee_rate(swap_type, is_market_maker),
+        true,
+      )
+    }
+  }
+
+  impl<T: Config> SwapFeesExt<T::AccountId> for Pallet<T> {
+    fn base_swap_fee_rate(swap_type: SwapType, is_market_maker: bool) -> Permill {
+      Self::fee_rate(Self::global_swap_fees(), swap_type, is_market_maker)
+    }
+
+    fn swap_fee_rate(
+      account_id: &T::AccountId,
+      currency_from: CurrencyId,
+      currency_to: CurrencyId,
+      swap_type: SwapType,
+      is_market_maker: bool,
+    ) -> Permill {
+      let swap_fees =
+        Self::pair_swap_fees(currency_from, currency_to).unwrap_or_else(Self::global_swap_fees);
+      let fee_rate = Self::fee_rate(swap_fees, swap_type, is_market_maker);
+      let discount = Self::volume_discount(account_id);
+      fee_rate.saturating_sub(Permill::from_parts(discount * fee_rate.deconstruct()))
+    }
+
+    fn swap_fee_rate_weight() -> Weight {
+      let volume_tier_eras = Weight::from(T::VolumeTierEras::get());
+      // `PairSwapFees`, `SwapFees`, `VolumeFeeTiers`, `ActiveEra`, `AccountTradingVolume`,
+      // `PayFeesInTdfy` and `TdfyFeeDiscount`, then the `AccountFees` of each era with the TDFY
+      // value of its fees, and the `AccountTradingVolume` update
+      T::DbWeight::get().reads_writes(volume_tier_eras.saturating_mul(2).saturating_add(7), 1)
+    }
+
+    fn calculate_swap_fees_with_rate(
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      fee_rate: Permill,
+    ) -> Fee {
+      let fee = fee_rate * total_amount_before_fees;
+      Fee {
+        amount: total_amount_before_fees,
+        fee,
+        fee_tdfy: T::Sunrise::try_get_tdfy_value(currency_id, fee).unwrap_or_default(),
+      }
+    }
+
+    fn register_swap_fees_with_rate(
+      account_id: T::AccountId,
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      fee_rate: Permill,
+      sunrise_rewardable: bool,
+    ) -> Result<Fee, DispatchError> {
+      Self::do_register_swap_fees(
+        account_id,
+        currency_id,
+        Self::calculate_swap_fees_with_rate(currency_id, total_amount_before_fees, fee_rate),
+        sunrise_rewardable,
+      )
+    }
+
+    fn swap_fee_in_tdfy(
+      account_id: &T::AccountId,
+      currency_id: CurrencyId,
+      fee: Balance,
+    ) -> Option<Balance> {
+      if currency_id == CurrencyId::Tdfy || !Self::pay_fees_in_tdfy(account_id) {
+        return None;
+      }
+
+      let fee_tdfy = T::Sunrise::try_get_tdfy_value(currency_id, fee).ok()?;
+      if fee_tdfy.is_zero() {
+        // no exchange rate available, the fee is paid in the traded currency
+        return None;
+      }
+
+      Some(fee_tdfy.saturating_sub(Self::tdfy_fee_discount() * fee_tdfy))
+    }
+
+    fn register_swap_fees_in_tdfy(
+      account_id: T::AccountId,
+      currency_id: CurrencyId,
+      total_amount_before_fees: Balance,
+      fee_tdfy: Balance,
+      sunrise_rewardable: bool,
+    ) -> Result<Fee, DispatchError> {
+      Self::do_register_swap_fees(
+        account_id,
+        CurrencyId::Tdfy,
+        Fee {
+          amount: T::Sunrise::try_get_tdfy_value(currency_id, total_amount_before_fees)
+            .unwrap_or_default(),
+          fee: fee_tdfy,
+          fee_tdfy,
+        },
+        sunrise_rewardable,
+      )
+    }
+  }
+
+  impl<T: Config> FeeSessionsInfo<T::BlockNumber> for Pallet<T> {
+    fn session_blocks_count() -> T::BlockNumber {
+      Self::measured_session_blocks_count()
+    }
+
+    fn stakers_fee_share() -> Percent {
+      Self::fee_distribution()
+        .map(|shares| shares.stakers)
+        .unwrap_or_else(T::StakersFeeShare::get)
+    }
   }
 }
@@ -25,7 +25,7 @@ use frame_support::{
     },
     fungibles::{Inspect, InspectHold, Mutate, MutateHold, Transfer},
     tokens::{DepositConsequence, WithdrawConsequence},
-    ConstU128, ConstU32, GenesisBuild,
+    ConstU128, ConstU32, ConstU64, GenesisBuild,
   },
   PalletId,
 };
@@ -37,11 +37,11 @@ use sp_core::H256;
 use sp_runtime::{
   testing::Header,
   traits::{BlakeTwo256, IdentityLookup},
-  DispatchError, DispatchResult, FixedPointNumber, FixedU128, Permill, RuntimeDebug,
+  DispatchError, DispatchResult, FixedPointNumber, FixedU128, Percent, Permill, RuntimeDebug,
 };
 use std::marker::PhantomData;
 use system::EnsureRoot;
-use tidefi_primitives::{assets, BlockNumber, CurrencyId, SessionIndex, SunriseSwapPool};
+use tidefi_primitives::{assets, BlockNumber, CurrencyId, EraIndex, SessionIndex, SunriseSwapPool};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -88,7 +88,7 @@ frame_support::construct_runtime!(
     Balances: pallet_balances::{Pallet, Call, Config<T>, Storage, Event<T>},
     Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
     AssetRegistry: pallet_asset_registry::{Pallet, Call, Config<T>, Storage, Event<T>},
-    Fees: pallet_fees::{Pallet, Config<T>, Storage, Event<T>},
+    Fees: pallet_fees::{Pallet, Call, Config<T>, Storage, Event<T>},
     Sunrise: pallet_sunrise::{Pallet, Config<T>, Storage, Event<T>},
     Security: pallet_security::{Pallet, Call, Config, Storage, Event<T>},
     TidefiStaking: pallet_tidefi_stake::{Pallet, Call, Config<T>, Storage, Event<T>},
@@ -181,6 +181,7 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
   pub const SunrisePalletId: PalletId = PalletId(*b"sunr*pal");
+  pub const TreasuryPalletId: PalletId = PalletId(*b"trsy*pal");
   pub const TidefiPalletId: PalletId = PalletId(*b"wrpr*pal");
   pub const QuorumPalletId: PalletId = PalletId(*b"qurm*pal");
   pub const AssetRegistryPalletId: PalletId = PalletId(*b"asst*pal");
@@ -190,6 +191,10 @@ parameter_types! {
   pub const SessionsArchive: SessionIndex = 2;
   pub const BlocksPerSession: BlockNumber = 50;
   pub const BlocksForceUnstake: BlockNumber = 10;
+  pub const CancelUnstakeFeeRefund: Percent = Percent::from_percent(50);
+  pub const UnstakeFeeDestination: Option<AccountId> = None;
+  pub const StakersFeeShare: Percent = Percent::from_percent(5);
+  pub const RewardsProjectionSessions: SessionIndex = 4;
   pub const StakeAccountCap: u32 = 10;
   pub const UnstakeQueueCap: u32 = 100;
   // 20 basis point
@@ -197,6 +202,10 @@ parameter_types! {
   // 10 basis point
   pub const MarketMakerFeeAmount: Permill = Permill::from_perthousand(10);
   pub const MarketMakerLimitFeeAmount: Permill = Permill::from_perthousand(10);
+  pub const VolumeTierEras: EraIndex = 30;
+  pub const EraArchive: EraIndex = 30;
+  pub static UseSessionManager: bool = false;
+  pub static ValidatorEra: Option<EraIndex> = None;
   // Maximum number of staking period the chain can support
   pub const StakingRewardCap: u32 = 10;
   pub const Cooldown: BlockNumber = 1_296_000; // 90 DAYS
@@ -214,21 +223,40 @@ impl pallet_sunrise::Config for Test {
   type Cooldown = Cooldown;
   type MaximumRewardPerSwap = MaximumRewardPerSwap;
   type LeftoverSwapRebates = LeftoverSwapRebates;
+  type MaximumRewardPerAccountPerEra = ConstU128<{ u128::MAX }>;
+  type MinimumHoldingPeriod = ConstU64<0>;
+  type LinkedAccounts = ();
+  type MaximumOnboardingRefundPerAccount = ConstU128<{ u128::MAX }>;
+  type CurrentEra = ();
+  type RewardsClaimWindow = ConstU32<4_320>;
+  type RewardsExpiryNotice = ConstU32<168>;
+  type ExpiredRewardsDestination = ();
+  type RewardsVesting = ();
+  type Vesting = ();
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
+  type WeightInfo = pallet_sunrise::weights::SubstrateWeight<Test>;
 }
 
 impl pallet_fees::Config for Test {
   type Event = Event;
   type Security = Security;
   type FeesPalletId = TidefiPalletId;
+  type TreasuryPalletId = TreasuryPalletId;
   type CurrencyTidefi = Adapter<AccountId>;
   type ForceOrigin = EnsureRoot<Self::AccountId>;
+  type WeightInfo = crate::weights::SubstrateWeight<Test>;
   type UnixTime = Timestamp;
   type SessionsPerEra = SessionsPerEra;
   type SessionsArchive = SessionsArchive;
   type BlocksPerSession = BlocksPerSession;
+  type UseSessionManager = UseSessionManager;
+  type ValidatorEra = ValidatorEra;
   type FeeAmount = FeeAmount;
   type MarketMakerFeeAmount = MarketMakerFeeAmount;
   type MarketMakerLimitFeeAmount = MarketMakerLimitFeeAmount;
+  type VolumeTierEras = VolumeTierEras;
+  type EraArchive = EraArchive;
+  type StakersFeeShare = StakersFeeShare;
   type Staking = TidefiStaking;
   type Sunrise = Sunrise;
 }
@@ -241,20 +269,32 @@ impl pallet_tidefi_stake::Config for Test {
   type StakeAccountCap = StakeAccountCap;
   type UnstakeQueueCap = UnstakeQueueCap;
   type BlocksForceUnstake = BlocksForceUnstake;
+  type CancelUnstakeFeeRefund = CancelUnstakeFeeRefund;
+  type UnstakeFeeDestination = UnstakeFeeDestination;
+  type RewardsProjectionSessions = RewardsProjectionSessions;
   type AssetRegistry = AssetRegistry;
   type StakingRewardCap = StakingRewardCap;
   type Security = Security;
+  type Sunrise = Sunrise;
+  type Fees = Fees;
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
 }
 
 impl pallet_asset_registry::Config for Test {
   type Event = Event;
   type WeightInfo = pallet_asset_registry::weights::SubstrateWeight<Test>;
   type AssetRegistryPalletId = AssetRegistryPalletId;
+  type StringLimit = ConstU32<255>;
   type CurrencyTidefi = Adapter<AccountId>;
 }
 
 impl pallet_security::Config for Test {
   type Event = Event;
+  type PauseOrigin = EnsureRoot<Self::AccountId>;
+  type EmergencyOrigin = system::EnsureSigned<Self::AccountId>;
+  type EmergencyInitiator = Self::AccountId;
+  type EmergencyPauseDuration = ConstU64<100>;
+  type PauseReasonLimit = ConstU32<256>;
   type WeightInfo = pallet_security::weights::SubstrateWeight<Test>;
 }
 
@@ -0,0 +1,56 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::traits::Get;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// `pallet_session::SessionManager` driving the fees sessions and eras from `pallet_session`,
+/// when `UseSessionManager` is enabled.
+///
+/// Wraps the `SessionManager` of the runtime, `I`, so the fees sessions end with the validators
+/// sessions, and a new fees era starts when the validators era (`ValidatorEra`) changes. The fees
+/// sessions and eras keep their own increasing index.
+pub struct FeesSessionManager<T, I>(PhantomData<(T, I)>);
+
+impl<T, I, ValidatorId> pallet_session::SessionManager<ValidatorId> for FeesSessionManager<T, I>
+where
+  T: Config,
+  I: pallet_session::SessionManager<ValidatorId>,
+{
+  fn new_session(new_index: pallet_session::SessionIndex) -> Option<Vec<ValidatorId>> {
+    I::new_session(new_index)
+  }
+
+  fn new_session_genesis(new_index: pallet_session::SessionIndex) -> Option<Vec<ValidatorId>> {
+    I::new_session_genesis(new_index)
+  }
+
+  fn end_session(end_index: pallet_session::SessionIndex) {
+    I::end_session(end_index);
+    if T::UseSessionManager::get() {
+      Pallet::<T>::on_session_manager_end(end_index.into());
+    }
+  }
+
+  fn start_session(start_index: pallet_session::SessionIndex) {
+    // the validators era is updated by the inner session manager
+    I::start_session(start_index);
+    if T::UseSessionManager::get() {
+      Pallet::<T>::on_session_manager_start(start_index.into());
+    }
+  }
+}
@@ -14,13 +14,29 @@
 // You should have received a copy of the GNU General Public License
 // along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::mock::{new_test_ext, AccountId, Fees, Sunrise};
-use frame_support::assert_ok;
-use sp_runtime::{FixedPointNumber, FixedU128};
+use crate::{
+  mock::{
+    new_test_ext, AccountId, Adapter, Assets, BlocksPerSession, FeeAmount, Fees, Origin,
+    SessionsArchive, StakersFeeShare, Sunrise, Test, TidefiStaking, TDFY,
+  },
+  AccountFees, AccountTradingVolume, ActiveEra, CurrentSession, Error, FeeDistributionShares,
+  SessionTotalFees, StoredSessions, SwapFeeSchedule, SwapFeesExt, VolumeFeeTier,
+  MAX_DRAINED_SESSIONS,
+};
+use frame_support::{
+  assert_noop, assert_ok,
+  traits::{
+    fungibles::{Inspect, Mutate},
+    Hooks,
+  },
+  BoundedVec,
+};
+use pallet_security::CurrentBlockCount;
+use sp_runtime::{traits::BadOrigin, FixedPointNumber, FixedU128, Percent, Permill};
 use tidefi_primitives::{
   assets::Asset,
-  pallet::{FeesExt, SunriseExt},
-  CurrencyId, SwapType,
+  pallet::{FeesExt, StakingExt, SunriseExt},
+  Balance, CurrencyId, EraIndex, Fee, SwapType,
 };
 
 #[test]
@@ -253,3 +269,838 @@ pub fn test_maximum_fee_values() {
     assert_eq!(reward, 12_500_000_000_000_000);
   });
 }
+
+// Mint the session fees into the fees account and run the first session until its end
+fn end_first_session_with_fees(session_fees: Balance) {
+  end_first_session_with_currency_fees(CurrencyId::Tdfy, session_fees)
+}
+
+fn end_first_session_with_currency_fees(currency_id: CurrencyId, session_fees: Balance) {
+  assert_ok!(Adapter::mint_into(
+    currency_id,
+    &Fees::account_id(),
+    session_fees
+  ));
+  SessionTotalFees::<Test>::insert(
+    1,
+    currency_id,
+    Fee {
+      amount: 100 * session_fees,
+      fee: session_fees,
+      fee_tdfy: session_fees,
+    },
+  );
+
+  // start the era
+  CurrentBlockCount::<Test>::put(1);
+  Fees::on_finalize(1);
+
+  // end the session
+  let session_end_block = 1 + BlocksPerSession::get();
+  CurrentBlockCount::<Test>::put(session_end_block);
+  Fees::on_initialize(session_end_block);
+}
+
+#[test]
+pub fn should_transfer_staking_rewards_on_session_end() {
+  new_test_ext().execute_with(|| {
+    const SESSION_FEES: Balance = 100 * TDFY;
+    let staking_account_id = <TidefiStaking as StakingExt<AccountId>>::account_id();
+
+    end_first_session_with_fees(SESSION_FEES);
+
+    let staking_rewards = StakersFeeShare::get() * SESSION_FEES;
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &staking_account_id),
+      staking_rewards
+    );
+    assert_eq!(Fees::current_session(), 2);
+    assert_eq!(
+      TidefiStaking::session_total_fees(1, CurrencyId::Tdfy),
+      SESSION_FEES
+    );
+    assert_eq!(
+      TidefiStaking::unallocated_rewards(CurrencyId::Tdfy),
+      staking_rewards
+    );
+    // nothing is burned without a distribution table
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &Fees::account_id()),
+      Adapter::minimum_balance(CurrencyId::Tdfy) + SESSION_FEES - staking_rewards
+    );
+  });
+}
+
+mod set_fee_distribution {
+  use super::*;
+
+  const DISTRIBUTION: FeeDistributionShares = FeeDistributionShares {
+    stakers: Percent::from_percent(40),
+    treasury: Percent::from_percent(30),
+    sunrise: Percent::from_percent(20),
+  };
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Fees::set_fee_distribution(
+        Origin::root(),
+        Some(DISTRIBUTION)
+      ));
+      assert_eq!(Fees::fee_distribution(), Some(DISTRIBUTION));
+
+      assert_ok!(Fees::set_fee_distribution(Origin::root(), None));
+      assert_eq!(Fees::fee_distribution(), None);
+    });
+  }
+
+  #[test]
+  pub fn distributes_session_fees() {
+    new_test_ext().execute_with(|| {
+      const SESSION_FEES: Balance = 100 * TDFY;
+      let staking_account_id = <TidefiStaking as StakingExt<AccountId>>::account_id();
+      let sunrise_account_id = <Sunrise as SunriseExt<AccountId, u64>>::account_id();
+      let sunrise_initial_balance = Adapter::balance(CurrencyId::Tdfy, &sunrise_account_id);
+      let pools_left_over = Sunrise::pools_left_over();
+
+      assert_ok!(Fees::set_fee_distribution(
+        Origin::root(),
+        Some(DISTRIBUTION)
+      ));
+      let total_issuance = Adapter::total_issuance(CurrencyId::Tdfy) + SESSION_FEES;
+
+      end_first_session_with_fees(SESSION_FEES);
+
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &staking_account_id),
+        40 * TDFY
+      );
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &Fees::treasury_account_id()),
+        30 * TDFY
+      );
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &sunrise_account_id),
+        sunrise_initial_balance + 20 * TDFY
+      );
+      assert_eq!(Sunrise::pools_left_over(), pools_left_over + 20 * TDFY);
+
+      // the remainder has been burned
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &Fees::account_id()),
+        Adapter::minimum_balance(CurrencyId::Tdfy)
+      );
+      assert_eq!(
+        Adapter::total_issuance(CurrencyId::Tdfy),
+        total_issuance - 10 * TDFY
+      );
+    });
+  }
+
+  #[test]
+  pub fn transfers_wrapped_session_fees_to_treasury() {
+    new_test_ext().execute_with(|| {
+      const WRAPPED_CURRENCY_ID: CurrencyId = CurrencyId::Wrapped(4);
+      const SESSION_FEES: Balance = 100 * TDFY;
+      let staking_account_id = <TidefiStaking as StakingExt<AccountId>>::account_id();
+      let sunrise_account_id = <Sunrise as SunriseExt<AccountId, u64>>::account_id();
+      let pools_left_over = Sunrise::pools_left_over();
+
+      assert_ok!(Assets::force_create(
+        Origin::root(),
+        4,
+        Fees::account_id(),
+        true,
+        1
+      ));
+      // keep the fees account alive
+      assert_ok!(Adapter::mint_into(
+        WRAPPED_CURRENCY_ID,
+        &Fees::account_id(),
+        1
+      ));
+      assert_ok!(Fees::set_fee_distribution(
+        Origin::root(),
+        Some(DISTRIBUTION)
+      ));
+      let total_issuance = Adapter::total_issuance(WRAPPED_CURRENCY_ID) + SESSION_FEES;
+
+      end_first_session_with_currency_fees(WRAPPED_CURRENCY_ID, SESSION_FEES);
+
+      assert_eq!(
+        Adapter::balance(WRAPPED_CURRENCY_ID, &staking_account_id),
+        40 * TDFY
+      );
+      // the sunrise share and the remainder are transferred to the treasury
+      assert_eq!(
+        Adapter::balance(WRAPPED_CURRENCY_ID, &Fees::treasury_account_id()),
+        60 * TDFY
+      );
+      assert_eq!(
+        Adapter::balance(WRAPPED_CURRENCY_ID, &sunrise_account_id),
+        0
+      );
+      assert_eq!(Sunrise::pools_left_over(), pools_left_over);
+
+      // nothing has been burned
+      assert_eq!(
+        Adapter::balance(WRAPPED_CURRENCY_ID, &Fees::account_id()),
+        1
+      );
+      assert_eq!(Adapter::total_issuance(WRAPPED_CURRENCY_ID), total_issuance);
+    });
+  }
+
+  mod fails_when {
+    use super::*;
+
+    #[test]
+    pub fn origin_is_not_force_origin() {
+      new_test_ext().execute_with(|| {
+        assert_noop!(
+          Fees::set_fee_distribution(Origin::signed(1u64.into()), Some(DISTRIBUTION)),
+          BadOrigin
+        );
+      });
+    }
+
+    #[test]
+    pub fn shares_exceed_one_hundred_percent() {
+      new_test_ext().execute_with(|| {
+        assert_noop!(
+          Fees::set_fee_distribution(
+            Origin::root(),
+            Some(FeeDistributionShares {
+              stakers: Percent::from_percent(50),
+              treasury: Percent::from_percent(30),
+              sunrise: Percent::from_percent(21),
+            })
+          ),
+          Error::<Test>::InvalidFeeDistribution
+        );
+      });
+    }
+  }
+}
+
+const SWAP_FEES: SwapFeeSchedule = SwapFeeSchedule {
+  fee: Permill::from_parts(3_000),
+  market_maker_fee: Permill::from_parts(2_000),
+  market_maker_limit_fee: Permill::from_parts(1_000),
+};
+
+mod set_swap_fees {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Fees::set_swap_fees(Origin::root(), SWAP_FEES));
+      assert_eq!(Fees::swap_fees(), Some(SWAP_FEES));
+
+      // 100 TDFY @ 0.3% should cost 0.3 TDFY
+      let calculated_fee =
+        Fees::calculate_swap_fees(CurrencyId::Tdfy, 100 * TDFY, SwapType::Market, false);
+      assert_eq!(calculated_fee.fee, 300_000_000_000);
+
+      // 100 TDFY @ 0.1% should cost 0.1 TDFY
+      let calculated_fee =
+        Fees::calculate_swap_fees(CurrencyId::Tdfy, 100 * TDFY, SwapType::Limit, true);
+      assert_eq!(calculated_fee.fee, 100_000_000_000);
+    });
+  }
+
+  #[test]
+  pub fn fails_when_origin_is_not_force_origin() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Fees::set_swap_fees(Origin::signed(1u64.into()), SWAP_FEES),
+        BadOrigin
+      );
+    });
+  }
+}
+
+mod set_pair_swap_fees {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      let account_id: AccountId = 1u64.into();
+      assert_ok!(Fees::set_pair_swap_fees(
+        Origin::root(),
+        CurrencyId::Tdfy,
+        CurrencyId::Wrapped(4),
+        Some(SWAP_FEES)
+      ));
+
+      // the pair fee schedule is applied in both directions
+      assert_eq!(
+        Fees::swap_fee_rate(
+          &account_id,
+          CurrencyId::Tdfy,
+          CurrencyId::Wrapped(4),
+          SwapType::Market,
+          false
+        ),
+        SWAP_FEES.fee
+      );
+      assert_eq!(
+        Fees::swap_fee_rate(
+          &account_id,
+          CurrencyId::Wrapped(4),
+          CurrencyId::Tdfy,
+          SwapType::Market,
+          true
+        ),
+        SWAP_FEES.market_maker_fee
+      );
+      // other pairs use the global fee schedule
+      assert_eq!(
+        Fees::swap_fee_rate(
+          &account_id,
+          CurrencyId::Tdfy,
+          CurrencyId::Wrapped(2),
+          SwapType::Market,
+          false
+        ),
+        FeeAmount::get()
+      );
+
+      assert_ok!(Fees::set_pair_swap_fees(
+        Origin::root(),
+        CurrencyId::Wrapped(4),
+        CurrencyId::Tdfy,
+        None
+      ));
+      assert_eq!(
+        Fees::pair_swap_fees(CurrencyId::Tdfy, CurrencyId::Wrapped(4)),
+        None
+      );
+      assert_eq!(
+        Fees::pair_swap_fees(CurrencyId::Wrapped(4), CurrencyId::Tdfy),
+        None
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_when_currencies_are_the_same() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Fees::set_pair_swap_fees(
+          Origin::root(),
+          CurrencyId::Tdfy,
+          CurrencyId::Tdfy,
+          Some(SWAP_FEES)
+        ),
+        Error::<Test>::InvalidPair
+      );
+    });
+  }
+}
+
+mod set_volume_fee_tiers {
+  use super::*;
+
+  fn volume_fee_tiers() -> Vec<VolumeFeeTier> {
+    vec![
+      VolumeFeeTier {
+        minimum_volume: 500 * TDFY,
+        discount: Percent::from_percent(10),
+      },
+      VolumeFeeTier {
+        minimum_volume: 2_000 * TDFY,
+        discount: Percent::from_percent(20),
+      },
+    ]
+  }
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      let account_id: AccountId = 1u64.into();
+      let current_era = Fees::current_era().unwrap().index;
+
+      // 1_000 TDFY traded during the previous era
+      AccountFees::<Test>::insert(
+        current_era - 1,
+        account_id,
+        BoundedVec::try_from(vec![(
+          CurrencyId::Tdfy,
+          Fee {
+            amount: 1_000 * TDFY,
+            fee: 20 * TDFY,
+            fee_tdfy: 20 * TDFY,
+          },
+        )])
+        .unwrap(),
+      );
+
+      assert_eq!(
+        Fees::swap_fee_rate(
+          &account_id,
+          CurrencyId::Tdfy,
+          CurrencyId::Wrapped(4),
+          SwapType::Market,
+          false
+        ),
+        FeeAmount::get()
+      );
+
+      assert_ok!(Fees::set_volume_fee_tiers(
+        Origin::root(),
+        volume_fee_tiers()
+      ));
+
+      // 2% with a 10% discount
+      assert_eq!(
+        Fees::swap_fee_rate(
+          &account_id,
+          CurrencyId::Tdfy,
+          CurrencyId::Wrapped(4),
+          SwapType::Market,
+          false
+        ),
+        Permill::from_parts(18_000)
+      );
+      assert_eq!(
+        AccountTradingVolume::<Test>::get(account_id),
+        Some((current_era, 1_000 * TDFY))
+      );
+    });
+  }
+
+  mod fails_when {
+    use super::*;
+
+    #[test]
+    pub fn tiers_are_not_sorted() {
+      new_test_ext().execute_with(|| {
+        let mut volume_fee_tiers = volume_fee_tiers();
+        volume_fee_tiers.reverse();
+        assert_noop!(
+          Fees::set_volume_fee_tiers(Origin::root(), volume_fee_tiers),
+          Error::<Test>::InvalidVolumeFeeTiers
+        );
+      });
+    }
+
+    #[test]
+    pub fn there_is_too_many_tiers() {
+      new_test_ext().execute_with(|| {
+        let volume_fee_tiers = (1..=11_u128)
+          .map(|tier| VolumeFeeTier {
+            minimum_volume: tier * TDFY,
+            discount: Percent::from_percent(tier as u8),
+          })
+          .collect();
+        assert_noop!(
+          Fees::set_volume_fee_tiers(Origin::root(), volume_fee_tiers),
+          Error::<Test>::TooManyVolumeFeeTiers
+        );
+      });
+    }
+  }
+}
+
+mod set_pay_fees_in_tdfy {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      let account_id: AccountId = 1u64.into();
+      assert!(!Fees::pay_fees_in_tdfy(account_id));
+
+      assert_ok!(Fees::set_pay_fees_in_tdfy(Origin::signed(account_id), true));
+      assert!(Fees::pay_fees_in_tdfy(account_id));
+
+      assert_ok!(Fees::set_pay_fees_in_tdfy(
+        Origin::signed(account_id),
+        false
+      ));
+      assert!(!Fees::pay_fees_in_tdfy(account_id));
+    });
+  }
+
+  #[test]
+  pub fn calculates_discounted_fee_in_tdfy() {
+    new_test_ext().execute_with(|| {
+      let account_id: AccountId = 1u64.into();
+      // 2 TDFY / USDT
+      assert_ok!(Sunrise::register_exchange_rate(vec![(
+        4,
+        2_000_000_000_000
+      )]));
+      assert_ok!(Fees::set_tdfy_fee_discount(
+        Origin::root(),
+        Percent::from_percent(25)
+      ));
+
+      // 100 USDT @ 2% should cost 2 USDT
+      let fee =
+        Fees::calculate_swap_fees(CurrencyId::Wrapped(4), 100_000_000, SwapType::Limit, false);
+      assert_eq!(fee.fee, 2_000_000);
+
+      // not opted in
+      assert_eq!(
+        Fees::swap_fee_in_tdfy(&account_id, CurrencyId::Wrapped(4), fee.fee),
+        None
+      );
+
+      assert_ok!(Fees::set_pay_fees_in_tdfy(Origin::signed(account_id), true));
+
+      // 2 USDT = 4 TDFY, with a 25% discount
+      assert_eq!(
+        Fees::swap_fee_in_tdfy(&account_id, CurrencyId::Wrapped(4), fee.fee),
+        Some(3 * TDFY)
+      );
+
+      // fees in TDFY are already paid in TDFY
+      assert_eq!(
+        Fees::swap_fee_in_tdfy(&account_id, CurrencyId::Tdfy, TDFY),
+        None
+      );
+    });
+  }
+}
+
+mod set_tdfy_fee_discount {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Fees::set_tdfy_fee_discount(
+        Origin::root(),
+        Percent::from_percent(25)
+      ));
+      assert_eq!(Fees::tdfy_fee_discount(), Percent::from_percent(25));
+    });
+  }
+
+  #[test]
+  pub fn fails_when_origin_is_not_force_origin() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Fees::set_tdfy_fee_discount(Origin::signed(1u64.into()), Percent::from_percent(25)),
+        BadOrigin
+      );
+    });
+  }
+}
+
+mod prune_old_eras {
+  use super::*;
+
+  fn register_fees_in_two_eras() -> EraIndex {
+    let first_era = Fees::current_era().unwrap().index;
+    assert_ok!(Fees::register_swap_fees(
+      3u64.into(),
+      CurrencyId::Tdfy,
+      100 * TDFY,
+      SwapType::Limit,
+      false,
+    ));
+    Fees::start_era();
+    assert_ok!(Fees::register_swap_fees(
+      3u64.into(),
+      CurrencyId::Tdfy,
+      100 * TDFY,
+      SwapType::Limit,
+      false,
+    ));
+
+    // the first era is now out of the retention
+    ActiveEra::<Test>::mutate(|active_era| {
+      active_era.as_mut().unwrap().index = first_era + 1 + Fees::retained_eras();
+    });
+
+    first_era
+  }
+
+  #[test]
+  pub fn retains_eras_until_sunrise_cooldown() {
+    new_test_ext().execute_with(|| {
+      // 1_296_000 blocks cooldown with 500 blocks eras
+      assert_eq!(Fees::retained_eras(), 2_593);
+    });
+  }
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      let first_era = register_fees_in_two_eras();
+
+      Fees::on_idle(0, 1_000);
+
+      assert_eq!(Fees::oldest_stored_era(), first_era + 1);
+      assert!(Fees::account_fees(first_era, AccountId(3u64)).is_empty());
+      assert_eq!(Fees::era_total_fees(first_era, CurrencyId::Tdfy).fee, 0);
+      assert_eq!(
+        Fees::account_fees(first_era + 1, AccountId(3u64))
+          .first()
+          .unwrap()
+          .1
+          .fee,
+        2 * TDFY
+      );
+      assert_eq!(
+        Fees::era_total_fees(first_era + 1, CurrencyId::Tdfy).fee,
+        2 * TDFY
+      );
+    });
+  }
+
+  #[test]
+  pub fn stops_when_weight_is_exhausted() {
+    new_test_ext().execute_with(|| {
+      let first_era = register_fees_in_two_eras();
+
+      Fees::on_idle(0, 0);
+
+      assert_eq!(Fees::oldest_stored_era(), first_era);
+      assert!(!Fees::account_fees(first_era, AccountId(3u64)).is_empty());
+    });
+  }
+}
+
+mod session_end {
+  use super::*;
+
+  #[test]
+  pub fn succeeds_in_on_initialize() {
+    new_test_ext().execute_with(|| {
+      // start the era
+      CurrentBlockCount::<Test>::put(1);
+      Fees::on_finalize(1);
+      assert_eq!(Fees::current_session(), 1);
+
+      let session_end_block = 1 + BlocksPerSession::get();
+      CurrentBlockCount::<Test>::put(session_end_block - 1);
+      Fees::on_initialize(session_end_block - 1);
+      assert_eq!(Fees::current_session(), 1);
+
+      CurrentBlockCount::<Test>::put(session_end_block);
+      Fees::on_initialize(session_end_block);
+      assert_eq!(Fees::current_session(), 2);
+      assert!(Fees::stored_sessions(1).is_some());
+
+      // on_finalize doesn't end the session again
+      Fees::on_finalize(session_end_block);
+      assert_eq!(Fees::current_session(), 2);
+    });
+  }
+
+  #[test]
+  pub fn drains_expired_session() {
+    new_test_ext().execute_with(|| {
+      let expired_session = 1;
+      CurrentSession::<Test>::put(expired_session + 1 + SessionsArchive::get());
+      for session in [expired_session, expired_session + 1] {
+        StoredSessions::<Test>::insert(session, ());
+        SessionTotalFees::<Test>::insert(
+          session,
+          CurrencyId::Tdfy,
+          Fee {
+            amount: 100 * TDFY,
+            fee: TDFY,
+            fee_tdfy: TDFY,
+          },
+        );
+      }
+
+      Fees::drain_old_sessions();
+
+      assert!(Fees::stored_sessions(expired_session).is_none());
+      assert_eq!(
+        Fees::session_total_fees(expired_session, CurrencyId::Tdfy).fee,
+        0
+      );
+      assert!(Fees::stored_sessions(expired_session + 1).is_some());
+      assert_eq!(
+        Fees::session_total_fees(expired_session + 1, CurrencyId::Tdfy).fee,
+        TDFY
+      );
+    });
+  }
+
+  #[test]
+  pub fn drains_skipped_sessions_in_bounded_batches() {
+    new_test_ext().execute_with(|| {
+      let skipped_session = MAX_DRAINED_SESSIONS + 5;
+      // the session index jumped
+      CurrentSession::<Test>::put(3 * MAX_DRAINED_SESSIONS + SessionsArchive::get());
+      for session in [1, skipped_session] {
+        StoredSessions::<Test>::insert(session, ());
+        SessionTotalFees::<Test>::insert(
+          session,
+          CurrencyId::Tdfy,
+          Fee {
+            amount: 100 * TDFY,
+            fee: TDFY,
+            fee_tdfy: TDFY,
+          },
+        );
+      }
+
+      Fees::drain_old_sessions();
+      assert!(Fees::stored_sessions(1).is_none());
+      assert!(Fees::stored_sessions(skipped_session).is_some());
+      assert_eq!(Fees::oldest_stored_session(), MAX_DRAINED_SESSIONS);
+
+      Fees::drain_old_sessions();
+      assert!(Fees::stored_sessions(skipped_session).is_none());
+      assert_eq!(
+        Fees::session_total_fees(skipped_session, CurrencyId::Tdfy).fee,
+        0
+      );
+      assert_eq!(Fees::oldest_stored_session(), 2 * MAX_DRAINED_SESSIONS);
+
+      Fees::drain_old_sessions();
+      Fees::drain_old_sessions();
+      assert_eq!(Fees::oldest_stored_session(), 3 * MAX_DRAINED_SESSIONS);
+    });
+  }
+}
+
+mod session_manager {
+  use super::*;
+  use crate::{
+    mock::{UseSessionManager, ValidatorEra},
+    FeesSessionManager,
+  };
+  use pallet_session::SessionManager;
+  use pallet_tidefi_stake::FeeSessionsInfo;
+
+  type FeesSessions = FeesSessionManager<Test, ()>;
+
+  #[test]
+  pub fn drives_sessions_and_eras() {
+    new_test_ext().execute_with(|| {
+      UseSessionManager::set(true);
+      ValidatorEra::set(Some(5));
+
+      <FeesSessions as SessionManager<AccountId>>::start_session(3);
+      // the fees sessions and eras keep their own index
+      assert_eq!(Fees::current_session(), 1);
+      let active_era = Fees::current_era().unwrap();
+      assert_eq!(active_era.index, 1);
+      assert_eq!(active_era.start_session_index, Some(1));
+      let start_block = active_era.start_block.unwrap();
+
+      SessionTotalFees::<Test>::insert(
+        1,
+        CurrencyId::Tdfy,
+        Fee {
+          amount: 100 * TDFY,
+          fee: TDFY,
+          fee_tdfy: TDFY,
+        },
+      );
+      CurrentBlockCount::<Test>::put(start_block + 10);
+      <FeesSessions as SessionManager<AccountId>>::end_session(3);
+      assert!(Fees::stored_sessions(1).is_some());
+      assert_eq!(Fees::current_session(), 2);
+      assert_eq!(<Fees as FeeSessionsInfo<u64>>::session_blocks_count(), 10);
+
+      // same validators era
+      <FeesSessions as SessionManager<AccountId>>::start_session(4);
+      assert_eq!(Fees::current_session(), 2);
+      assert_eq!(Fees::current_era().unwrap().index, 1);
+      assert_eq!(Fees::current_era().unwrap().start_session_index, Some(1));
+
+      // new validators era
+      ValidatorEra::set(Some(6));
+      CurrentBlockCount::<Test>::put(start_block + 25);
+      <FeesSessions as SessionManager<AccountId>>::end_session(4);
+      <FeesSessions as SessionManager<AccountId>>::start_session(5);
+      let active_era = Fees::current_era().unwrap();
+      assert_eq!(active_era.index, 2);
+      assert_eq!(active_era.start_session_index, Some(3));
+      assert_eq!(<Fees as FeeSessionsInfo<u64>>::session_blocks_count(), 15);
+      assert_eq!(Fees::era_blocks_count(), 25);
+
+      // the fees era index never goes back
+      ValidatorEra::set(Some(2));
+      <FeesSessions as SessionManager<AccountId>>::end_session(5);
+      <FeesSessions as SessionManager<AccountId>>::start_session(6);
+      assert_eq!(Fees::current_era().unwrap().index, 3);
+    });
+  }
+
+  #[test]
+  pub fn ignores_block_count() {
+    new_test_ext().execute_with(|| {
+      UseSessionManager::set(true);
+      <FeesSessions as SessionManager<AccountId>>::start_session(1);
+
+      let session_end_block =
+        Fees::current_era().unwrap().start_block.unwrap() + BlocksPerSession::get();
+      CurrentBlockCount::<Test>::put(session_end_block);
+      assert_eq!(Fees::on_initialize(session_end_block), 0);
+      Fees::on_finalize(session_end_block);
+      assert_eq!(Fees::current_session(), 1);
+    });
+  }
+
+  #[test]
+  pub fn is_disabled_by_default() {
+    new_test_ext().execute_with(|| {
+      <FeesSessions as SessionManager<AccountId>>::start_session(3);
+      <FeesSessions as SessionManager<AccountId>>::end_session(3);
+      assert_eq!(Fees::current_session(), 1);
+      assert!(Fees::stored_sessions(3).is_none());
+    });
+  }
+}
+
+mod get_fees {
+  use super::*;
+  use crate::MAX_ACCOUNT_FEES_ERAS;
+
+  #[test]
+  pub fn returns_era_and_account_fees() {
+    new_test_ext().execute_with(|| {
+      let current_era = Fees::current_era().unwrap().index;
+      assert_ok!(Fees::register_swap_fees(
+        3u64.into(),
+        CurrencyId::Tdfy,
+        100 * TDFY,
+        SwapType::Limit,
+        false,
+      ));
+
+      let era_fees = Fees::get_era_fees(current_era);
+      assert_eq!(era_fees.len(), 1);
+      assert_eq!(era_fees.first().unwrap().0, CurrencyId::Tdfy);
+      assert_eq!(era_fees.first().unwrap().1.fee, 2 * TDFY);
+
+      let account_fees =
+        Fees::get_account_fees(&3u64.into(), current_era.saturating_sub(1), current_era + 1)
+          .unwrap();
+      assert_eq!(account_fees.len(), 1);
+      assert_eq!(account_fees.first().unwrap().0, current_era);
+      assert_eq!(
+        account_fees.first().unwrap().1.first().unwrap().1.fee,
+        2 * TDFY
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_with_invalid_era_range() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Fees::get_account_fees(&3u64.into(), 2, 1),
+        Error::<Test>::InvalidEraRange
+      );
+      assert_noop!(
+        Fees::get_account_fees(&3u64.into(), 0, MAX_ACCOUNT_FEES_ERAS),
+        Error::<Test>::InvalidEraRange
+      );
+    });
+  }
+}
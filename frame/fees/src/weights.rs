@@ -0,0 +1,61 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for `pallet_fees`.
+pub trait WeightInfo {
+   fn set_fee_distribution() -> Weight;
+   fn set_swap_fees() -> Weight;
+   fn set_pair_swap_fees() -> Weight;
+   fn set_volume_fee_tiers() -> Weight;
+   fn set_pay_fees_in_tdfy() -> Weight;
+   fn set_tdfy_fee_discount() -> Weight;
+}
+
+/// Weights for `pallet_fees` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+   fn set_fee_distribution() -> Weight {
+      17_000_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn set_swap_fees() -> Weight {
+      17_000_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn set_pair_swap_fees() -> Weight {
+      20_000_000_u64
+          .saturating_add(T::DbWeight::get().writes(2_u64))
+  }
+   fn set_volume_fee_tiers() -> Weight {
+      19_000_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn set_pay_fees_in_tdfy() -> Weight {
+      18_000_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn set_tdfy_fee_discount() -> Weight {
+      16_000_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+}
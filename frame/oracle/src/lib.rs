@@ -41,6 +41,9 @@ pub mod pallet {
     PalletId,
   };
   use frame_system::pallet_prelude::*;
+  use pallet_fees::SwapFeesExt;
+  use pallet_security::ExtrinsicIdExt;
+  use pallet_sunrise::SunriseSwapRewards;
   #[cfg(feature = "std")]
   use sp_runtime::traits::AccountIdConversion;
   use sp_runtime::{
@@ -51,7 +54,7 @@ pub mod pallet {
   use tidefi_primitives::{
     assets::Asset,
     pallet::{FeesExt, OracleExt, SecurityExt, SunriseExt},
-    AssetId, Balance, CurrencyId, Hash, Swap, SwapConfirmation, SwapStatus, SwapType,
+    AssetId, Balance, CurrencyId, Fee, Hash, Swap, SwapConfirmation, SwapStatus, SwapType,
   };
 
   /// Oracle configuration
@@ -70,17 +73,18 @@ pub mod pallet {
     type WeightInfo: WeightInfo;
 
     /// Security traits
-    type Security: SecurityExt<Self::AccountId, Self::BlockNumber>;
+    type Security: SecurityExt<Self::AccountId, Self::BlockNumber> + ExtrinsicIdExt;
 
     /// The maximum number of active swaps per account id
     #[pallet::constant]
     type SwapLimitByAccount: Get<u32>;
 
     /// Fees traits
-    type Fees: FeesExt<Self::AccountId, Self::BlockNumber>;
+    type Fees: FeesExt<Self::AccountId, Self::BlockNumber> + SwapFeesExt<Self::AccountId>;
 
     /// Tidefi sunrise traits
-    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>;
+    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>
+      + SunriseSwapRewards<Self::AccountId>;
 
     /// Tidechain currency wrapper
     type CurrencyTidefi: Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
@@ -115,6 +119,16 @@ pub mod pallet {
   pub type Swaps<T: Config> =
     StorageMap<_, Blake2_128Concat, Hash, Swap<T::AccountId, T::BlockNumber>>;
 
+  /// Fee rate locked for each pending swap when the swap was created
+  #[pallet::storage]
+  #[pallet::getter(fn swap_fee_rates)]
+  pub type SwapFeeRates<T: Config> = StorageMap<_, Blake2_128Concat, Hash, Permill>;
+
+  /// TDFY fee held for each pending swap paying its fees in TDFY, for the whole swap amount
+  #[pallet::storage]
+  #[pallet::getter(fn swap_fees_in_tdfy)]
+  pub type SwapFeesInTdfy<T: Config> = StorageMap<_, Blake2_128Concat, Hash, Balance>;
+
   /// Mapping of pending Swaps by AccountId
   #[pallet::storage]
   #[pallet::getter(fn account_swaps)]
@@ -519,14 +533,20 @@ pub mod pallet {
                     market_maker_trade_intent.status = SwapStatus::PartiallyFilled;
                   }
 
-                  // 11. d) Transfer funds from the requester to the market makers
-                  let amount_and_fee = T::Fees::calculate_swap_fees(
+                  // self-matched trades and assets sold before the end of their holding period
+                  // are not rewarded by the sunrise pools
+                  let trader_rewardable = T::Sunrise::is_trade_rewardable(
+                    &trade.account_id,
+                    &market_maker_trade_intent.account_id,
                     trade.token_from,
-                    mm.amount_to_receive,
-                    trade.swap_type.clone(),
-                    trade.is_market_maker,
+                  );
+                  let market_maker_rewardable = T::Sunrise::is_trade_rewardable(
+                    &market_maker_trade_intent.account_id,
+                    &trade.account_id,
+                    trade.token_to,
                   );
 
+                  // 11. d) Transfer funds from the requester to the market makers
                   if T::CurrencyTidefi::transfer_held(
                     trade.token_from,
                     &trade.account_id,
@@ -540,38 +560,12 @@ pub mod pallet {
                     // FIXME: Add rollback
                   }
 
-                  if T::CurrencyTidefi::transfer_held(
-                    trade.token_from,
-                    &trade.account_id,
-                    &T::Fees::account_id(),
-                    amount_and_fee.fee,
-                    false,
-                    false,
-                  )
-                  .is_err()
-                  {
-                    // FIXME: Add rollback
-                  }
-
-                  // 11. f) Register a new trading fees associated with the account.
+                  // 11. f) Transfer the swap fees and register a new trading fees associated with the account.
                   // A percentage of the network profits will be re-distributed to the account at the end of the era.
-                  T::Fees::register_swap_fees(
-                    trade.account_id.clone(),
-                    trade.token_from,
-                    mm.amount_to_receive,
-                    trade.swap_type.clone(),
-                    trade.is_market_maker,
-                  )
-                  .map_err(|_| Error::<T>::SwapFeeRegistrationFailed)?;
+                  Self::pay_swap_fees(request_id, trade, mm.amount_to_receive, trader_rewardable)
+                    .map_err(|_| Error::<T>::SwapFeeRegistrationFailed)?;
 
                   // 12. a) Transfer funds from the market makers to the account
-                  let amount_and_fee = T::Fees::calculate_swap_fees(
-                    trade.token_to,
-                    mm.amount_to_send,
-                    market_maker_trade_intent.swap_type.clone(),
-                    market_maker_trade_intent.is_market_maker,
-                  );
-
                   if T::CurrencyTidefi::transfer_held(
                     trade.token_to,
                     &market_maker_trade_intent.account_id,
@@ -588,30 +582,23 @@ pub mod pallet {
 
                   // 12. b) Market makers pay fees of the transaction, but this is deducted
                   // from the requester final amount, so this is paid by the requester
-                  if T::CurrencyTidefi::transfer_held(
-                    trade.token_to,
-                    &market_maker_trade_intent.account_id,
-                    &T::Fees::account_id(),
-                    amount_and_fee.fee,
-                    false,
-                    false,
-                  )
-                  .is_err()
-                  {
-                    // FIXME: Add rollback
-                  }
-
                   // 12. c) Register a new trading fees associated with the account.
                   // A percentage of the network profits will be re-distributed to the account at the end of the era.
-                  T::Fees::register_swap_fees(
-                    market_maker_trade_intent.account_id.clone(),
-                    trade.token_to,
+                  Self::pay_swap_fees(
+                    mm.request_id,
+                    market_maker_trade_intent,
                     mm.amount_to_send,
-                    market_maker_trade_intent.swap_type.clone(),
-                    market_maker_trade_intent.is_market_maker,
+                    market_maker_rewardable,
                   )
                   .map_err(|_| Error::<T>::MarketMakerSwapFeeRegistrationFailed)?;
 
+                  // start the holding period of the assets received
+                  T::Sunrise::register_swap_acquisition(&trade.account_id, trade.token_to);
+                  T::Sunrise::register_swap_acquisition(
+                    &market_maker_trade_intent.account_id,
+                    trade.token_from,
+                  );
+
                   // 13. Emit market maker trade event on chain
                   Self::deposit_event(Event::<T>::SwapProcessed {
                     request_id: mm.request_id,
@@ -635,8 +622,10 @@ pub mod pallet {
                       mm.request_id,
                     )
                     .map_err(|_| Error::<T>::DeleteMarketMakerSwapFailed)?;
-                    Self::swap_release_funds(market_maker_trade_intent)
+                    Self::swap_release_funds(mm.request_id, market_maker_trade_intent)
                       .map_err(|_| Error::<T>::ReleaseMarketMakerUnswappedFundsFailed)?;
+                    SwapFeeRates::<T>::remove(mm.request_id);
+                    SwapFeesInTdfy::<T>::remove(mm.request_id);
                     *mm_trade_request = None;
                   } else {
                     Self::try_update_account_swap_status(
@@ -668,8 +657,10 @@ pub mod pallet {
             if trade.status == SwapStatus::Completed || trade.swap_type == SwapType::Market {
               Self::try_delete_account_swap(&trade.account_id, request_id)
                 .map_err(|_| Error::<T>::DeleteSwapFailed)?;
-              Self::swap_release_funds(trade)
+              Self::swap_release_funds(request_id, trade)
                 .map_err(|_| Error::<T>::ReleaseUnswappedFundsFailed)?;
+              SwapFeeRates::<T>::remove(request_id);
+              SwapFeesInTdfy::<T>::remove(request_id);
 
               *trade_request = None;
             } else {
@@ -889,35 +880,142 @@ pub mod pallet {
 
   // helper functions (not dispatchable)
   impl<T: Config> Pallet<T> {
-    fn swap_release_funds(trade: &Swap<T::AccountId, T::BlockNumber>) -> Result<(), DispatchError> {
-      // real fees required
-      let real_fees_amount = T::Fees::calculate_swap_fees(
-        trade.token_from,
-        trade.amount_from_filled,
-        trade.swap_type.clone(),
-        trade.is_market_maker,
-      );
-      let fees_with_slippage = T::Fees::calculate_swap_fees(
-        trade.token_from,
-        trade.amount_from,
-        trade.swap_type.clone(),
-        trade.is_market_maker,
-      );
+    // Fee rate locked when the swap was created, swaps created before the rates were locked
+    // use the global fee schedule
+    fn swap_fee_rate(request_id: Hash, trade: &Swap<T::AccountId, T::BlockNumber>) -> Permill {
+      SwapFeeRates::<T>::get(request_id).unwrap_or_else(|| {
+        T::Fees::base_swap_fee_rate(trade.swap_type.clone(), trade.is_market_maker)
+      })
+    }
+
+    // Share of the TDFY fee held for the swap, paid for `amount` of the swap
+    fn tdfy_fee_share(
+      fee_tdfy: Balance,
+      amount: Balance,
+      trade: &Swap<T::AccountId, T::BlockNumber>,
+    ) -> Balance {
+      FixedU128::saturating_from_rational(amount, trade.amount_from).saturating_mul_int(fee_tdfy)
+    }
+
+    // Transfer the held swap fees for `amount` of the swap to the fees pallet and register them,
+    // allocating the sunrise rewards when `sunrise_rewardable`
+    fn pay_swap_fees(
+      request_id: Hash,
+      trade: &Swap<T::AccountId, T::BlockNumber>,
+      amount: Balance,
+      sunrise_rewardable: bool,
+    ) -> Result<Fee, DispatchError> {
+      match SwapFeesInTdfy::<T>::get(request_id) {
+        Some(fee_tdfy) => {
+          let fee_tdfy = Self::tdfy_fee_share(fee_tdfy, amount, trade);
+          if T::CurrencyTidefi::transfer_held(
+            CurrencyId::Tdfy,
+            &trade.account_id,
+            &T::Fees::account_id(),
+            fee_tdfy,
+            false,
+            false,
+          )
+          .is_err()
+          {
+            // FIXME: Add rollback
+          }
+
+          T::Fees::register_swap_fees_in_tdfy(
+            trade.account_id.clone(),
+            trade.token_from,
+            amount,
+            fee_tdfy,
+            sunrise_rewardable,
+          )
+        }
+        None => {
+          let fee_rate = Self::swap_fee_rate(request_id, trade);
+          let amount_and_fee =
+            T::Fees::calculate_swap_fees_with_rate(trade.token_from, amount, fee_rate);
+          if T::CurrencyTidefi::transfer_held(
+            trade.token_from,
+            &trade.account_id,
+            &T::Fees::account_id(),
+            amount_and_fee.fee,
+            false,
+            false,
+          )
+          .is_err()
+          {
+            // FIXME: Add rollback
+          }
+
+          T::Fees::register_swap_fees_with_rate(
+            trade.account_id.clone(),
+            trade.token_from,
+            amount,
+            fee_rate,
+            sunrise_rewardable,
+          )
+        }
+      }
+    }
+
+    // Swap fees held but not paid for the unfilled amount of the swap, with their currency
+    fn unpaid_swap_fees(
+      request_id: Hash,
+      trade: &Swap<T::AccountId, T::BlockNumber>,
+    ) -> Result<(CurrencyId, Balance), DispatchError> {
+      match SwapFeesInTdfy::<T>::get(request_id) {
+        Some(fee_tdfy) => Ok((
+          CurrencyId::Tdfy,
+          fee_tdfy
+            .checked_sub(Self::tdfy_fee_share(
+              fee_tdfy,
+              trade.amount_from_filled,
+              trade,
+            ))
+            .ok_or(Error::<T>::SlippageOverflow)?,
+        )),
+        None => {
+          let fee_rate = Self::swap_fee_rate(request_id, trade);
+          // real fees required
+          let real_fees_amount = T::Fees::calculate_swap_fees_with_rate(
+            trade.token_from,
+            trade.amount_from_filled,
+            fee_rate,
+          );
+          let fees_with_slippage =
+            T::Fees::calculate_swap_fees_with_rate(trade.token_from, trade.amount_from, fee_rate);
+
+          Ok((
+            trade.token_from,
+            fees_with_slippage
+              .fee
+              .checked_sub(real_fees_amount.fee)
+              .ok_or(Error::<T>::SlippageOverflow)?,
+          ))
+        }
+      }
+    }
 
-      let amount_to_release = trade
+    fn swap_release_funds(
+      request_id: Hash,
+      trade: &Swap<T::AccountId, T::BlockNumber>,
+    ) -> Result<(), DispatchError> {
+      let mut amount_to_release = trade
         .amount_from
         // reduce filled amount
         .checked_sub(trade.amount_from_filled)
-        .ok_or(Error::<T>::ArithmeticError)?
-        // reduce un-needed locked fee
-        .checked_add(
-          fees_with_slippage
-            .fee
-            .checked_sub(real_fees_amount.fee)
-            .ok_or(Error::<T>::SlippageOverflow)?,
-        )
         .ok_or(Error::<T>::ArithmeticError)?;
 
+      // release un-needed locked fee
+      let (fee_currency_id, unpaid_fees) = Self::unpaid_swap_fees(request_id, trade)?;
+      if fee_currency_id == trade.token_from {
+        amount_to_release = amount_to_release
+          .checked_add(unpaid_fees)
+          .ok_or(Error::<T>::ArithmeticError)?;
+      } else if unpaid_fees > 0 {
+        T::CurrencyTidefi::release(fee_currency_id, &trade.account_id, unpaid_fees, true)
+          .map_err(|_| Error::<T>::ReleaseFailed)?;
+      }
+
       T::CurrencyTidefi::release(trade.token_from, &trade.account_id, amount_to_release, true)
         .map_err(|_| Error::<T>::ReleaseFailed)?;
 
@@ -991,7 +1089,10 @@ pub mod pallet {
       swap_type: SwapType,
       slippage: Permill,
     ) -> Result<(Hash, Swap<T::AccountId, T::BlockNumber>), DispatchError> {
-      let request_id = T::Security::get_unique_id(account_id.clone());
+      // derived from the signer of the extrinsic when the swap is requested by a signed
+      // extrinsic, so the signer can predict it
+      let request_id = T::Security::get_extrinsic_unique_id()
+        .unwrap_or_else(|| T::Security::get_unique_id(account_id.clone()));
       let swap = Swap {
         account_id: account_id.clone(),
         token_from: asset_id_from,
@@ -1008,19 +1109,37 @@ pub mod pallet {
         slippage,
       };
 
-      // 6. Freeze asset
-      let amount_and_fee =
-        T::Fees::calculate_swap_fees(asset_id_from, amount_from, swap_type, is_market_maker);
-
-      T::CurrencyTidefi::hold(
-        asset_id_from,
+      // 6. Freeze asset, with the fee rate locked for the lifetime of the swap
+      let fee_rate = T::Fees::swap_fee_rate(
         &account_id,
-        amount_from
-          .checked_add(amount_and_fee.fee)
-          .ok_or(Error::<T>::ArithmeticError)?,
-      )?;
+        asset_id_from,
+        asset_id_to,
+        swap_type,
+        is_market_maker,
+      );
+      let amount_and_fee =
+        T::Fees::calculate_swap_fees_with_rate(asset_id_from, amount_from, fee_rate);
+
+      match T::Fees::swap_fee_in_tdfy(&account_id, asset_id_from, amount_and_fee.fee) {
+        // the account pays the swap fees in TDFY
+        Some(fee_tdfy) => {
+          T::CurrencyTidefi::hold(asset_id_from, &account_id, amount_from)?;
+          T::CurrencyTidefi::hold(CurrencyId::Tdfy, &account_id, fee_tdfy)?;
+          SwapFeesInTdfy::<T>::insert(request_id, fee_tdfy);
+        }
+        None => {
+          T::CurrencyTidefi::hold(
+            asset_id_from,
+            &account_id,
+            amount_from
+              .checked_add(amount_and_fee.fee)
+              .ok_or(Error::<T>::ArithmeticError)?,
+          )?;
+        }
+      }
 
       Swaps::<T>::insert(request_id, swap.clone());
+      SwapFeeRates::<T>::insert(request_id, fee_rate);
 
       AccountSwaps::<T>::try_mutate(account_id, |account_swaps| match account_swaps {
         Some(swaps) => swaps
@@ -1059,58 +1178,17 @@ pub mod pallet {
             .checked_sub(swap_intent.amount_from_filled)
             .unwrap_or(0);
 
+          // FIXME: Should we refund the swap fee?
+          // release the remaining funds and the network fee
           if amount_to_release > 0 {
-            // release the remaining funds and the network fee
-            let amount_and_fee = T::Fees::calculate_swap_fees(
-              swap_intent.token_from,
-              swap_intent.amount_from,
-              swap_intent.swap_type.clone(),
-              swap_intent.is_market_maker,
-            );
-
-            // FIXME: Should we refund the swap fee?
-            // swap fee
-            let real_amount_to_release = if swap_intent.amount_from_filled == 0 {
-              amount_to_release
-                .checked_add(amount_and_fee.fee)
-                .ok_or(Error::<T>::ArithmeticError)?
-            } else {
-              // real fees required
-              let fees_amount_filled = T::Fees::calculate_swap_fees(
-                swap_intent.token_from,
-                swap_intent.amount_from_filled,
-                swap_intent.swap_type.clone(),
-                swap_intent.is_market_maker,
-              );
-              let fees_amount = T::Fees::calculate_swap_fees(
-                swap_intent.token_from,
-                swap_intent.amount_from,
-                swap_intent.swap_type.clone(),
-                swap_intent.is_market_maker,
-              );
-
-              amount_to_release
-                .checked_add(
-                  fees_amount
-                    .fee
-                    .checked_sub(fees_amount_filled.fee)
-                    .ok_or(Error::<T>::ArithmeticError)?,
-                )
-                .ok_or(Error::<T>::ArithmeticError)?
-            };
-
-            T::CurrencyTidefi::release(
-              swap_intent.token_from,
-              &swap_intent.account_id,
-              real_amount_to_release,
-              true,
-            )
-            .map_err(|_| Error::<T>::ReleaseFailed)?;
+            Self::swap_release_funds(request_id, swap_intent)?;
           }
 
           // delete the swap from the storage
           Self::try_delete_account_swap(&swap_intent.account_id, request_id)
             .map_err(|_| Error::<T>::UnknownError)?;
+          SwapFeeRates::<T>::remove(request_id);
+          SwapFeesInTdfy::<T>::remove(request_id);
 
           *swap = None;
 
@@ -24,7 +24,7 @@ use frame_support::{
       MutateHold as FungibleMutateHold, Transfer as FungibleTransfer,
     },
     fungibles::{Inspect, InspectHold, Mutate, MutateHold, Transfer},
-    ConstU128, ConstU32, GenesisBuild,
+    ConstU128, ConstU32, ConstU64, GenesisBuild,
   },
   PalletId,
 };
@@ -33,11 +33,11 @@ use sp_core::H256;
 use sp_runtime::{
   testing::Header,
   traits::{BlakeTwo256, IdentityLookup},
-  DispatchError, DispatchResult, FixedU128, Permill,
+  DispatchError, DispatchResult, FixedU128, Percent, Permill,
 };
 use std::marker::PhantomData;
 use system::EnsureRoot;
-use tidefi_primitives::{BlockNumber, CurrencyId, SessionIndex};
+use tidefi_primitives::{BlockNumber, CurrencyId, EraIndex, SessionIndex};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -141,6 +141,7 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
   pub const SunrisePalletId: PalletId = PalletId(*b"sunr*pal");
+  pub const TreasuryPalletId: PalletId = PalletId(*b"trsy*pal");
   pub const TidefiPalletId: PalletId = PalletId(*b"wrpr*pal");
   pub const FeesPalletId: PalletId = PalletId(*b"wrpr*pab");
   pub const AssetRegistryPalletId: PalletId = PalletId(*b"asst*pal");
@@ -150,6 +151,10 @@ parameter_types! {
   pub const SessionsArchive: SessionIndex = 2;
   pub const BlocksPerSession: BlockNumber = 50;
   pub const BlocksForceUnstake: BlockNumber = 10;
+  pub const CancelUnstakeFeeRefund: Percent = Percent::from_percent(50);
+  pub const UnstakeFeeDestination: Option<AccountId> = None;
+  pub const StakersFeeShare: Percent = Percent::from_percent(5);
+  pub const RewardsProjectionSessions: SessionIndex = 4;
   pub const StakeAccountCap: u32 = 10;
   pub const UnstakeQueueCap: u32 = 100;
   // 20 basis point
@@ -157,6 +162,9 @@ parameter_types! {
   // 10 basis point
   pub const MarketMakerFeeAmount: Permill = Permill::from_perthousand(10);
   pub const MarketMakerLimitFeeAmount: Permill = Permill::from_perthousand(10);
+  pub const VolumeTierEras: EraIndex = 30;
+  pub const EraArchive: EraIndex = 30;
+  pub const UseSessionManager: bool = false;
   // 20 %
   pub const DistributionPercentage: Permill = Permill::from_percent(20);
   // The number of swap each account can have in queue
@@ -183,6 +191,11 @@ impl pallet_oracle::Config for Test {
 
 impl pallet_security::Config for Test {
   type Event = Event;
+  type PauseOrigin = EnsureRoot<Self::AccountId>;
+  type EmergencyOrigin = system::EnsureSigned<Self::AccountId>;
+  type EmergencyInitiator = Self::AccountId;
+  type EmergencyPauseDuration = ConstU64<100>;
+  type PauseReasonLimit = ConstU32<256>;
   type WeightInfo = pallet_security::weights::SubstrateWeight<Test>;
 }
 
@@ -194,15 +207,22 @@ impl pallet_tidefi_stake::Config for Test {
   type StakeAccountCap = StakeAccountCap;
   type UnstakeQueueCap = UnstakeQueueCap;
   type BlocksForceUnstake = BlocksForceUnstake;
+  type CancelUnstakeFeeRefund = CancelUnstakeFeeRefund;
+  type UnstakeFeeDestination = UnstakeFeeDestination;
+  type RewardsProjectionSessions = RewardsProjectionSessions;
   type AssetRegistry = AssetRegistry;
   type StakingRewardCap = StakingRewardCap;
   type Security = Security;
+  type Sunrise = Sunrise;
+  type Fees = Fees;
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
 }
 
 impl pallet_asset_registry::Config for Test {
   type Event = Event;
   type WeightInfo = pallet_asset_registry::weights::SubstrateWeight<Test>;
   type AssetRegistryPalletId = AssetRegistryPalletId;
+  type StringLimit = ConstU32<255>;
   type CurrencyTidefi = Adapter<AccountId>;
 }
 
@@ -210,14 +230,21 @@ impl pallet_fees::Config for Test {
   type Event = Event;
   type Security = Security;
   type FeesPalletId = TidefiPalletId;
+  type TreasuryPalletId = TreasuryPalletId;
   type CurrencyTidefi = Adapter<AccountId>;
   type ForceOrigin = EnsureRoot<Self::AccountId>;
+  type WeightInfo = pallet_fees::weights::SubstrateWeight<Test>;
   type UnixTime = Timestamp;
   type SessionsPerEra = SessionsPerEra;
   type SessionsArchive = SessionsArchive;
   type FeeAmount = FeeAmount;
   type MarketMakerFeeAmount = MarketMakerFeeAmount;
   type MarketMakerLimitFeeAmount = MarketMakerLimitFeeAmount;
+  type VolumeTierEras = VolumeTierEras;
+  type EraArchive = EraArchive;
+  type UseSessionManager = UseSessionManager;
+  type ValidatorEra = ();
+  type StakersFeeShare = StakersFeeShare;
   type BlocksPerSession = BlocksPerSession;
   type Staking = TidefiStaking;
   type Sunrise = Sunrise;
@@ -231,6 +258,18 @@ impl pallet_sunrise::Config for Test {
   type Cooldown = Cooldown;
   type MaximumRewardPerSwap = MaximumRewardPerSwap;
   type LeftoverSwapRebates = LeftoverSwapRebates;
+  type MaximumRewardPerAccountPerEra = ConstU128<{ u128::MAX }>;
+  type MinimumHoldingPeriod = ConstU64<0>;
+  type LinkedAccounts = ();
+  type MaximumOnboardingRefundPerAccount = ConstU128<{ u128::MAX }>;
+  type CurrentEra = ();
+  type RewardsClaimWindow = ConstU32<4_320>;
+  type RewardsExpiryNotice = ConstU32<168>;
+  type ExpiredRewardsDestination = ();
+  type RewardsVesting = ();
+  type Vesting = ();
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
+  type WeightInfo = pallet_sunrise::weights::SubstrateWeight<Test>;
 }
 
 impl pallet_timestamp::Config for Test {
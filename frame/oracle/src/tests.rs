@@ -25,14 +25,15 @@ use frame_support::{
   assert_noop, assert_ok,
   traits::fungibles::{Inspect, InspectHold, Mutate},
 };
+use pallet_sunrise::SunriseSwapRewards;
 use sp_core::H256;
 use sp_runtime::{
   traits::{BadOrigin, Zero},
-  FixedPointNumber, FixedU128, Permill,
+  FixedPointNumber, FixedU128, Percent, Permill,
 };
 use std::str::FromStr;
 use tidefi_primitives::{
-  pallet::{FeesExt, OracleExt},
+  pallet::{FeesExt, OracleExt, SunriseExt},
   Balance, CurrencyId, Hash, SwapConfirmation, SwapStatus, SwapType,
 };
 
@@ -1201,6 +1202,201 @@ pub fn confirm_swap_with_fees() {
   });
 }
 
+#[test]
+pub fn confirm_swap_with_locked_fee_rates() {
+  new_test_ext().execute_with(|| {
+    const BOB_INITIAL_20_TDFYS: Balance = 20 * ONE_TDFY;
+    const CHARLIE_INITIAL_10000_TEMPS: Balance = 10_000 * ONE_TEMP;
+
+    let context = Context::default()
+      .set_oracle_status(true)
+      .set_market_makers(vec![CHARLIE_ACCOUNT_ID])
+      .mint_tdfy(ALICE_ACCOUNT_ID, ONE_TDFY)
+      .mint_tdfy(CHARLIE_ACCOUNT_ID, ONE_TDFY)
+      .mint_tdfy(BOB_ACCOUNT_ID, BOB_INITIAL_20_TDFYS)
+      .create_temp_asset_and_metadata()
+      .mint_temp(CHARLIE_ACCOUNT_ID, CHARLIE_INITIAL_10000_TEMPS);
+
+    Fees::start_era();
+
+    const BOB_SELLS_10_TDFYS: Balance = 10 * ONE_TDFY;
+    const BOB_BUYS_200_TEMPS: Balance = 200 * ONE_TEMP;
+    let trade_request_id = context.create_tdfy_to_temp_limit_swap_request(
+      BOB_ACCOUNT_ID,
+      BOB_SELLS_10_TDFYS,
+      BOB_BUYS_200_TEMPS,
+      EXTRINSIC_HASH_0,
+      SLIPPAGE_2_PERCENTS,
+    );
+    assert_eq!(
+      Oracle::swap_fee_rates(trade_request_id),
+      Some(REQUESTER_SWAP_FEE_RATE)
+    );
+
+    const CHARLIE_SELLS_200_TEMPS: Balance = 200 * ONE_TEMP;
+    const CHARLIE_BUYS_10_TDFYS: Balance = 10 * ONE_TDFY;
+    let trade_request_mm_id = context.create_temp_to_tdfy_limit_swap_request(
+      CHARLIE_ACCOUNT_ID,
+      CHARLIE_SELLS_200_TEMPS,
+      CHARLIE_BUYS_10_TDFYS,
+      EXTRINSIC_HASH_1,
+      SLIPPAGE_5_PERCENTS,
+    );
+
+    // the fee schedule is updated after the swaps are created
+    assert_ok!(Fees::set_swap_fees(
+      Origin::root(),
+      pallet_fees::SwapFeeSchedule {
+        fee: Permill::from_percent(5),
+        market_maker_fee: Permill::from_percent(5),
+        market_maker_limit_fee: Permill::from_percent(5),
+      }
+    ));
+
+    assert_ok!(Oracle::confirm_swap(
+      context.alice,
+      trade_request_id,
+      vec![SwapConfirmation {
+        request_id: trade_request_mm_id,
+        amount_to_receive: CHARLIE_BUYS_10_TDFYS,
+        amount_to_send: CHARLIE_SELLS_200_TEMPS,
+      }],
+    ));
+
+    // the fees are paid with the rates locked when the swaps were created
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &context.fees_account_id),
+      ONE_TDFY + REQUESTER_SWAP_FEE_RATE * BOB_SELLS_10_TDFYS
+    );
+    assert_eq!(
+      Adapter::balance(TEMP_CURRENCY_ID, &context.fees_account_id),
+      MARKET_MAKER_SWAP_FEE_RATE * CHARLIE_SELLS_200_TEMPS
+    );
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &BOB_ACCOUNT_ID),
+      BOB_INITIAL_20_TDFYS
+        .saturating_sub(BOB_SELLS_10_TDFYS)
+        .saturating_sub(REQUESTER_SWAP_FEE_RATE * BOB_SELLS_10_TDFYS)
+    );
+
+    // the locked rates are deleted with the completed swaps
+    assert!(Oracle::swap_fee_rates(trade_request_id).is_none());
+    assert!(Oracle::swap_fee_rates(trade_request_mm_id).is_none());
+  });
+}
+
+#[test]
+pub fn confirm_swap_with_fees_paid_in_tdfy() {
+  new_test_ext().execute_with(|| {
+    const BOB_INITIAL_ONE_TDFY: Balance = ONE_TDFY;
+    const BOB_INITIAL_1000_TEMPS: Balance = 1_000 * ONE_TEMP;
+    const CHARLIE_INITIAL_20_TDFYS: Balance = 20 * ONE_TDFY;
+
+    let context = Context::default()
+      .set_oracle_status(true)
+      .set_market_makers(vec![CHARLIE_ACCOUNT_ID])
+      .mint_tdfy(ALICE_ACCOUNT_ID, ONE_TDFY)
+      .mint_tdfy(BOB_ACCOUNT_ID, BOB_INITIAL_ONE_TDFY)
+      .mint_tdfy(CHARLIE_ACCOUNT_ID, CHARLIE_INITIAL_20_TDFYS)
+      .create_temp_asset_and_metadata()
+      .mint_temp(BOB_ACCOUNT_ID, BOB_INITIAL_1000_TEMPS);
+
+    Fees::start_era();
+    let current_era = Fees::current_era().unwrap().index;
+
+    // 1 TEMP = 0.05 TDFY
+    assert_ok!(Sunrise::register_exchange_rate(vec![(
+      TEMP_ASSET_ID,
+      500_000_000
+    )]));
+    assert_ok!(Fees::set_tdfy_fee_discount(
+      Origin::root(),
+      Percent::from_percent(25)
+    ));
+    assert_ok!(Fees::set_pay_fees_in_tdfy(context.bob.clone(), true));
+
+    // 200 TEMPS @ 2% = 4 TEMPS = 0.2 TDFY, with a 25% discount
+    const BOB_SELLS_200_TEMPS: Balance = 200 * ONE_TEMP;
+    const BOB_BUYS_10_TDFYS: Balance = 10 * ONE_TDFY;
+    const BOB_FEE_IN_TDFY: Balance = 150_000_000_000;
+    let (trade_request_id, _) = Oracle::add_new_swap_in_queue(
+      BOB_ACCOUNT_ID,
+      TEMP_CURRENCY_ID,
+      BOB_SELLS_200_TEMPS,
+      CurrencyId::Tdfy,
+      BOB_BUYS_10_TDFYS,
+      CURRENT_BLOCK_NUMBER,
+      EXTRINSIC_HASH_0,
+      false,
+      SwapType::Limit,
+      SLIPPAGE_2_PERCENTS,
+    )
+    .unwrap();
+
+    // the fee is held in TDFY instead of the sold currency
+    assert_eq!(
+      Oracle::swap_fees_in_tdfy(trade_request_id),
+      Some(BOB_FEE_IN_TDFY)
+    );
+    assert_eq!(
+      Adapter::balance_on_hold(TEMP_CURRENCY_ID, &BOB_ACCOUNT_ID),
+      BOB_SELLS_200_TEMPS
+    );
+    assert_eq!(
+      Adapter::balance_on_hold(CurrencyId::Tdfy, &BOB_ACCOUNT_ID),
+      BOB_FEE_IN_TDFY
+    );
+
+    const CHARLIE_SELLS_10_TDFYS: Balance = 10 * ONE_TDFY;
+    const CHARLIE_BUYS_200_TEMPS: Balance = 200 * ONE_TEMP;
+    let trade_request_mm_id = context.create_tdfy_to_temp_limit_swap_request(
+      CHARLIE_ACCOUNT_ID,
+      CHARLIE_SELLS_10_TDFYS,
+      CHARLIE_BUYS_200_TEMPS,
+      EXTRINSIC_HASH_1,
+      SLIPPAGE_5_PERCENTS,
+    );
+
+    assert_ok!(Oracle::confirm_swap(
+      context.alice,
+      trade_request_id,
+      vec![SwapConfirmation {
+        request_id: trade_request_mm_id,
+        amount_to_receive: BOB_SELLS_200_TEMPS,
+        amount_to_send: CHARLIE_SELLS_10_TDFYS,
+      }],
+    ));
+
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &context.fees_account_id),
+      ONE_TDFY + BOB_FEE_IN_TDFY + MARKET_MAKER_SWAP_FEE_RATE * CHARLIE_SELLS_10_TDFYS
+    );
+    assert_eq!(
+      Adapter::balance(TEMP_CURRENCY_ID, &context.fees_account_id),
+      0
+    );
+    assert_eq!(
+      Adapter::balance(TEMP_CURRENCY_ID, &BOB_ACCOUNT_ID),
+      BOB_INITIAL_1000_TEMPS - BOB_SELLS_200_TEMPS
+    );
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &BOB_ACCOUNT_ID),
+      BOB_INITIAL_ONE_TDFY + BOB_BUYS_10_TDFYS - BOB_FEE_IN_TDFY
+    );
+    assert_eq!(
+      Adapter::balance_on_hold(CurrencyId::Tdfy, &BOB_ACCOUNT_ID),
+      0
+    );
+
+    // the fee is registered in TDFY
+    let bob_fee = Fees::account_fees(current_era, BOB_ACCOUNT_ID);
+    assert_eq!(bob_fee.first().unwrap().0, CurrencyId::Tdfy);
+    assert_eq!(bob_fee.first().unwrap().1.fee, BOB_FEE_IN_TDFY);
+    assert_eq!(bob_fee.first().unwrap().1.amount, BOB_BUYS_10_TDFYS);
+    assert!(Oracle::swap_fees_in_tdfy(trade_request_id).is_none());
+  });
+}
+
 #[test]
 pub fn confirm_swap_ourself() {
   new_test_ext().execute_with(|| {
@@ -1291,6 +1487,16 @@ pub fn confirm_swap_ourself() {
       Adapter::balance_on_hold(TEMP_CURRENCY_ID, &BOB_ACCOUNT_ID),
       Zero::zero()
     );
+
+    // the holding period of the assets received through the swap started
+    assert!(Sunrise::swap_acquisition(BOB_ACCOUNT_ID, TEMP_CURRENCY_ID).is_some());
+    assert!(Sunrise::swap_acquisition(BOB_ACCOUNT_ID, CurrencyId::Tdfy).is_some());
+    // the self-matched trade can't be rewarded
+    assert!(!Sunrise::is_trade_rewardable(
+      &BOB_ACCOUNT_ID,
+      &BOB_ACCOUNT_ID,
+      TEMP_CURRENCY_ID
+    ));
   });
 }
 
@@ -20,7 +20,7 @@ use super::*;
 use frame_benchmarking::{
   account, benchmarks, impl_benchmark_test_suite, vec, whitelisted_caller, Vec,
 };
-use frame_support::BoundedVec;
+use frame_support::{traits::EnsureOrigin, BoundedVec};
 use frame_system::{self, RawOrigin};
 use tidefi_primitives::{
   pallet::SecurityExt, ComplianceLevel, CurrencyId, Hash, Mint, ProposalType, ProposalVotes,
@@ -138,6 +138,19 @@ benchmarks! {
       }
       let user = pre_set_auth::<T>();
    }: _(RawOrigin::Signed(user), all_keys)
+   review_held_mint {
+      let account_id: T::AccountId = whitelisted_caller();
+      let proposal_id = Hash::zero();
+      HeldMints::<T>::insert(proposal_id, Mint {
+         account_id,
+         currency_id: CurrencyId::Tdfy,
+         mint_amount: 1_000_000_000_000,
+         gas_amount: None,
+         transaction_id: Default::default(),
+         compliance_level: ComplianceLevel::Green,
+      });
+      let origin = T::MintReviewOrigin::successful_origin();
+   }: _<T::Origin>(origin, proposal_id, true)
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
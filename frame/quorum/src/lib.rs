@@ -54,6 +54,8 @@ pub mod pallet {
     PalletId,
   };
   use frame_system::pallet_prelude::*;
+  use pallet_security::{RateLimitsExt, Subsystem, SubsystemsStatusExt};
+  use pallet_sunrise::SunriseOnboardingRefund;
   use rand_chacha::{
     rand_core::{RngCore, SeedableRng},
     ChaChaRng,
@@ -94,10 +96,13 @@ pub mod pallet {
     type WeightInfo: WeightInfo;
 
     /// Security traits
-    type Security: SecurityExt<Self::AccountId, Self::BlockNumber>;
+    type Security: SecurityExt<Self::AccountId, Self::BlockNumber>
+      + SubsystemsStatusExt
+      + RateLimitsExt<Self::AccountId>;
 
     /// Sunrise traits
-    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>;
+    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>
+      + SunriseOnboardingRefund<Self::AccountId>;
 
     /// The maximum length of string (public keys etc..)
     #[pallet::constant]
@@ -118,6 +123,10 @@ pub mod pallet {
     /// Asset registry traits
     type AssetRegistry: AssetRegistryExt;
 
+    /// The origin which may mint or discard the deposits held for review, when they exceeded
+    /// the currency rate limits
+    type MintReviewOrigin: EnsureOrigin<Self::Origin>;
+
     /// Tidechain currency wrapper
     type CurrencyTidefi: Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
       + Mutate<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
@@ -223,6 +232,16 @@ pub mod pallet {
     ValueQuery,
   >;
 
+  /// Mints held for review, as they exceeded the currency rate limits
+  #[pallet::storage]
+  #[pallet::getter(fn held_mint)]
+  pub type HeldMints<T: Config> = StorageMap<
+    _,
+    Blake2_128Concat,
+    Hash,
+    Mint<T::AccountId, BoundedVec<u8, <T as pallet::Config>::StringLimit>>,
+  >;
+
   /// Genesis configuration
   #[pallet::genesis_config]
   pub struct GenesisConfig<T: Config> {
@@ -273,6 +292,15 @@ pub mod pallet {
       transaction_id: Vec<u8>,
       compliance_level: ComplianceLevel,
     },
+    /// The mint exceeded the currency rate limits and is held for review
+    MintHeldForReview {
+      proposal_id: Hash,
+      account_id: T::AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+    },
+    /// The mint held for review has been discarded
+    HeldMintDiscarded { proposal_id: Hash },
     /// A new transaction has been added to the watch list
     WatchTransactionAdded {
       account_id: T::AccountId,
@@ -372,6 +400,12 @@ pub mod pallet {
     UnknownError,
     /// Invalid asset
     InvalidAsset,
+    /// Deposits are paused
+    DepositsPaused,
+    /// The currency is paused
+    CurrencyPaused,
+    /// No held mint with the ID was found
+    HeldMintDoesNotExist,
   }
 
   #[pallet::hooks]
@@ -407,17 +441,20 @@ pub mod pallet {
         BoundedVec<u8, <T as pallet::Config>::StringLimit>,
         BoundedVec<T::AccountId, <T as pallet::Config>::VotesLimit>,
       > = match proposal {
-        ProposalType::Mint(mint) => ProposalType::Mint(Mint {
-          account_id: mint.account_id,
-          currency_id: mint.currency_id,
-          mint_amount: mint.mint_amount,
-          gas_amount: mint.gas_amount,
-          transaction_id: mint
-            .transaction_id
-            .try_into()
-            .map_err(|_| Error::<T>::BadTransactionId)?,
-          compliance_level: mint.compliance_level,
-        }),
+        ProposalType::Mint(mint) => {
+          Self::ensure_deposits_running(mint.currency_id)?;
+          ProposalType::Mint(Mint {
+            account_id: mint.account_id,
+            currency_id: mint.currency_id,
+            mint_amount: mint.mint_amount,
+            gas_amount: mint.gas_amount,
+            transaction_id: mint
+              .transaction_id
+              .try_into()
+              .map_err(|_| Error::<T>::BadTransactionId)?,
+            compliance_level: mint.compliance_level,
+          })
+        }
         ProposalType::Withdrawal(withdrawal) => ProposalType::Withdrawal(Withdrawal {
           account_id: withdrawal.account_id,
           asset_id: withdrawal.asset_id,
@@ -540,6 +577,39 @@ pub mod pallet {
       // Don't take tx fees on success
       Ok(Pays::No.into())
     }
+
+    /// Mint or discard a deposit held for review, as it exceeded the currency rate limits.
+    ///
+    /// - `proposal_id`: The proposal of the held mint
+    /// - `approve`: Whether the deposit is minted
+    ///
+    /// The deposit can't be minted while the deposits or its currency are paused, it stays
+    /// held until they are resumed.
+    ///
+    /// Emits `Minted` or `HeldMintDiscarded` event when successful.
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::review_held_mint())]
+    pub fn review_held_mint(
+      origin: OriginFor<T>,
+      proposal_id: Hash,
+      approve: bool,
+    ) -> DispatchResultWithPostInfo {
+      T::MintReviewOrigin::ensure_origin(origin)?;
+      let mint = HeldMints::<T>::get(proposal_id).ok_or(Error::<T>::HeldMintDoesNotExist)?;
+      if approve {
+        // the mint stays held until the deposits are resumed
+        Self::ensure_deposits_running(mint.currency_id)?;
+      }
+      HeldMints::<T>::remove(proposal_id);
+
+      if approve {
+        // the deposit has been reviewed, the rate limits are not consumed
+        Self::do_mint(proposal_id, &mint)?;
+      } else {
+        Self::deposit_event(Event::<T>::HeldMintDiscarded { proposal_id });
+      }
+
+      Ok(().into())
+    }
   }
 
   // helper functions (not dispatchable)
@@ -872,6 +942,9 @@ pub mod pallet {
         Error::<T>::AssetDisabled
       );
 
+      // 1 b. The proposal stays pending while the deposits or the currency are paused
+      Self::ensure_deposits_running(item.currency_id)?;
+
       // 2. Add `Amber` and `Red` to watch list
       if item.compliance_level == ComplianceLevel::Amber
         || item.compliance_level == ComplianceLevel::Red
@@ -890,44 +963,86 @@ pub mod pallet {
       if item.compliance_level == ComplianceLevel::Green
         || item.compliance_level == ComplianceLevel::Amber
       {
-        T::CurrencyTidefi::mint_into(item.currency_id, &item.account_id, item.mint_amount)
-          .map_err(|_| Error::<T>::MintFailed)?;
-
-        // 3 a. If Quorum provide `gas_amount` try to process refunds based on sunrise allocation
-        if let Some(gas_amount) = item.gas_amount {
-          // gas for USDT by example, are paid in ETH
-          // we extract the base chain for the asset
-          // and if needed extract the currency id
-
-          // quorum would have sent us the amount in ETH
-          // but the mint would have been for `USDT`
-          let asset_from: Asset = item
-            .currency_id
-            .try_into()
-            .map_err(|_| Error::<T>::InvalidAsset)?;
-
-          let real_currency_id = match asset_from.base_chain() {
-            Some(base_chain) => base_chain.currency_id(),
-            None => item.currency_id,
-          };
-          if let Err(refund_error) =
-            T::Sunrise::try_refund_gas_for_deposit(&item.account_id, real_currency_id, gas_amount)
-          {
-            log!(error, "Unable to process gas refund {:?}", refund_error);
-          }
+        // 3 a. Hold the mint for review when it exceeds the currency rate limits
+        if T::Security::try_consume_rate_limit(
+          Subsystem::Deposits,
+          &item.account_id,
+          item.currency_id,
+          item.mint_amount,
+        ) {
+          Self::do_mint(proposal_id, item)?;
+        } else {
+          HeldMints::<T>::insert(proposal_id, item.clone());
+          Self::deposit_event(Event::<T>::MintHeldForReview {
+            proposal_id,
+            account_id: item.account_id.clone(),
+            currency_id: item.currency_id,
+            amount: item.mint_amount,
+          });
         }
-        // item.gas_amount
+      }
 
-        Self::deposit_event(Event::<T>::Minted {
-          proposal_id,
-          account_id: item.account_id.clone(),
-          currency_id: item.currency_id,
-          amount: item.mint_amount,
-          transaction_id: item.transaction_id.clone().to_vec(),
-          compliance_level: item.compliance_level.clone(),
-        });
+      Ok(())
+    }
+
+    // Make sure the deposits and the currency are not paused
+    fn ensure_deposits_running(currency_id: CurrencyId) -> Result<(), Error<T>> {
+      ensure!(
+        T::Security::is_subsystem_running(Subsystem::Deposits),
+        Error::<T>::DepositsPaused
+      );
+      ensure!(
+        T::Security::is_currency_running(currency_id),
+        Error::<T>::CurrencyPaused
+      );
+      Ok(())
+    }
+
+    // Mint the deposit and refund the gas
+    fn do_mint(
+      proposal_id: Hash,
+      item: &Mint<T::AccountId, BoundedVec<u8, <T as pallet::Config>::StringLimit>>,
+    ) -> Result<(), Error<T>> {
+      T::CurrencyTidefi::mint_into(item.currency_id, &item.account_id, item.mint_amount)
+        .map_err(|_| Error::<T>::MintFailed)?;
+
+      // If Quorum provide `gas_amount` try to process refunds based on sunrise allocation
+      if let Some(gas_amount) = item.gas_amount {
+        // gas for USDT by example, are paid in ETH
+        // we extract the base chain for the asset
+        // and if needed extract the currency id
+
+        // quorum would have sent us the amount in ETH
+        // but the mint would have been for `USDT`
+        let asset_from: Asset = item
+          .currency_id
+          .try_into()
+          .map_err(|_| Error::<T>::InvalidAsset)?;
+
+        let real_currency_id = match asset_from.base_chain() {
+          Some(base_chain) => base_chain.currency_id(),
+          None => item.currency_id,
+        };
+        // the deposit is refunded once, within the account onboarding allowance
+        if let Err(refund_error) = T::Sunrise::try_refund_gas_for_mint(
+          &item.account_id,
+          real_currency_id,
+          gas_amount,
+          &item.transaction_id,
+        ) {
+          log!(error, "Unable to process gas refund {:?}", refund_error);
+        }
       }
 
+      Self::deposit_event(Event::<T>::Minted {
+        proposal_id,
+        account_id: item.account_id.clone(),
+        currency_id: item.currency_id,
+        amount: item.mint_amount,
+        transaction_id: item.transaction_id.clone().to_vec(),
+        compliance_level: item.compliance_level.clone(),
+      });
+
       Ok(())
     }
 
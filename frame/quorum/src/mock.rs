@@ -24,7 +24,7 @@ use frame_support::{
       MutateHold as FungibleMutateHold, Transfer as FungibleTransfer,
     },
     fungibles::{Inspect, InspectHold, Mutate, MutateHold, Transfer},
-    ConstU128, ConstU32, GenesisBuild,
+    ConstU128, ConstU32, ConstU64, GenesisBuild,
   },
   PalletId,
 };
@@ -166,6 +166,7 @@ impl pallet_quorum::Config for Test {
   type Sunrise = Sunrise;
   type CurrencyTidefi = Adapter<AccountId>;
   type AssetRegistry = AssetRegistry;
+  type MintReviewOrigin = EnsureRoot<Self::AccountId>;
   type ProposalsCap = ProposalsCap;
   type BurnedCap = BurnedCap;
   type ProposalLifetime = ProposalLifetime;
@@ -183,10 +184,27 @@ impl pallet_sunrise::Config for Test {
   type Cooldown = Cooldown;
   type MaximumRewardPerSwap = MaximumRewardPerSwap;
   type LeftoverSwapRebates = LeftoverSwapRebates;
+  type MaximumRewardPerAccountPerEra = ConstU128<{ u128::MAX }>;
+  type MinimumHoldingPeriod = ConstU64<0>;
+  type LinkedAccounts = ();
+  type MaximumOnboardingRefundPerAccount = ConstU128<{ u128::MAX }>;
+  type CurrentEra = ();
+  type RewardsClaimWindow = ConstU32<4_320>;
+  type RewardsExpiryNotice = ConstU32<168>;
+  type ExpiredRewardsDestination = ();
+  type RewardsVesting = ();
+  type Vesting = ();
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
+  type WeightInfo = pallet_sunrise::weights::SubstrateWeight<Test>;
 }
 
 impl pallet_security::Config for Test {
   type Event = Event;
+  type PauseOrigin = EnsureRoot<Self::AccountId>;
+  type EmergencyOrigin = system::EnsureSigned<Self::AccountId>;
+  type EmergencyInitiator = Self::AccountId;
+  type EmergencyPauseDuration = ConstU64<100>;
+  type PauseReasonLimit = ConstU32<256>;
   type WeightInfo = pallet_security::weights::SubstrateWeight<Test>;
 }
 
@@ -194,6 +212,7 @@ impl pallet_asset_registry::Config for Test {
   type Event = Event;
   type WeightInfo = pallet_asset_registry::weights::SubstrateWeight<Test>;
   type AssetRegistryPalletId = AssetRegistryPalletId;
+  type StringLimit = ConstU32<255>;
   type CurrencyTidefi = Adapter<AccountId>;
 }
 
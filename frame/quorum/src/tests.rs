@@ -33,7 +33,7 @@ use sp_core::H256;
 use sp_runtime::traits::BadOrigin;
 use std::str::FromStr;
 
-use pallet_security::CurrentBlockCount as CurrentBlockNumber;
+use pallet_security::{CurrentBlockCount as CurrentBlockNumber, RateLimit, Subsystem};
 use tidefi_primitives::{
   pallet::SecurityExt, AssetId, Balance, ComplianceLevel, CurrencyId, Hash, Mint, ProposalStatus,
   ProposalType, ProposalVotes, WatchList, WatchListAction, Withdrawal,
@@ -1419,6 +1419,56 @@ mod voting_for_proposals {
           });
         }
 
+        #[test]
+        pub fn deposits_are_paused() {
+          new_test_ext().execute_with(|| {
+            let context = Context::default()
+              .insert_asset1_with_alice_public_key()
+              .insert_a_valid_mint_proposal_with_green_compliance_level();
+
+            let asset_balance_before = get_alice_tdfy_balance();
+            assert_ok!(Security::set_subsystem_status(
+              Origin::root(),
+              Subsystem::Deposits,
+              true
+            ));
+
+            assert_err!(
+              Quorum::acknowledge_proposal(context.alice.clone(), context.proposal_id),
+              Error::<Test>::DepositsPaused
+            );
+
+            assert_mint_proposal_exists_in_storage(&context, ComplianceLevel::Green);
+            assert_vote_for_exists_in_storage(&context);
+            assert_eq!(asset_balance_before, get_alice_tdfy_balance());
+          });
+        }
+
+        #[test]
+        pub fn currency_is_paused() {
+          new_test_ext().execute_with(|| {
+            let context = Context::default()
+              .insert_asset1_with_alice_public_key()
+              .insert_a_valid_mint_proposal_with_green_compliance_level();
+
+            let asset_balance_before = get_alice_tdfy_balance();
+            assert_ok!(Security::set_currency_status(
+              Origin::root(),
+              context.valid_mint.currency_id,
+              true
+            ));
+
+            assert_err!(
+              Quorum::acknowledge_proposal(context.alice.clone(), context.proposal_id),
+              Error::<Test>::CurrencyPaused
+            );
+
+            assert_mint_proposal_exists_in_storage(&context, ComplianceLevel::Green);
+            assert_vote_for_exists_in_storage(&context);
+            assert_eq!(asset_balance_before, get_alice_tdfy_balance());
+          });
+        }
+
         #[test]
         pub fn watch_list_overflow() {
           new_test_ext().execute_with(|| {
@@ -1976,3 +2026,152 @@ mod submit_public_keys {
     }
   }
 }
+
+mod review_held_mint {
+  use super::*;
+
+  fn hold_valid_mint(context: &Context) {
+    assert_ok!(Security::set_rate_limit(
+      Origin::root(),
+      Subsystem::Deposits,
+      CurrencyId::Tdfy,
+      Some(RateLimit {
+        period: 100,
+        global_cap: 10 * ONE_TDFY,
+        account_cap: ONE_TDFY / 2,
+      })
+    ));
+    assert_ok!(Quorum::acknowledge_proposal(
+      context.alice.clone(),
+      context.proposal_id
+    ));
+
+    assert!(Quorum::held_mint(context.proposal_id).is_some());
+    assert_proposal_and_its_votes_have_been_deleted(context.proposal_id);
+    System::assert_has_event(MockEvent::Quorum(Event::MintHeldForReview {
+      proposal_id: context.proposal_id,
+      account_id: ALICE_ACCOUNT_ID.into(),
+      currency_id: CurrencyId::Tdfy,
+      amount: context.valid_mint.mint_amount,
+    }));
+  }
+
+  #[test]
+  pub fn mints_when_approved() {
+    new_test_ext().execute_with(|| {
+      let context = Context::default()
+        .insert_asset1_with_alice_public_key()
+        .insert_a_valid_mint_proposal_with_green_compliance_level();
+      let asset_balance_before = get_alice_tdfy_balance();
+
+      hold_valid_mint(&context);
+      assert_eq!(asset_balance_before, get_alice_tdfy_balance());
+
+      assert_ok!(Quorum::review_held_mint(
+        Origin::root(),
+        context.proposal_id,
+        true
+      ));
+
+      assert_eq!(
+        asset_balance_before + context.valid_mint.mint_amount,
+        get_alice_tdfy_balance()
+      );
+      assert!(Quorum::held_mint(context.proposal_id).is_none());
+      assert_event_is_emitted_minted(&context, ComplianceLevel::Green);
+    });
+  }
+
+  #[test]
+  pub fn discards_when_rejected() {
+    new_test_ext().execute_with(|| {
+      let context = Context::default()
+        .insert_asset1_with_alice_public_key()
+        .insert_a_valid_mint_proposal_with_green_compliance_level();
+      let asset_balance_before = get_alice_tdfy_balance();
+
+      hold_valid_mint(&context);
+      assert_ok!(Quorum::review_held_mint(
+        Origin::root(),
+        context.proposal_id,
+        false
+      ));
+
+      assert_eq!(asset_balance_before, get_alice_tdfy_balance());
+      assert!(Quorum::held_mint(context.proposal_id).is_none());
+      System::assert_has_event(MockEvent::Quorum(Event::HeldMintDiscarded {
+        proposal_id: context.proposal_id,
+      }));
+    });
+  }
+
+  mod fails_when {
+    use super::*;
+
+    #[test]
+    pub fn signer_is_not_review_origin() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .insert_asset1_with_alice_public_key()
+          .insert_a_valid_mint_proposal_with_green_compliance_level();
+
+        hold_valid_mint(&context);
+        assert_noop!(
+          Quorum::review_held_mint(context.alice.clone(), context.proposal_id, true),
+          BadOrigin
+        );
+      });
+    }
+
+    #[test]
+    pub fn deposits_are_paused() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .insert_asset1_with_alice_public_key()
+          .insert_a_valid_mint_proposal_with_green_compliance_level();
+        let asset_balance_before = get_alice_tdfy_balance();
+
+        hold_valid_mint(&context);
+        assert_ok!(Security::set_subsystem_status(
+          Origin::root(),
+          Subsystem::Deposits,
+          true
+        ));
+
+        assert_noop!(
+          Quorum::review_held_mint(Origin::root(), context.proposal_id, true),
+          Error::<Test>::DepositsPaused
+        );
+        assert!(Quorum::held_mint(context.proposal_id).is_some());
+
+        // the held mint is minted once the deposits are resumed
+        assert_ok!(Security::set_subsystem_status(
+          Origin::root(),
+          Subsystem::Deposits,
+          false
+        ));
+        assert_ok!(Quorum::review_held_mint(
+          Origin::root(),
+          context.proposal_id,
+          true
+        ));
+        assert_eq!(
+          asset_balance_before + context.valid_mint.mint_amount,
+          get_alice_tdfy_balance()
+        );
+      });
+    }
+
+    #[test]
+    pub fn held_mint_does_not_exist() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default();
+
+        assert_noop!(
+          Quorum::review_held_mint(Origin::root(), context.proposal_id, true),
+          Error::<Test>::HeldMintDoesNotExist
+        );
+      });
+    }
+  }
+}
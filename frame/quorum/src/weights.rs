@@ -29,6 +29,7 @@ pub trait WeightInfo {
    fn reject_proposal() -> Weight;
    fn eval_proposal_state() -> Weight;
    fn submit_public_keys(_k: u32) -> Weight;
+   fn review_held_mint() -> Weight;
 }
 
 /// Weights for `pallet_tidefi` using the Substrate node and recommended hardware.
@@ -65,4 +66,9 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
          .saturating_add(T::DbWeight::get().reads(6_u64))
          .saturating_add(T::DbWeight::get().writes(5_u64))
    }
+   fn review_held_mint() -> Weight {
+      50_000_000_u64
+         .saturating_add(T::DbWeight::get().reads(5_u64))
+         .saturating_add(T::DbWeight::get().writes(4_u64))
+   }
 }
@@ -16,15 +16,36 @@
 
 #![cfg(feature = "runtime-benchmarks")]
 
-use crate::{Call, Config, Pallet};
+use crate::{Call, Config, Pallet, RateLimit, Subsystem};
 use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::{EnsureOrigin, Get};
 use frame_system::RawOrigin;
-use tidefi_primitives::StatusCode;
+use sp_std::{vec, vec::Vec};
+use tidefi_primitives::{CurrencyId, StatusCode};
 
 benchmarks! {
    set_status {
       let new_status = StatusCode::Maintenance;
    }: _(RawOrigin::Root, new_status.clone())
+   set_subsystem_status {
+   }: _(RawOrigin::Root, Subsystem::Swaps, true)
+   set_currency_status {
+   }: _(RawOrigin::Root, CurrencyId::Wrapped(2), true)
+   emergency_pause {
+      let origin = T::EmergencyOrigin::successful_origin();
+      let reason = vec![0u8; T::PauseReasonLimit::get() as usize];
+   }: _<T::Origin>(origin, reason)
+   extend_emergency_pause {
+      let origin = T::EmergencyOrigin::successful_origin();
+      Pallet::<T>::emergency_pause(origin, Vec::new())?;
+   }: _(RawOrigin::Root, 10_u32.into())
+   set_rate_limit {
+      let rate_limit = RateLimit {
+         period: 100_u32.into(),
+         global_cap: 1_000_000,
+         account_cap: 1_000,
+      };
+   }: _(RawOrigin::Root, Subsystem::Withdrawals, CurrencyId::Wrapped(2), Some(rate_limit))
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,93 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{Config, ExtrinsicSigner};
+use codec::{Decode, Encode};
+use frame_support::weights::DispatchInfo;
+use scale_info::TypeInfo;
+use sp_runtime::{
+  traits::{DispatchInfoOf, Dispatchable, One, PostDispatchInfoOf, Saturating, SignedExtension},
+  transaction_validity::TransactionValidityError,
+  DispatchResult,
+};
+use sp_std::marker::PhantomData;
+
+/// Record the signer and the nonce of the extrinsic during its dispatch, so the pallets can
+/// derive the unique IDs the signer predicts before the submission.
+///
+/// Must be placed after `frame_system::CheckNonce`, which increments the nonce before the
+/// dispatch.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckExtrinsicSigner<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckExtrinsicSigner<T> {
+  /// Create new `SignedExtension` to record the signer of the extrinsic.
+  pub fn new() -> Self {
+    Self(PhantomData)
+  }
+}
+
+impl<T: Config + Send + Sync> Default for CheckExtrinsicSigner<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for CheckExtrinsicSigner<T> {
+  fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+    write!(f, "CheckExtrinsicSigner")
+  }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckExtrinsicSigner<T>
+where
+  T::Call: Dispatchable<Info = DispatchInfo>,
+{
+  const IDENTIFIER: &'static str = "CheckExtrinsicSigner";
+  type AccountId = T::AccountId;
+  type Call = T::Call;
+  type AdditionalSigned = ();
+  type Pre = ();
+
+  fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+    Ok(())
+  }
+
+  fn pre_dispatch(
+    self,
+    who: &Self::AccountId,
+    _call: &Self::Call,
+    _info: &DispatchInfoOf<Self::Call>,
+    _len: usize,
+  ) -> Result<Self::Pre, TransactionValidityError> {
+    // the nonce the extrinsic has been signed with, already incremented by `CheckNonce`
+    let nonce = frame_system::Pallet::<T>::account_nonce(who).saturating_sub(One::one());
+    ExtrinsicSigner::<T>::put((who.clone(), nonce, 0));
+    Ok(())
+  }
+
+  fn post_dispatch(
+    _pre: Option<Self::Pre>,
+    _info: &DispatchInfoOf<Self::Call>,
+    _post_info: &PostDispatchInfoOf<Self::Call>,
+    _len: usize,
+    _result: &DispatchResult,
+  ) -> Result<(), TransactionValidityError> {
+    ExtrinsicSigner::<T>::kill();
+    Ok(())
+  }
+}
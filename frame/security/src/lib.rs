@@ -28,6 +28,9 @@ mod benchmarking;
 pub mod weights;
 pub use weights::*;
 
+mod extension;
+pub use extension::CheckExtrinsicSigner;
+
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
 
@@ -38,14 +41,125 @@ pub mod pallet {
   use frame_system::pallet_prelude::*;
   use sha2::{Digest, Sha256};
   use sp_core::{H256, U256};
-  use sp_runtime::traits::Saturating;
-  use tidefi_primitives::{pallet::SecurityExt, Hash, StatusCode};
+  use sp_runtime::{
+    traits::{BlakeTwo256, Hash as HashT, Saturating, UniqueSaturatedInto, Zero},
+    Perquintill,
+  };
+  use sp_std::vec::Vec;
+  use tidefi_primitives::{pallet::SecurityExt, Balance, CurrencyId, Hash, StatusCode};
+
+  /// Domain of the unique IDs derived from the extrinsics.
+  pub const EXTRINSIC_ID_PREFIX: &[u8] = b"tidefi/extrinsic-id";
+
+  /// Subsystems which can be paused independently while the chain is running.
+  #[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+  pub enum Subsystem {
+    /// Swaps requests and cancellations
+    Swaps,
+    /// Deposits minted by the quorum
+    Deposits,
+    /// Withdrawals requests
+    Withdrawals,
+    /// Staking of the funds
+    Staking,
+  }
+
+  /// Emergency pause of the chain, lapsing at `expires_at` unless extended by root.
+  #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+  pub struct EmergencyPause<Initiator, BlockNumber, Reason> {
+    /// Initiator of the pause, as returned by the emergency origin
+    pub initiator: Initiator,
+    /// Reason of the pause
+    pub reason: Reason,
+    /// Block the pause lapses
+    pub expires_at: BlockNumber,
+  }
+
+  pub type EmergencyPauseOf<T> = EmergencyPause<
+    <T as Config>::EmergencyInitiator,
+    <T as frame_system::Config>::BlockNumber,
+    BoundedVec<u8, <T as Config>::PauseReasonLimit>,
+  >;
+
+  /// Rate limit of a currency in a subsystem, the consumed amount is released linearly over
+  /// `period` blocks.
+  #[derive(
+    Clone, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+  )]
+  pub struct RateLimit<BlockNumber> {
+    /// Number of blocks of the rolling window
+    pub period: BlockNumber,
+    /// Maximum amount for all the accounts in the window
+    pub global_cap: Balance,
+    /// Maximum amount per account in the window
+    pub account_cap: Balance,
+  }
+
+  /// Amount consumed in a rate limit window.
+  #[derive(
+    Clone, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+  )]
+  pub struct RateLimitUsage<BlockNumber> {
+    /// Amount consumed, as of `updated_at`
+    pub amount: Balance,
+    /// Block of the last update
+    pub updated_at: BlockNumber,
+  }
+
+  /// Interface used by other pallets to check the pause flags of the subsystems and currencies.
+  pub trait SubsystemsStatusExt {
+    /// The chain is running and the subsystem is not paused.
+    fn is_subsystem_running(subsystem: Subsystem) -> bool;
+    /// The chain is running and the currency is not paused.
+    fn is_currency_running(currency_id: CurrencyId) -> bool;
+  }
+
+  /// Interface used by other pallets to generate unique IDs the clients can predict before
+  /// submitting the extrinsic.
+  pub trait ExtrinsicIdExt {
+    /// Unique ID derived from the signer of the extrinsic being dispatched, its nonce and the
+    /// number of IDs already generated for this extrinsic.
+    ///
+    /// `None` outside of a signed extrinsic (hooks, inherents and unsigned extrinsics), the
+    /// caller falls back to `SecurityExt::get_unique_id`.
+    fn get_extrinsic_unique_id() -> Option<Hash>;
+  }
+
+  /// Interface used by other pallets to enforce the rate limits of the deposits and withdrawals.
+  pub trait RateLimitsExt<AccountId> {
+    /// Consume `amount` of the currency rate limits in the subsystem, for the account and
+    /// globally.
+    ///
+    /// Returns `false`, without consuming anything, when a cap would be exceeded.
+    fn try_consume_rate_limit(
+      subsystem: Subsystem,
+      account_id: &AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+    ) -> bool;
+  }
 
   /// Security configuration
   #[pallet::config]
   pub trait Config: frame_system::Config {
     /// Events
     type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+    /// The origin which may pause and resume the subsystems and the currencies, and set their
+    /// rate limits
+    type PauseOrigin: EnsureOrigin<Self::Origin>;
+    /// The origin which may pause the chain during an incident, for `EmergencyPauseDuration`
+    /// blocks
+    type EmergencyOrigin: EnsureOrigin<Self::Origin, Success = Self::EmergencyInitiator>;
+    /// Initiator of an emergency pause, stored and emitted with the pause. This is the signer
+    /// for a guardian set, or `()` for a collective motion, which is already recorded by the
+    /// collective
+    type EmergencyInitiator: Parameter + Member + MaxEncodedLen;
+    /// Number of blocks an emergency pause lasts before it lapses, unless root extends it
+    #[pallet::constant]
+    type EmergencyPauseDuration: Get<Self::BlockNumber>;
+    /// Maximum length of the emergency pause reason
+    #[pallet::constant]
+    type PauseReasonLimit: Get<u32>;
     /// Weights
     type WeightInfo: WeightInfo;
   }
@@ -69,6 +183,71 @@ pub mod pallet {
   #[pallet::getter(fn current_block_number)]
   pub type CurrentBlockCount<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
 
+  /// Subsystems paused, the whole chain is paused with the `ChainStatus`
+  #[pallet::storage]
+  #[pallet::getter(fn is_subsystem_paused)]
+  pub type PausedSubsystems<T: Config> =
+    StorageMap<_, Blake2_128Concat, Subsystem, bool, ValueQuery>;
+
+  /// Currencies paused, in all the subsystems
+  #[pallet::storage]
+  #[pallet::getter(fn is_currency_paused)]
+  pub type PausedCurrencies<T: Config> =
+    StorageMap<_, Blake2_128Concat, CurrencyId, bool, ValueQuery>;
+
+  /// Signer and nonce of the signed extrinsic being dispatched, and the number of unique IDs it
+  /// generated, so a batch gets distinct IDs.
+  ///
+  /// Set and removed by the `CheckExtrinsicSigner` signed extension, around the dispatch.
+  #[pallet::storage]
+  #[pallet::getter(fn extrinsic_signer)]
+  pub type ExtrinsicSigner<T: Config> = StorageValue<_, (T::AccountId, T::Index, u32), OptionQuery>;
+
+  /// Rate limits of the currencies, per subsystem
+  #[pallet::storage]
+  #[pallet::getter(fn rate_limit)]
+  pub type RateLimits<T: Config> = StorageDoubleMap<
+    _,
+    Blake2_128Concat,
+    Subsystem,
+    Blake2_128Concat,
+    CurrencyId,
+    RateLimit<T::BlockNumber>,
+    OptionQuery,
+  >;
+
+  /// Amount consumed of the rate limits, for all the accounts
+  #[pallet::storage]
+  #[pallet::getter(fn global_rate_limit_usage)]
+  pub type GlobalRateLimitUsage<T: Config> = StorageDoubleMap<
+    _,
+    Blake2_128Concat,
+    Subsystem,
+    Blake2_128Concat,
+    CurrencyId,
+    RateLimitUsage<T::BlockNumber>,
+    ValueQuery,
+  >;
+
+  /// Amount consumed of the rate limits, per account
+  #[pallet::storage]
+  #[pallet::getter(fn account_rate_limit_usage)]
+  pub type AccountRateLimitUsage<T: Config> = StorageNMap<
+    _,
+    (
+      NMapKey<Blake2_128Concat, Subsystem>,
+      NMapKey<Blake2_128Concat, CurrencyId>,
+      NMapKey<Blake2_128Concat, T::AccountId>,
+    ),
+    RateLimitUsage<T::BlockNumber>,
+    ValueQuery,
+  >;
+
+  /// Active emergency pause of the chain
+  #[pallet::storage]
+  #[pallet::getter(fn emergency_pause)]
+  pub type ActiveEmergencyPause<T: Config> = StorageValue<_, EmergencyPauseOf<T>, OptionQuery>;
+
   /// Genesis config
   #[pallet::genesis_config]
   pub struct GenesisConfig {
@@ -98,6 +277,28 @@ pub mod pallet {
   pub enum Event<T: Config> {
     StatusChanged(StatusCode),
     UpdateCurrentBlock(T::BlockNumber),
+    /// A subsystem has been paused or resumed
+    SubsystemStatusChanged(Subsystem, bool),
+    /// A currency has been paused or resumed
+    CurrencyStatusChanged(CurrencyId, bool),
+    /// The chain has been paused by the emergency origin
+    EmergencyPaused {
+      initiator: T::EmergencyInitiator,
+      reason: Vec<u8>,
+      expires_at: T::BlockNumber,
+    },
+    /// The emergency pause has been extended by root
+    EmergencyPauseExtended {
+      expires_at: T::BlockNumber,
+    },
+    /// The emergency pause lapsed, the chain is running again
+    EmergencyPauseLapsed,
+    /// The rate limit of a currency has been set or removed
+    RateLimitChanged {
+      subsystem: Subsystem,
+      currency_id: CurrencyId,
+      rate_limit: Option<RateLimit<T::BlockNumber>>,
+    },
   }
 
   // Errors inform users that something went wrong.
@@ -105,11 +306,30 @@ pub mod pallet {
   pub enum Error<T> {
     /// Chain is in maintenance mode
     ChainMaintenanceMode,
+    /// The emergency pause reason is too long
+    PauseReasonTooLong,
+    /// There is no emergency pause to extend
+    NoEmergencyPause,
+    /// The rate limit period can't be zero
+    InvalidRateLimitPeriod,
   }
 
   #[pallet::hooks]
   impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
-    fn on_initialize(_current_block: T::BlockNumber) -> Weight {
+    fn on_initialize(current_block: T::BlockNumber) -> Weight {
+      // `ActiveEmergencyPause` and `ChainStatus`
+      let mut weight = T::DbWeight::get().reads(2);
+
+      // lift the emergency pause once expired, the block count is frozen while paused
+      if let Some(emergency_pause) = Self::emergency_pause() {
+        if current_block >= emergency_pause.expires_at {
+          ActiveEmergencyPause::<T>::kill();
+          <ChainStatus<T>>::set(StatusCode::Running);
+          Self::deposit_event(Event::EmergencyPauseLapsed);
+          weight = weight.saturating_add(T::DbWeight::get().writes(2));
+        }
+      }
+
       // updated ONLY when the chain status is running
       if Self::status() == StatusCode::Running {
         let height = <CurrentBlockCount<T>>::mutate(|n| {
@@ -117,8 +337,10 @@ pub mod pallet {
           *n
         });
         Self::deposit_event(Event::UpdateCurrentBlock(height));
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
       }
-      0
+
+      weight
     }
   }
 
@@ -140,10 +362,155 @@ pub mod pallet {
     #[pallet::weight(<T as pallet::Config>::WeightInfo::set_status())]
     pub fn set_status(origin: OriginFor<T>, status_code: StatusCode) -> DispatchResultWithPostInfo {
       ensure_root(origin)?;
+      // root takes over the emergency pause
+      ActiveEmergencyPause::<T>::kill();
       <ChainStatus<T>>::set(status_code.clone());
       Self::deposit_event(Event::StatusChanged(status_code));
       Ok(().into())
     }
+
+    /// Pause the chain during an incident, without waiting for root.
+    ///
+    /// The pause lapses after `EmergencyPauseDuration` blocks, unless root extends it or
+    /// sets the chain status.
+    ///
+    /// - `reason`: Reason of the pause
+    ///
+    /// Emits `EmergencyPaused` event when successful.
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::emergency_pause())]
+    pub fn emergency_pause(origin: OriginFor<T>, reason: Vec<u8>) -> DispatchResultWithPostInfo {
+      let initiator = T::EmergencyOrigin::ensure_origin(origin)?;
+      ensure!(
+        Self::status() == StatusCode::Running,
+        Error::<T>::ChainMaintenanceMode
+      );
+
+      let expires_at =
+        frame_system::Pallet::<T>::block_number().saturating_add(T::EmergencyPauseDuration::get());
+      ActiveEmergencyPause::<T>::put(EmergencyPause {
+        initiator: initiator.clone(),
+        reason: reason
+          .clone()
+          .try_into()
+          .map_err(|_| Error::<T>::PauseReasonTooLong)?,
+        expires_at,
+      });
+      <ChainStatus<T>>::set(StatusCode::Maintenance);
+
+      Self::deposit_event(Event::EmergencyPaused {
+        initiator,
+        reason,
+        expires_at,
+      });
+      Ok(().into())
+    }
+
+    /// Extend the active emergency pause.
+    ///
+    /// - `blocks`: Number of blocks added to the pause
+    ///
+    /// Emits `EmergencyPauseExtended` event when successful.
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::extend_emergency_pause())]
+    pub fn extend_emergency_pause(
+      origin: OriginFor<T>,
+      blocks: T::BlockNumber,
+    ) -> DispatchResultWithPostInfo {
+      ensure_root(origin)?;
+      let expires_at = ActiveEmergencyPause::<T>::try_mutate(|emergency_pause| {
+        let emergency_pause = emergency_pause
+          .as_mut()
+          .ok_or(Error::<T>::NoEmergencyPause)?;
+        emergency_pause.expires_at = emergency_pause.expires_at.saturating_add(blocks);
+        Ok::<_, Error<T>>(emergency_pause.expires_at)
+      })?;
+
+      Self::deposit_event(Event::EmergencyPauseExtended { expires_at });
+      Ok(().into())
+    }
+
+    /// Pause or resume a subsystem.
+    ///
+    /// The other subsystems keep running, the chain status takes precedence over the
+    /// subsystems status.
+    ///
+    /// - `subsystem`: The subsystem to pause or resume
+    /// - `paused`: Whether the subsystem is paused
+    ///
+    /// Emits `SubsystemStatusChanged` event when successful.
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::set_subsystem_status())]
+    pub fn set_subsystem_status(
+      origin: OriginFor<T>,
+      subsystem: Subsystem,
+      paused: bool,
+    ) -> DispatchResultWithPostInfo {
+      T::PauseOrigin::ensure_origin(origin)?;
+      if paused {
+        PausedSubsystems::<T>::insert(subsystem, true);
+      } else {
+        PausedSubsystems::<T>::remove(subsystem);
+      }
+      Self::deposit_event(Event::SubsystemStatusChanged(subsystem, paused));
+      Ok(().into())
+    }
+
+    /// Pause or resume a currency in all the subsystems.
+    ///
+    /// This is meant for a known issue with a bridge, the other currencies keep running.
+    ///
+    /// - `currency_id`: The currency to pause or resume
+    /// - `paused`: Whether the currency is paused
+    ///
+    /// Emits `CurrencyStatusChanged` event when successful.
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::set_currency_status())]
+    pub fn set_currency_status(
+      origin: OriginFor<T>,
+      currency_id: CurrencyId,
+      paused: bool,
+    ) -> DispatchResultWithPostInfo {
+      T::PauseOrigin::ensure_origin(origin)?;
+      if paused {
+        PausedCurrencies::<T>::insert(currency_id, true);
+      } else {
+        PausedCurrencies::<T>::remove(currency_id);
+      }
+      Self::deposit_event(Event::CurrencyStatusChanged(currency_id, paused));
+      Ok(().into())
+    }
+
+    /// Set or remove the rate limit of a currency in a subsystem.
+    ///
+    /// The amount already consumed in the window is kept when the rate limit is updated.
+    ///
+    /// - `subsystem`: The subsystem of the rate limit, `Deposits` or `Withdrawals`
+    /// - `currency_id`: The currency of the rate limit
+    /// - `rate_limit`: The new rate limit, `None` to remove it
+    ///
+    /// Emits `RateLimitChanged` event when successful.
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::set_rate_limit())]
+    pub fn set_rate_limit(
+      origin: OriginFor<T>,
+      subsystem: Subsystem,
+      currency_id: CurrencyId,
+      rate_limit: Option<RateLimit<T::BlockNumber>>,
+    ) -> DispatchResultWithPostInfo {
+      T::PauseOrigin::ensure_origin(origin)?;
+      match rate_limit.clone() {
+        Some(rate_limit) => {
+          ensure!(
+            !rate_limit.period.is_zero(),
+            Error::<T>::InvalidRateLimitPeriod
+          );
+          RateLimits::<T>::insert(subsystem, currency_id, rate_limit);
+        }
+        None => RateLimits::<T>::remove(subsystem, currency_id),
+      }
+      Self::deposit_event(Event::RateLimitChanged {
+        subsystem,
+        currency_id,
+        rate_limit,
+      });
+      Ok(().into())
+    }
   }
 
   // helper functions (not dispatchable)
@@ -170,6 +537,37 @@ pub mod pallet {
       result.copy_from_slice(&hasher.finalize()[..]);
       H256(result)
     }
+
+    /// Unique ID of the `index`th ID generated by the extrinsic signed by `signer` with `nonce`.
+    ///
+    /// `blake2_256(SCALE(("tidefi/extrinsic-id", signer, nonce, index)))`, so it can be
+    /// computed off-chain before the submission.
+    ///
+    /// The ID is keyed on the account signing the extrinsic. For a proxy or a multisig call, this
+    /// is the proxy or the multisig member submitting the extrinsic, with its own nonce, and not
+    /// the proxied or the multisig account the call is dispatched for.
+    pub fn extrinsic_unique_id(signer: &T::AccountId, nonce: T::Index, index: u32) -> Hash {
+      BlakeTwo256::hash_of(&(EXTRINSIC_ID_PREFIX, signer, nonce, index))
+    }
+
+    /// Amount still consumed of a rate limit at `current_block`, the usage is released linearly
+    /// over the rate limit period.
+    fn rate_limit_usage_at(
+      usage: &RateLimitUsage<T::BlockNumber>,
+      cap: Balance,
+      period: T::BlockNumber,
+      current_block: T::BlockNumber,
+    ) -> Balance {
+      let elapsed = current_block.saturating_sub(usage.updated_at);
+      if elapsed >= period {
+        return Zero::zero();
+      }
+      let released = Perquintill::from_rational(
+        UniqueSaturatedInto::<u128>::unique_saturated_into(elapsed),
+        UniqueSaturatedInto::<u128>::unique_saturated_into(period),
+      ) * cap;
+      usage.amount.saturating_sub(released)
+    }
   }
 
   impl<T: Config> SecurityExt<T::AccountId, T::BlockNumber> for Pallet<T> {
@@ -183,4 +581,77 @@ pub mod pallet {
       Self::get_next_id(&account_id)
     }
   }
+
+  impl<T: Config> SubsystemsStatusExt for Pallet<T> {
+    fn is_subsystem_running(subsystem: Subsystem) -> bool {
+      Self::is_chain_running() && !Self::is_subsystem_paused(subsystem)
+    }
+    fn is_currency_running(currency_id: CurrencyId) -> bool {
+      Self::is_chain_running() && !Self::is_currency_paused(currency_id)
+    }
+  }
+
+  impl<T: Config> ExtrinsicIdExt for Pallet<T> {
+    fn get_extrinsic_unique_id() -> Option<Hash> {
+      ExtrinsicSigner::<T>::mutate(|extrinsic_signer| {
+        extrinsic_signer.as_mut().map(|(signer, nonce, index)| {
+          let unique_id = Self::extrinsic_unique_id(signer, *nonce, *index);
+          *index = index.saturating_add(1);
+          unique_id
+        })
+      })
+    }
+  }
+
+  impl<T: Config> RateLimitsExt<T::AccountId> for Pallet<T> {
+    fn try_consume_rate_limit(
+      subsystem: Subsystem,
+      account_id: &T::AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+    ) -> bool {
+      let rate_limit = match Self::rate_limit(subsystem, currency_id) {
+        Some(rate_limit) => rate_limit,
+        // no rate limit for this currency
+        None => return true,
+      };
+      let current_block = frame_system::Pallet::<T>::block_number();
+
+      let global_amount = Self::rate_limit_usage_at(
+        &Self::global_rate_limit_usage(subsystem, currency_id),
+        rate_limit.global_cap,
+        rate_limit.period,
+        current_block,
+      )
+      .saturating_add(amount);
+      let account_amount = Self::rate_limit_usage_at(
+        &Self::account_rate_limit_usage((subsystem, currency_id, account_id)),
+        rate_limit.account_cap,
+        rate_limit.period,
+        current_block,
+      )
+      .saturating_add(amount);
+
+      if global_amount > rate_limit.global_cap || account_amount > rate_limit.account_cap {
+        return false;
+      }
+
+      GlobalRateLimitUsage::<T>::insert(
+        subsystem,
+        currency_id,
+        RateLimitUsage {
+          amount: global_amount,
+          updated_at: current_block,
+        },
+      );
+      AccountRateLimitUsage::<T>::insert(
+        (subsystem, currency_id, account_id),
+        RateLimitUsage {
+          amount: account_amount,
+          updated_at: current_block,
+        },
+      );
+      true
+    }
+  }
 }
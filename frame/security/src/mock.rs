@@ -18,13 +18,13 @@ use crate::pallet as pallet_security;
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_benchmarking::frame_support::traits::tokens::{DepositConsequence, WithdrawConsequence};
 use frame_support::{
-  parameter_types,
+  ord_parameter_types, parameter_types,
   traits::{
     fungible::{
       Inspect as FungibleInspect, Mutate as FungibleMutate, Transfer as FungibleTransfer,
     },
     fungibles::{Inspect, Mutate, Transfer},
-    ConstU128, ConstU32,
+    ConstU128, ConstU32, ConstU64,
   },
   PalletId,
 };
@@ -38,7 +38,7 @@ use sp_runtime::{
   DispatchError, DispatchResult, RuntimeDebug,
 };
 use std::marker::PhantomData;
-use system::EnsureRoot;
+use system::{EnsureRoot, EnsureSignedBy};
 use tidefi_primitives::CurrencyId;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -165,6 +165,11 @@ impl pallet_balances::Config for Test {
 
 impl pallet_security::Config for Test {
   type Event = Event;
+  type PauseOrigin = EnsureRoot<Self::AccountId>;
+  type EmergencyOrigin = EnsureSignedBy<Guardian, Self::AccountId>;
+  type EmergencyInitiator = Self::AccountId;
+  type EmergencyPauseDuration = ConstU64<100>;
+  type PauseReasonLimit = ConstU32<32>;
   type WeightInfo = crate::weights::SubstrateWeight<Test>;
 }
 
@@ -172,6 +177,10 @@ parameter_types! {
   pub const TidefiPalletId: PalletId = PalletId(*b"wrpr*pal");
 }
 
+ord_parameter_types! {
+  pub const Guardian: AccountId = AccountId(2);
+}
+
 // this is only the mock for benchmarking, it's implemented directly in the runtime
 pub struct Adapter<AccountId> {
   phantom: PhantomData<AccountId>,
@@ -14,9 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::mock::{new_test_ext, Origin, Security};
-use frame_support::{assert_noop, assert_ok, error::BadOrigin};
-use tidefi_primitives::StatusCode;
+use crate::{
+  mock::{new_test_ext, Origin, Security, System},
+  Error, ExtrinsicIdExt, RateLimit, RateLimitsExt, Subsystem, SubsystemsStatusExt,
+  EXTRINSIC_ID_PREFIX,
+};
+use frame_support::{assert_noop, assert_ok, error::BadOrigin, traits::Hooks};
+use tidefi_primitives::{CurrencyId, StatusCode};
 
 #[test]
 pub fn check_genesis_config() {
@@ -56,3 +60,483 @@ mod set_status {
     });
   }
 }
+
+mod set_subsystem_status {
+  use super::*;
+
+  #[test]
+  fn succeeds() {
+    new_test_ext().execute_with(|| {
+      assert!(Security::is_subsystem_running(Subsystem::Swaps));
+      assert_ok!(Security::set_subsystem_status(
+        Origin::root(),
+        Subsystem::Swaps,
+        true
+      ));
+      assert!(!Security::is_subsystem_running(Subsystem::Swaps));
+      // the other subsystems keep running
+      assert!(Security::is_subsystem_running(Subsystem::Withdrawals));
+
+      assert_ok!(Security::set_subsystem_status(
+        Origin::root(),
+        Subsystem::Swaps,
+        false
+      ));
+      assert!(Security::is_subsystem_running(Subsystem::Swaps));
+    });
+  }
+
+  #[test]
+  fn chain_status_takes_precedence() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Security::set_status(
+        Origin::root(),
+        StatusCode::Maintenance
+      ));
+      assert!(!Security::is_subsystem_running(Subsystem::Deposits));
+      assert!(!Security::is_currency_running(CurrencyId::Tdfy));
+    });
+  }
+
+  #[test]
+  fn fails_when_signer_is_not_pause_origin() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Security::set_subsystem_status(Origin::signed(1.into()), Subsystem::Swaps, true),
+        BadOrigin
+      );
+    });
+  }
+}
+
+mod set_currency_status {
+  use super::*;
+
+  #[test]
+  fn succeeds() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Security::set_currency_status(
+        Origin::root(),
+        CurrencyId::Wrapped(2),
+        true
+      ));
+      assert!(!Security::is_currency_running(CurrencyId::Wrapped(2)));
+      assert!(Security::is_currency_running(CurrencyId::Wrapped(4)));
+
+      assert_ok!(Security::set_currency_status(
+        Origin::root(),
+        CurrencyId::Wrapped(2),
+        false
+      ));
+      assert!(Security::is_currency_running(CurrencyId::Wrapped(2)));
+    });
+  }
+
+  #[test]
+  fn fails_when_signer_is_not_pause_origin() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Security::set_currency_status(Origin::signed(1.into()), CurrencyId::Wrapped(2), true),
+        BadOrigin
+      );
+    });
+  }
+}
+
+mod emergency_pause {
+  use super::*;
+
+  const GUARDIAN: u64 = 2;
+
+  #[test]
+  fn succeeds() {
+    new_test_ext().execute_with(|| {
+      System::set_block_number(10);
+      assert_ok!(Security::emergency_pause(
+        Origin::signed(GUARDIAN.into()),
+        b"bridge incident".to_vec()
+      ));
+      assert_eq!(Security::status(), StatusCode::Maintenance);
+
+      let emergency_pause = Security::emergency_pause().unwrap();
+      assert_eq!(emergency_pause.initiator, GUARDIAN.into());
+      assert_eq!(emergency_pause.reason.to_vec(), b"bridge incident".to_vec());
+      assert_eq!(emergency_pause.expires_at, 110);
+    });
+  }
+
+  #[test]
+  fn lapses_after_duration() {
+    new_test_ext().execute_with(|| {
+      System::set_block_number(10);
+      assert_ok!(Security::emergency_pause(
+        Origin::signed(GUARDIAN.into()),
+        Vec::new()
+      ));
+
+      Security::on_initialize(109);
+      assert_eq!(Security::status(), StatusCode::Maintenance);
+
+      Security::on_initialize(110);
+      assert_eq!(Security::status(), StatusCode::Running);
+      assert!(Security::emergency_pause().is_none());
+    });
+  }
+
+  #[test]
+  fn can_be_extended_by_root() {
+    new_test_ext().execute_with(|| {
+      System::set_block_number(10);
+      assert_ok!(Security::emergency_pause(
+        Origin::signed(GUARDIAN.into()),
+        Vec::new()
+      ));
+      assert_ok!(Security::extend_emergency_pause(Origin::root(), 50));
+      assert_eq!(Security::emergency_pause().unwrap().expires_at, 160);
+
+      Security::on_initialize(110);
+      assert_eq!(Security::status(), StatusCode::Maintenance);
+      Security::on_initialize(160);
+      assert_eq!(Security::status(), StatusCode::Running);
+    });
+  }
+
+  #[test]
+  fn is_cleared_by_set_status() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Security::emergency_pause(
+        Origin::signed(GUARDIAN.into()),
+        Vec::new()
+      ));
+      assert_ok!(Security::set_status(
+        Origin::root(),
+        StatusCode::Maintenance
+      ));
+      assert!(Security::emergency_pause().is_none());
+
+      // root keeps the chain in maintenance, nothing lapses
+      Security::on_initialize(1_000);
+      assert_eq!(Security::status(), StatusCode::Maintenance);
+    });
+  }
+
+  #[test]
+  fn fails_when_signer_is_not_emergency_origin() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Security::emergency_pause(Origin::signed(1.into()), Vec::new()),
+        BadOrigin
+      );
+    });
+  }
+
+  #[test]
+  fn fails_when_reason_is_too_long() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Security::emergency_pause(Origin::signed(GUARDIAN.into()), vec![0; 33]),
+        Error::<crate::mock::Test>::PauseReasonTooLong
+      );
+    });
+  }
+
+  #[test]
+  fn fails_when_chain_is_not_running() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Security::set_status(
+        Origin::root(),
+        StatusCode::Maintenance
+      ));
+      assert_noop!(
+        Security::emergency_pause(Origin::signed(GUARDIAN.into()), Vec::new()),
+        Error::<crate::mock::Test>::ChainMaintenanceMode
+      );
+    });
+  }
+
+  #[test]
+  fn extend_fails_without_emergency_pause() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Security::extend_emergency_pause(Origin::root(), 50),
+        Error::<crate::mock::Test>::NoEmergencyPause
+      );
+    });
+  }
+
+  #[test]
+  fn extend_fails_when_signer_is_not_root() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Security::emergency_pause(
+        Origin::signed(GUARDIAN.into()),
+        Vec::new()
+      ));
+      assert_noop!(
+        Security::extend_emergency_pause(Origin::signed(GUARDIAN.into()), 50),
+        BadOrigin
+      );
+    });
+  }
+}
+
+mod set_rate_limit {
+  use super::*;
+
+  #[test]
+  fn succeeds() {
+    new_test_ext().execute_with(|| {
+      let rate_limit = RateLimit {
+        period: 100,
+        global_cap: 1_000,
+        account_cap: 100,
+      };
+      assert_ok!(Security::set_rate_limit(
+        Origin::root(),
+        Subsystem::Withdrawals,
+        CurrencyId::Wrapped(2),
+        Some(rate_limit.clone())
+      ));
+      assert_eq!(
+        Security::rate_limit(Subsystem::Withdrawals, CurrencyId::Wrapped(2)),
+        Some(rate_limit)
+      );
+
+      assert_ok!(Security::set_rate_limit(
+        Origin::root(),
+        Subsystem::Withdrawals,
+        CurrencyId::Wrapped(2),
+        None
+      ));
+      assert!(Security::rate_limit(Subsystem::Withdrawals, CurrencyId::Wrapped(2)).is_none());
+    });
+  }
+
+  #[test]
+  fn fails_when_period_is_zero() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Security::set_rate_limit(
+          Origin::root(),
+          Subsystem::Withdrawals,
+          CurrencyId::Wrapped(2),
+          Some(RateLimit {
+            period: 0,
+            global_cap: 1_000,
+            account_cap: 100,
+          })
+        ),
+        Error::<crate::mock::Test>::InvalidRateLimitPeriod
+      );
+    });
+  }
+
+  #[test]
+  fn fails_when_signer_is_not_pause_origin() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Security::set_rate_limit(
+          Origin::signed(1.into()),
+          Subsystem::Withdrawals,
+          CurrencyId::Wrapped(2),
+          None
+        ),
+        BadOrigin
+      );
+    });
+  }
+}
+
+mod try_consume_rate_limit {
+  use super::*;
+
+  const ALICE: u64 = 1;
+  const BOB: u64 = 2;
+  const CHARLIE: u64 = 3;
+
+  fn set_withdrawals_rate_limit() {
+    assert_ok!(Security::set_rate_limit(
+      Origin::root(),
+      Subsystem::Withdrawals,
+      CurrencyId::Wrapped(2),
+      Some(RateLimit {
+        period: 100,
+        global_cap: 1_000,
+        account_cap: 500,
+      })
+    ));
+  }
+
+  fn consume(account: u64, amount: u128) -> bool {
+    Security::try_consume_rate_limit(
+      Subsystem::Withdrawals,
+      &account.into(),
+      CurrencyId::Wrapped(2),
+      amount,
+    )
+  }
+
+  #[test]
+  fn succeeds_without_rate_limit() {
+    new_test_ext().execute_with(|| {
+      assert!(consume(ALICE, u128::MAX));
+    });
+  }
+
+  #[test]
+  fn rejects_above_account_cap() {
+    new_test_ext().execute_with(|| {
+      set_withdrawals_rate_limit();
+      assert!(consume(ALICE, 400));
+      assert!(!consume(ALICE, 101));
+      assert!(consume(ALICE, 100));
+      // other accounts have their own cap
+      assert!(consume(BOB, 500));
+      // other subsystems are not limited
+      assert!(Security::try_consume_rate_limit(
+        Subsystem::Deposits,
+        &ALICE.into(),
+        CurrencyId::Wrapped(2),
+        1_000,
+      ));
+    });
+  }
+
+  #[test]
+  fn rejects_above_global_cap() {
+    new_test_ext().execute_with(|| {
+      set_withdrawals_rate_limit();
+      assert!(consume(ALICE, 500));
+      assert!(consume(BOB, 400));
+      assert!(!consume(CHARLIE, 101));
+      assert!(consume(CHARLIE, 100));
+    });
+  }
+
+  #[test]
+  fn releases_usage_over_the_period() {
+    new_test_ext().execute_with(|| {
+      System::set_block_number(1);
+      set_withdrawals_rate_limit();
+      assert!(consume(ALICE, 500));
+      assert!(!consume(ALICE, 1));
+
+      // half of the account cap is released
+      System::set_block_number(51);
+      assert!(!consume(ALICE, 251));
+      assert!(consume(ALICE, 250));
+
+      // everything is released after the period
+      System::set_block_number(151);
+      assert!(consume(ALICE, 500));
+    });
+  }
+}
+
+mod get_extrinsic_unique_id {
+  use super::*;
+  use crate::{
+    mock::{AccountId, Call, Test},
+    CheckExtrinsicSigner,
+  };
+  use codec::Encode;
+  use frame_support::weights::{DispatchInfo, PostDispatchInfo};
+  use sp_core::blake2_256;
+  use sp_runtime::traits::SignedExtension;
+
+  const ALICE: u64 = 1;
+  const BOB: u64 = 2;
+
+  fn remark() -> Call {
+    Call::System(frame_system::Call::remark { remark: Vec::new() })
+  }
+
+  // the nonce is incremented by `CheckNonce` before `CheckExtrinsicSigner`
+  fn pre_dispatch(signer: &AccountId) {
+    System::inc_account_nonce(signer);
+    assert_ok!(CheckExtrinsicSigner::<Test>::new().pre_dispatch(
+      signer,
+      &remark(),
+      &DispatchInfo::default(),
+      0
+    ));
+  }
+
+  fn post_dispatch() {
+    assert_ok!(CheckExtrinsicSigner::<Test>::post_dispatch(
+      Some(()),
+      &DispatchInfo::default(),
+      &PostDispatchInfo::default(),
+      0,
+      &Ok(())
+    ));
+  }
+
+  #[test]
+  fn is_derived_from_the_signed_extrinsic() {
+    new_test_ext().execute_with(|| {
+      let alice: AccountId = ALICE.into();
+      for _ in 0..4 {
+        System::inc_account_nonce(&alice);
+      }
+      // fifth extrinsic, signed with the nonce 4
+      pre_dispatch(&alice);
+
+      let id = Security::get_extrinsic_unique_id().unwrap();
+      assert_eq!(id, Security::extrinsic_unique_id(&alice, 4, 0));
+      assert_eq!(
+        id.0,
+        blake2_256(&(EXTRINSIC_ID_PREFIX, &alice, 4_u64, 0_u32).encode())
+      );
+    });
+  }
+
+  #[test]
+  fn is_unique_within_the_extrinsic() {
+    new_test_ext().execute_with(|| {
+      let alice: AccountId = ALICE.into();
+      pre_dispatch(&alice);
+
+      assert_eq!(
+        Security::get_extrinsic_unique_id(),
+        Some(Security::extrinsic_unique_id(&alice, 0, 0))
+      );
+      assert_eq!(
+        Security::get_extrinsic_unique_id(),
+        Some(Security::extrinsic_unique_id(&alice, 0, 1))
+      );
+      post_dispatch();
+
+      // next extrinsic
+      pre_dispatch(&alice);
+      assert_eq!(
+        Security::get_extrinsic_unique_id(),
+        Some(Security::extrinsic_unique_id(&alice, 1, 0))
+      );
+    });
+  }
+
+  #[test]
+  fn is_unique_per_signer() {
+    new_test_ext().execute_with(|| {
+      pre_dispatch(&ALICE.into());
+      let alice_id = Security::get_extrinsic_unique_id();
+      post_dispatch();
+
+      pre_dispatch(&BOB.into());
+      assert_ne!(alice_id, Security::get_extrinsic_unique_id());
+    });
+  }
+
+  #[test]
+  fn is_none_outside_of_a_signed_extrinsic() {
+    new_test_ext().execute_with(|| {
+      // hooks, inherents and unsigned extrinsics
+      assert_eq!(Security::get_extrinsic_unique_id(), None);
+
+      pre_dispatch(&ALICE.into());
+      post_dispatch();
+      assert_eq!(Security::extrinsic_signer(), None);
+      assert_eq!(Security::get_extrinsic_unique_id(), None);
+    });
+  }
+}
@@ -24,6 +24,11 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for `pallet_tidefi`.
 pub trait WeightInfo {
    fn set_status() -> Weight;
+   fn set_subsystem_status() -> Weight;
+   fn set_currency_status() -> Weight;
+   fn emergency_pause() -> Weight;
+   fn extend_emergency_pause() -> Weight;
+   fn set_rate_limit() -> Weight;
 }
 
 /// Weights for `pallet_tidefi` using the Substrate node and recommended hardware.
@@ -34,4 +39,26 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
           .saturating_add(T::DbWeight::get().reads(6_u64))
           .saturating_add(T::DbWeight::get().writes(5_u64))
   }
+   fn set_subsystem_status() -> Weight {
+      16_878_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn set_currency_status() -> Weight {
+      16_878_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn emergency_pause() -> Weight {
+      20_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(1_u64))
+          .saturating_add(T::DbWeight::get().writes(2_u64))
+  }
+   fn extend_emergency_pause() -> Weight {
+      18_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(1_u64))
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn set_rate_limit() -> Weight {
+      17_000_000_u64
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
 }
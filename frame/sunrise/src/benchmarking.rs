@@ -0,0 +1,84 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+//! Benchmarking setup for pallet-sunrise
+use crate::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::{
+  traits::{fungibles::Mutate, ConstU32},
+  BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_runtime::FixedU128;
+use sp_std::vec::Vec;
+use tidefi_primitives::{pallet::SunriseExt, Balance, CurrencyId, SunriseSwapPool};
+
+const ONE_TDFY: Balance = 1_000_000_000_000;
+
+// Fund the sunrise account and make sure there is a pool available, with `pools` existing pools
+fn setup_pools<T: Config>(pools: u8) {
+  T::CurrencyTidefi::mint_into(
+    CurrencyId::Tdfy,
+    &<Pallet<T> as SunriseExt<T::AccountId, T::BlockNumber>>::account_id(),
+    100_000_000 * ONE_TDFY,
+  )
+  .expect("Unable to fund the sunrise account");
+
+  let bounded_pools: BoundedVec<SunriseSwapPool, ConstU32<6>> = (1..=pools)
+    .map(|id| SunriseSwapPool {
+      id,
+      minimum_tdfy_value: id as Balance * 100 * ONE_TDFY,
+      transactions_remaining: 1_000,
+      balance: 1_000 * ONE_TDFY,
+      rebates: FixedU128::from(1),
+    })
+    .collect::<Vec<_>>()
+    .try_into()
+    .expect("Too many pools");
+  Pools::<T>::put(bounded_pools);
+}
+
+benchmarks! {
+   create_pool {
+      setup_pools::<T>(5);
+   }: _(RawOrigin::Root, 6, 600 * ONE_TDFY, 1_000, 1_000 * ONE_TDFY, FixedU128::from(1))
+
+   top_up_pool {
+      setup_pools::<T>(6);
+   }: _(RawOrigin::Root, 6, 1_000 * ONE_TDFY)
+
+   modify_pool {
+      setup_pools::<T>(6);
+   }: _(RawOrigin::Root, 6, Some(700 * ONE_TDFY), Some(2_000), Some(FixedU128::from(2)))
+
+   close_pool {
+      setup_pools::<T>(6);
+   }: _(RawOrigin::Root, 6)
+
+   set_onboarding_rebates {
+      setup_pools::<T>(6);
+   }: _(RawOrigin::Root, 1_000_000 * ONE_TDFY, 1_000_000 * ONE_TDFY)
+
+   sweep_pools_left_over {
+      setup_pools::<T>(6);
+      PoolsLeftOverBalance::<T>::put(1_000 * ONE_TDFY);
+      let destination: T::AccountId = account("destination", 0, 0);
+   }: _(RawOrigin::Root, destination, 1_000 * ONE_TDFY)
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -16,12 +16,20 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod migrations;
+
 #[cfg(test)]
 mod mock;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::*;
+
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
 
@@ -51,9 +59,11 @@ pub mod pallet {
     },
     PalletId,
   };
-  use sp_arithmetic::traits::Zero;
+  use frame_system::pallet_prelude::*;
+  use pallet_vesting::{VestedTransfer, VestingSchedule};
+  use sp_arithmetic::traits::{One, Zero};
   use sp_runtime::{
-    traits::{AccountIdConversion, CheckedMul, Saturating},
+    traits::{AccountIdConversion, CheckedMul, Hash, Saturating},
     FixedPointNumber, FixedU128,
   };
   use sp_std::borrow::ToOwned;
@@ -64,7 +74,84 @@ pub mod pallet {
   };
 
   /// The current storage version.
-  const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+  const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
+  /// Interface used by other pallets to replenish the sunrise pools.
+  pub trait SunrisePoolsFunding {
+    /// Add `amount` TDFY, already transferred to the sunrise account, to the pools left-over.
+    fn replenish_pools_left_over(amount: Balance);
+  }
+
+  /// Interface used by other pallets to claim the sunrise rewards of several eras at once.
+  pub trait SunriseRewardsClaim<AccountId> {
+    /// Claim the rewards of the account for up to `max_eras` eras, the oldest first, until
+    /// `last_claimable_era`. The rewards are paid as a lump sum, without vesting schedule,
+    /// unless `vest_rewards` is set.
+    ///
+    /// Returns the number of eras scanned, at most `max_scanned_eras`, the number of eras
+    /// claimed and the total rewards claimed.
+    fn try_claim_all_sunrise_rewards(
+      account_id: &AccountId,
+      last_claimable_era: EraIndex,
+      max_eras: u32,
+      vest_rewards: bool,
+    ) -> Result<(u32, u32, Balance), DispatchError>;
+
+    /// Maximum number of eras scanned by `try_claim_all_sunrise_rewards`, the unexpired eras.
+    fn max_scanned_eras() -> u32;
+
+    /// Unclaimed and unexpired rewards of the account, sorted by era.
+    fn account_sunrise_rewards(account_id: &AccountId) -> Vec<(EraIndex, Balance)>;
+
+    /// Whether the rewards of the account for the era have to wait for the `Cooldown`, as they
+    /// can't be released through a vesting schedule.
+    fn is_subject_to_cooldown(account_id: &AccountId, era_index: EraIndex) -> bool;
+
+    /// Last era the rewards of `era_index` can be claimed before they expire.
+    fn last_claimable_era(era_index: EraIndex) -> EraIndex;
+  }
+
+  /// Interface used by the oracle to filter out the wash trading from the sunrise rewards.
+  pub trait SunriseSwapRewards<AccountId> {
+    /// Whether the swap fees paid by `account_id`, selling `currency_id` to `counterparty`, can
+    /// be rewarded. Self-matched trades and assets sold before the end of their holding period
+    /// are not rewarded.
+    fn is_trade_rewardable(
+      account_id: &AccountId,
+      counterparty: &AccountId,
+      currency_id: CurrencyId,
+    ) -> bool;
+
+    /// Start the holding period of `currency_id`, acquired by `account_id` through a swap.
+    fn register_swap_acquisition(account_id: &AccountId, currency_id: CurrencyId);
+  }
+
+  /// Interface used by the quorum to refund the gas of the deposits once.
+  pub trait SunriseOnboardingRefund<AccountId> {
+    /// Refund the gas paid for the deposit `transaction_id`, within the onboarding allowance of
+    /// the account. Each deposit can only be refunded once.
+    fn try_refund_gas_for_mint(
+      account_id: &AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+      transaction_id: &[u8],
+    ) -> Result<Option<Balance>, DispatchError>;
+
+    /// Onboarding rebates, in TDFY, the account can still be refunded.
+    fn remaining_onboarding_allowance(account_id: &AccountId) -> Balance;
+  }
+
+  /// Accounts controlled by the same owner, the trades between them are not rewarded.
+  pub trait LinkedAccounts<AccountId> {
+    /// Whether `account_id` and `other` are controlled by the same owner.
+    fn are_linked(account_id: &AccountId, other: &AccountId) -> bool;
+  }
+
+  impl<AccountId> LinkedAccounts<AccountId> for () {
+    fn are_linked(_account_id: &AccountId, _other: &AccountId) -> bool {
+      false
+    }
+  }
 
   type BoundedPools = BoundedVec<SunriseSwapPool, ConstU32<6>>;
 
@@ -92,6 +179,22 @@ pub mod pallet {
     #[pallet::constant]
     type LeftoverSwapRebates: Get<FixedU128>;
 
+    /// Maximum sunrise rewards an account can be allocated for its swaps of an era
+    #[pallet::constant]
+    type MaximumRewardPerAccountPerEra: Get<Balance>;
+
+    /// Number of blocks an asset acquired through a swap has to be held before its sale can be
+    /// rewarded
+    #[pallet::constant]
+    type MinimumHoldingPeriod: Get<Self::BlockNumber>;
+
+    /// Accounts controlled by the same owner, their trades together are not rewarded
+    type LinkedAccounts: LinkedAccounts<Self::AccountId>;
+
+    /// Maximum onboarding rebates, in TDFY, an account can be refunded over its lifetime
+    #[pallet::constant]
+    type MaximumOnboardingRefundPerAccount: Get<Balance>;
+
     /// Security traits
     type Security: SecurityExt<Self::AccountId, Self::BlockNumber>;
 
@@ -99,6 +202,37 @@ pub mod pallet {
     type CurrencyTidefi: Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
       + Mutate<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
       + Transfer<Self::AccountId, AssetId = CurrencyId, Balance = Balance>;
+
+    /// The active fees era, used to expire the unclaimed sunrise rewards
+    type CurrentEra: Get<Option<EraIndex>>;
+
+    /// Number of eras, after the rewarded era, the sunrise rewards can be claimed before they
+    /// expire. Should cover the `Cooldown`.
+    #[pallet::constant]
+    type RewardsClaimWindow: Get<EraIndex>;
+
+    /// Number of eras before their expiry the users are warned about their unclaimed rewards
+    #[pallet::constant]
+    type RewardsExpiryNotice: Get<EraIndex>;
+
+    /// Destination of the expired sunrise rewards, returned to the pools left-over when `None`
+    type ExpiredRewardsDestination: Get<Option<Self::AccountId>>;
+
+    /// Vesting of the claimed sunrise rewards as `(period, period_count)`, released linearly over
+    /// `period_count` periods of `period` blocks. When set, the rewards of the previous era can
+    /// be claimed without waiting for the `Cooldown`, unless they are below the minimum vested
+    /// transfer or the account has no vesting schedule left. They are paid as a lump sum after
+    /// the `Cooldown` otherwise.
+    type RewardsVesting: Get<Option<(Self::BlockNumber, u32)>>;
+
+    /// Vested transfers of the claimed sunrise rewards
+    type Vesting: VestedTransfer<Self::AccountId, Self::BlockNumber, Balance>;
+
+    /// The origin which may manage the sunrise pools and the onboarding rebates
+    type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
   }
 
   #[pallet::pallet]
@@ -116,11 +250,28 @@ pub mod pallet {
   #[pallet::getter(fn pools_left_over)]
   pub type PoolsLeftOverBalance<T: Config> = StorageValue<_, Balance, ValueQuery>;
 
+  /// The sunrise rewards allocated to the accounts and not claimed yet.
+  #[pallet::storage]
+  #[pallet::getter(fn pending_rewards)]
+  pub type PendingRewards<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
   /// The active onboarding rebates (gas refunds on-deposit)
   #[pallet::storage]
   #[pallet::getter(fn onboarding)]
   pub type Onboarding<T: Config> = StorageValue<_, OnboardingRebates, ValueQuery>;
 
+  /// Onboarding rebates refunded to each account
+  #[pallet::storage]
+  #[pallet::getter(fn onboarding_refunded)]
+  pub type OnboardingRefunded<T: Config> =
+    StorageMap<_, Blake2_128Concat, T::AccountId, Balance, ValueQuery>;
+
+  /// Onboarding rebates refunded for each deposit, by hash of the mint transaction id
+  #[pallet::storage]
+  #[pallet::getter(fn deposit_refund)]
+  pub type DepositRefunds<T: Config> =
+    StorageMap<_, Blake2_128Concat, T::Hash, (T::AccountId, Balance), OptionQuery>;
+
   /// TDFY price of each wrapped asset, reported by Oracle every X blocks.
   ///
   /// Exchange rate for 1 `AssetId` vs 1 TDFY
@@ -142,6 +293,58 @@ pub mod pallet {
     ValueQuery,
   >;
 
+  /// Accounts with unclaimed sunrise rewards, by era
+  #[pallet::storage]
+  pub type RewardsByEra<T: Config> = StorageDoubleMap<
+    _,
+    Blake2_128Concat,
+    EraIndex,
+    Blake2_128Concat,
+    T::AccountId,
+    (),
+    OptionQuery,
+  >;
+
+  /// Oldest era which may still hold unclaimed sunrise rewards
+  #[pallet::storage]
+  #[pallet::getter(fn oldest_claimable_era)]
+  pub type OldestClaimableEra<T: Config> = StorageValue<_, EraIndex, ValueQuery>;
+
+  /// Last era the sunrise rewards expiry notice has been processed
+  #[pallet::storage]
+  #[pallet::getter(fn last_expiry_notice_era)]
+  pub type LastExpiryNoticeEra<T: Config> = StorageValue<_, EraIndex, ValueQuery>;
+
+  /// Last block each account acquired an asset through a swap, starting its holding period
+  #[pallet::storage]
+  #[pallet::getter(fn swap_acquisition)]
+  pub type SwapAcquisitions<T: Config> = StorageDoubleMap<
+    _,
+    Blake2_128Concat,
+    T::AccountId,
+    Blake2_128Concat,
+    CurrencyId,
+    T::BlockNumber,
+    OptionQuery,
+  >;
+
+  /// Swap acquisitions by block, pruned once their holding period is over
+  #[pallet::storage]
+  pub type SwapAcquisitionsByBlock<T: Config> = StorageDoubleMap<
+    _,
+    Blake2_128Concat,
+    T::BlockNumber,
+    Blake2_128Concat,
+    (T::AccountId, CurrencyId),
+    (),
+    OptionQuery,
+  >;
+
+  /// Oldest block which may still hold swap acquisitions in their holding period
+  #[pallet::storage]
+  #[pallet::getter(fn oldest_swap_acquisition_block)]
+  pub type OldestSwapAcquisitionBlock<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
   /// Genesis configuration
   #[pallet::genesis_config]
   pub struct GenesisConfig<T: Config> {
@@ -206,6 +409,43 @@ pub mod pallet {
       initial_amount: Balance,
       rebate: Balance,
     },
+    /// The pools left-over has been replenished
+    PoolsLeftOverReplenished { amount: Balance },
+    /// A sunrise pool has been created
+    SunrisePoolCreated { pool_id: u8, balance: Balance },
+    /// A sunrise pool has been topped-up
+    SunrisePoolToppedUp { pool_id: u8, amount: Balance },
+    /// A sunrise pool has been updated
+    SunrisePoolUpdated { pool_id: u8 },
+    /// A sunrise pool has been closed, its remaining balance moved to the pools left-over
+    SunrisePoolClosed { pool_id: u8, left_over: Balance },
+    /// The onboarding rebates have been updated
+    OnboardingRebatesUpdated {
+      initial_amount: Balance,
+      available_amount: Balance,
+    },
+    /// The pools left-over has been swept
+    PoolsLeftOverSwept {
+      destination: T::AccountId,
+      amount: Balance,
+    },
+    /// The unclaimed sunrise rewards of an era expire after `last_claimable_era`
+    SunriseRewardsExpiringSoon {
+      era_index: EraIndex,
+      last_claimable_era: EraIndex,
+    },
+    /// An unclaimed sunrise reward has expired
+    SunriseRewardsExpired {
+      era_index: EraIndex,
+      account_id: T::AccountId,
+      reward: Balance,
+    },
+    /// The expired sunrise rewards have been reclaimed, to the pools left-over when the
+    /// destination is `None`
+    ExpiredRewardsReclaimed {
+      destination: Option<T::AccountId>,
+      amount: Balance,
+    },
   }
 
   // Errors inform users that something went wrong.
@@ -223,9 +463,589 @@ pub mod pallet {
     NoRewardsAvailable,
     /// There is no rebates available to process the gas refund
     NoRebatesAvailable,
+    /// A sunrise pool with the same id already exists
+    SunrisePoolAlreadyExists,
+    /// Too many sunrise pools
+    TooManySunrisePools,
+    /// The sunrise account doesn't hold enough TDFY for the pools, the left-over, the onboarding
+    /// rebates and the pending rewards
+    InsufficientSunriseFunds,
+    /// The pools left-over is lower than the amount requested
+    InsufficientPoolsLeftOver,
+    /// The available onboarding rebates exceed the initial amount
+    InvalidOnboardingRebates,
+    /// The claim window of the era is over, the rewards have expired
+    RewardsExpired,
+    /// The gas of the deposit has already been refunded
+    DepositAlreadyRefunded,
   }
 
+  // hooks
+  #[pallet::hooks]
+  impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+    fn on_runtime_upgrade() -> Weight {
+      migrations::migrate_to_v2::<T, Self>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade() -> Result<(), &'static str> {
+      Self::check_pending_rewards()
+    }
+
+    /// Warn about the sunrise rewards about to expire
+    fn on_initialize(_n: T::BlockNumber) -> Weight {
+      Self::notify_expiring_rewards()
+    }
+
+    /// Reclaim the expired sunrise rewards and prune the swap acquisitions out of their holding
+    /// period when chain is idle
+    fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+      let consumed_weight = Self::expire_rewards(remaining_weight);
+      consumed_weight.saturating_add(Self::prune_swap_acquisitions(
+        remaining_weight.saturating_sub(consumed_weight),
+      ))
+    }
+  }
+
+  #[pallet::call]
   impl<T: Config> Pallet<T> {
+    /// Create a sunrise pool.
+    ///
+    /// - `id`: Unique pool id
+    /// - `minimum_tdfy_value`: Minimum trade value, in TDFY, eligible to the pool
+    /// - `transactions_remaining`: Number of swaps rewarded by the pool
+    /// - `balance`: Rewards available in the pool, the sunrise account should hold them
+    /// - `rebates`: Rebates applied on the fees paid
+    ///
+    /// Emits `SunrisePoolCreated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::create_pool())]
+    pub fn create_pool(
+      origin: OriginFor<T>,
+      id: u8,
+      minimum_tdfy_value: Balance,
+      transactions_remaining: u32,
+      balance: Balance,
+      rebates: FixedU128,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+
+      let mut pools = Self::sunrise_pools();
+      ensure!(
+        !pools.iter().any(|pool| pool.id == id),
+        Error::<T>::SunrisePoolAlreadyExists
+      );
+      pools
+        .try_push(SunriseSwapPool {
+          id,
+          minimum_tdfy_value,
+          transactions_remaining,
+          balance,
+          rebates,
+        })
+        .map_err(|_| Error::<T>::TooManySunrisePools)?;
+      Self::ensure_sunrise_funds(&pools, Self::pools_left_over(), &Self::onboarding())?;
+
+      Pools::<T>::put(pools);
+      Self::deposit_event(Event::<T>::SunrisePoolCreated {
+        pool_id: id,
+        balance,
+      });
+
+      Ok(().into())
+    }
+
+    /// Add `amount` TDFY to the rewards of a sunrise pool, the sunrise account should hold them.
+    ///
+    /// Emits `SunrisePoolToppedUp` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::top_up_pool())]
+    pub fn top_up_pool(
+      origin: OriginFor<T>,
+      pool_id: u8,
+      amount: Balance,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+
+      let mut pools = Self::sunrise_pools();
+      let pool = pools
+        .iter_mut()
+        .find(|pool| pool.id == pool_id)
+        .ok_or(Error::<T>::InvalidSunrisePool)?;
+      pool.balance = pool
+        .balance
+        .checked_add(amount)
+        .ok_or(Error::<T>::BalanceOverflow)?;
+      Self::ensure_sunrise_funds(&pools, Self::pools_left_over(), &Self::onboarding())?;
+
+      Pools::<T>::put(pools);
+      Self::deposit_event(Event::<T>::SunrisePoolToppedUp { pool_id, amount });
+
+      Ok(().into())
+    }
+
+    /// Update the eligibility and the rebates of a sunrise pool, `None` keep the current value.
+    ///
+    /// Emits `SunrisePoolUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::modify_pool())]
+    pub fn modify_pool(
+      origin: OriginFor<T>,
+      pool_id: u8,
+      minimum_tdfy_value: Option<Balance>,
+      transactions_remaining: Option<u32>,
+      rebates: Option<FixedU128>,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+
+      Pools::<T>::try_mutate(|pools| -> DispatchResult {
+        let pool = pools
+          .iter_mut()
+          .find(|pool| pool.id == pool_id)
+          .ok_or(Error::<T>::InvalidSunrisePool)?;
+        if let Some(minimum_tdfy_value) = minimum_tdfy_value {
+          pool.minimum_tdfy_value = minimum_tdfy_value;
+        }
+        if let Some(transactions_remaining) = transactions_remaining {
+          pool.transactions_remaining = transactions_remaining;
+        }
+        if let Some(rebates) = rebates {
+          pool.rebates = rebates;
+        }
+        Ok(())
+      })?;
+      Self::deposit_event(Event::<T>::SunrisePoolUpdated { pool_id });
+
+      Ok(().into())
+    }
+
+    /// Close a sunrise pool, its remaining balance is moved to the pools left-over.
+    ///
+    /// Emits `SunrisePoolClosed` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::close_pool())]
+    pub fn close_pool(origin: OriginFor<T>, pool_id: u8) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+
+      let mut pools = Self::sunrise_pools();
+      let position = pools
+        .iter()
+        .position(|pool| pool.id == pool_id)
+        .ok_or(Error::<T>::InvalidSunrisePool)?;
+      let left_over = pools.remove(position).balance;
+
+      Pools::<T>::put(pools);
+      PoolsLeftOverBalance::<T>::mutate(|pools_left_over| {
+        *pools_left_over = pools_left_over.saturating_add(left_over);
+      });
+      Self::deposit_event(Event::<T>::SunrisePoolClosed { pool_id, left_over });
+
+      Ok(().into())
+    }
+
+    /// Set the onboarding rebates, the sunrise account should hold the `available_amount`.
+    ///
+    /// Emits `OnboardingRebatesUpdated` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::set_onboarding_rebates())]
+    pub fn set_onboarding_rebates(
+      origin: OriginFor<T>,
+      initial_amount: Balance,
+      available_amount: Balance,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+      ensure!(
+        available_amount <= initial_amount,
+        Error::<T>::InvalidOnboardingRebates
+      );
+
+      let onboarding = OnboardingRebates {
+        initial_amount,
+        available_amount,
+      };
+      Self::ensure_sunrise_funds(&Self::sunrise_pools(), Self::pools_left_over(), &onboarding)?;
+
+      Onboarding::<T>::put(onboarding);
+      Self::deposit_event(Event::<T>::OnboardingRebatesUpdated {
+        initial_amount,
+        available_amount,
+      });
+
+      Ok(().into())
+    }
+
+    /// Transfer `amount` TDFY of the pools left-over out of the sunrise account.
+    ///
+    /// Emits `PoolsLeftOverSwept` event when successful.
+    #[pallet::weight(<T as Config>::WeightInfo::sweep_pools_left_over())]
+    pub fn sweep_pools_left_over(
+      origin: OriginFor<T>,
+      destination: T::AccountId,
+      amount: Balance,
+    ) -> DispatchResultWithPostInfo {
+      T::ForceOrigin::ensure_origin(origin)?;
+
+      PoolsLeftOverBalance::<T>::try_mutate(|pools_left_over| -> DispatchResult {
+        *pools_left_over = pools_left_over
+          .checked_sub(amount)
+          .ok_or(Error::<T>::InsufficientPoolsLeftOver)?;
+        T::CurrencyTidefi::transfer(
+          CurrencyId::Tdfy,
+          &Self::account_id(),
+          &destination,
+          amount,
+          true,
+        )?;
+        Ok(())
+      })?;
+      Self::deposit_event(Event::<T>::PoolsLeftOverSwept {
+        destination,
+        amount,
+      });
+
+      Ok(().into())
+    }
+  }
+
+  impl<T: Config> Pallet<T> {
+    // Make sure the sunrise account holds the TDFY promised by the pools, the left-over, the
+    // onboarding rebates and the pending rewards
+    pub(crate) fn ensure_sunrise_funds(
+      pools: &BoundedPools,
+      pools_left_over: Balance,
+      onboarding: &OnboardingRebates,
+    ) -> DispatchResult {
+      let promised_funds = pools.iter().fold(
+        pools_left_over
+          .saturating_add(onboarding.available_amount)
+          .saturating_add(Self::pending_rewards()),
+        |promised_funds, pool| promised_funds.saturating_add(pool.balance),
+      );
+      let available_funds =
+        T::CurrencyTidefi::reducible_balance(CurrencyId::Tdfy, &Self::account_id(), true);
+      ensure!(
+        available_funds >= promised_funds,
+        Error::<T>::InsufficientSunriseFunds
+      );
+      Ok(())
+    }
+
+    // Make sure the `PendingRewards` match the unclaimed rewards of all accounts
+    pub fn check_pending_rewards() -> Result<(), &'static str> {
+      let unclaimed_rewards =
+        Rewards::<T>::iter_values().fold(0, |total: Balance, reward| total.saturating_add(reward));
+      ensure!(
+        unclaimed_rewards == Self::pending_rewards(),
+        "Pending rewards don't match the unclaimed rewards"
+      );
+      Ok(())
+    }
+
+    // Increment the unclaimed sunrise rewards of the account for the era
+    pub(crate) fn add_reward(account_id: &T::AccountId, era_index: EraIndex, reward: Balance) {
+      Rewards::<T>::mutate(account_id, era_index, |rewards| {
+        *rewards = rewards.saturating_add(reward);
+      });
+      RewardsByEra::<T>::insert(era_index, account_id, ());
+      PendingRewards::<T>::mutate(|pending_rewards| {
+        *pending_rewards = pending_rewards.saturating_add(reward);
+      });
+    }
+
+    // Rewards the account can still be allocated for its swaps of the era
+    fn remaining_era_reward(account_id: &T::AccountId, era_index: EraIndex) -> Balance {
+      T::MaximumRewardPerAccountPerEra::get()
+        .saturating_sub(Rewards::<T>::get(account_id, era_index))
+    }
+
+    // The claim window of the era is over
+    pub(crate) fn is_expired(era_index: EraIndex) -> bool {
+      T::CurrentEra::get()
+        .map(|current_era| era_index < current_era.saturating_sub(T::RewardsClaimWindow::get()))
+        .unwrap_or(false)
+    }
+
+    // Emit the expiry notice of the era entering its last `RewardsExpiryNotice` claimable eras,
+    // once per era
+    pub(crate) fn notify_expiring_rewards() -> Weight {
+      let db_weight = T::DbWeight::get();
+      // `CurrentEra` and `LastExpiryNoticeEra`
+      let mut weight = db_weight.reads(2);
+
+      let current_era = match T::CurrentEra::get() {
+        Some(current_era) => current_era,
+        None => return weight,
+      };
+      if current_era <= Self::last_expiry_notice_era() {
+        return weight;
+      }
+
+      LastExpiryNoticeEra::<T>::put(current_era);
+      weight = weight.saturating_add(db_weight.writes(1));
+
+      let claim_window = T::RewardsClaimWindow::get();
+      if let Some(era_index) = current_era
+        .saturating_add(T::RewardsExpiryNotice::get())
+        .checked_sub(claim_window)
+      {
+        weight = weight.saturating_add(db_weight.reads(1));
+        if RewardsByEra::<T>::iter_key_prefix(era_index)
+          .next()
+          .is_some()
+        {
+          Self::deposit_event(Event::<T>::SunriseRewardsExpiringSoon {
+            era_index,
+            last_claimable_era: era_index.saturating_add(claim_window),
+          });
+        }
+      }
+
+      weight
+    }
+
+    // Remove the unclaimed rewards of the eras out of their claim window, within the
+    // `remaining_weight`, and reclaim them
+    pub(crate) fn expire_rewards(remaining_weight: Weight) -> Weight {
+      let db_weight = T::DbWeight::get();
+      // `RewardsByEra` and `Rewards` entries of an account
+      let reward_weight = db_weight.reads_writes(2, 2).max(1);
+      // scan of the `RewardsByEra` prefix of an era
+      let era_weight = db_weight.reads(1).max(1);
+      // `PendingRewards`, the reclaim and `OldestClaimableEra`
+      let settle_weight = db_weight.reads_writes(4, 5);
+      // `CurrentEra` and `OldestClaimableEra`
+      let mut consumed_weight = db_weight.reads(2);
+
+      let current_era = match T::CurrentEra::get() {
+        Some(current_era) => current_era,
+        None => return consumed_weight,
+      };
+      let expire_before = current_era.saturating_sub(T::RewardsClaimWindow::get());
+      let oldest_claimable_era = Self::oldest_claimable_era();
+      let mut oldest_era = oldest_claimable_era;
+      let mut expired_rewards: Balance = 0;
+      let available_weight = remaining_weight.saturating_sub(settle_weight);
+
+      'eras: while oldest_era < expire_before {
+        if available_weight.saturating_sub(consumed_weight)
+          < era_weight.saturating_add(reward_weight)
+        {
+          break;
+        }
+        consumed_weight = consumed_weight.saturating_add(era_weight);
+
+        let mut accounts = RewardsByEra::<T>::drain_prefix(oldest_era);
+        loop {
+          if available_weight.saturating_sub(consumed_weight) < reward_weight {
+            break 'eras;
+          }
+          let account_id = match accounts.next() {
+            Some((account_id, _)) => account_id,
+            None => break,
+          };
+          consumed_weight = consumed_weight.saturating_add(reward_weight);
+
+          let reward = Rewards::<T>::take(&account_id, oldest_era);
+          if !reward.is_zero() {
+            expired_rewards = expired_rewards.saturating_add(reward);
+            Self::deposit_event(Event::<T>::SunriseRewardsExpired {
+              era_index: oldest_era,
+              account_id,
+              reward,
+            });
+          }
+        }
+
+        oldest_era = oldest_era.saturating_add(1);
+      }
+
+      if !expired_rewards.is_zero() {
+        PendingRewards::<T>::mutate(|pending_rewards| {
+          *pending_rewards = pending_rewards.saturating_sub(expired_rewards);
+        });
+        Self::reclaim_expired_rewards(expired_rewards);
+        consumed_weight = consumed_weight.saturating_add(settle_weight);
+      }
+
+      if oldest_era != oldest_claimable_era {
+        OldestClaimableEra::<T>::put(oldest_era);
+        consumed_weight = consumed_weight.saturating_add(db_weight.writes(1));
+      }
+
+      consumed_weight
+    }
+
+    // Delete the swap acquisitions of the blocks where the holding period is over, within the
+    // `remaining_weight`, and return the weight consumed
+    pub(crate) fn prune_swap_acquisitions(remaining_weight: Weight) -> Weight {
+      let db_weight = T::DbWeight::get();
+      // `SwapAcquisitionsByBlock` and `SwapAcquisitions` entries of an acquisition
+      let acquisition_weight = db_weight.reads_writes(2, 2).max(1);
+      // scan of the `SwapAcquisitionsByBlock` prefix of a block
+      let block_weight = db_weight.reads(1).max(1);
+      // `OldestSwapAcquisitionBlock` update
+      let write_weight = db_weight.writes(1);
+      // `CurrentBlockCount` and `OldestSwapAcquisitionBlock`
+      let mut consumed_weight = db_weight.reads(2);
+
+      let prune_before =
+        T::Security::get_current_block_count().saturating_sub(T::MinimumHoldingPeriod::get());
+      let oldest_acquisition_block = Self::oldest_swap_acquisition_block();
+      let mut oldest_block = oldest_acquisition_block;
+      let available_weight = remaining_weight.saturating_sub(write_weight);
+
+      'blocks: while oldest_block < prune_before {
+        if available_weight.saturating_sub(consumed_weight)
+          < block_weight.saturating_add(acquisition_weight)
+        {
+          break;
+        }
+        consumed_weight = consumed_weight.saturating_add(block_weight);
+
+        let mut acquisitions = SwapAcquisitionsByBlock::<T>::drain_prefix(oldest_block);
+        loop {
+          if available_weight.saturating_sub(consumed_weight) < acquisition_weight {
+            break 'blocks;
+          }
+          let (account_id, currency_id) = match acquisitions.next() {
+            Some((acquisition, _)) => acquisition,
+            None => break,
+          };
+          consumed_weight = consumed_weight.saturating_add(acquisition_weight);
+
+          // a later acquisition restarted the holding period
+          if Self::swap_acquisition(&account_id, currency_id) == Some(oldest_block) {
+            SwapAcquisitions::<T>::remove(&account_id, currency_id);
+          }
+        }
+
+        oldest_block = oldest_block.saturating_add(One::one());
+      }
+
+      if oldest_block != oldest_acquisition_block {
+        OldestSwapAcquisitionBlock::<T>::put(oldest_block);
+        consumed_weight = consumed_weight.saturating_add(write_weight);
+      }
+
+      consumed_weight
+    }
+
+    // Send the expired rewards to the `ExpiredRewardsDestination`, or back to the pools left-over
+    fn reclaim_expired_rewards(amount: Balance) {
+      if let Some(destination) = T::ExpiredRewardsDestination::get() {
+        match T::CurrencyTidefi::transfer(
+          CurrencyId::Tdfy,
+          &Self::account_id(),
+          &destination,
+          amount,
+          true,
+        ) {
+          Ok(_) => {
+            Self::deposit_event(Event::<T>::ExpiredRewardsReclaimed {
+              destination: Some(destination),
+              amount,
+            });
+            return;
+          }
+          Err(err) => log!(
+            error,
+            "Unable to transfer the expired sunrise rewards: {:?}",
+            err
+          ),
+        }
+      }
+
+      PoolsLeftOverBalance::<T>::mutate(|left_over| {
+        *left_over = left_over.saturating_add(amount);
+      });
+      Self::deposit_event(Event::<T>::ExpiredRewardsReclaimed {
+        destination: None,
+        amount,
+      });
+    }
+
+    // Vesting schedule releasing the rewards of the account, when `RewardsVesting` is set, the
+    // rewards reach the minimum vested transfer and the account can take a new schedule
+    pub(crate) fn rewards_vesting_schedule(
+      account_id: &T::AccountId,
+      rewards: Balance,
+    ) -> Option<VestingSchedule<T::BlockNumber, Balance>> {
+      let (period, period_count) = T::RewardsVesting::get()?;
+      let per_period = rewards.checked_div(period_count.into()).unwrap_or_default();
+      let vested_rewards = per_period.saturating_mul(period_count.into());
+
+      if vested_rewards.is_zero()
+        || vested_rewards < T::Vesting::min_vested_transfer()
+        || !T::Vesting::can_add_vesting_schedule(account_id)
+      {
+        return None;
+      }
+
+      Some(VestingSchedule {
+        start: frame_system::Pallet::<T>::block_number(),
+        period,
+        period_count,
+        per_period,
+      })
+    }
+
+    // Pay the claimed sunrise rewards, released through a vesting schedule when `vest_rewards` is
+    // set and a schedule can be created, as a lump sum otherwise
+    pub(crate) fn pay_rewards(
+      account_id: &T::AccountId,
+      rewards: Balance,
+      vest_rewards: bool,
+    ) -> DispatchResult {
+      let sunrise_account_id = Self::account_id();
+
+      if let Some(schedule) = vest_rewards
+        .then(|| Self::rewards_vesting_schedule(account_id, rewards))
+        .flatten()
+      {
+        let vested_rewards = schedule
+          .per_period
+          .saturating_mul(schedule.period_count.into());
+        // the vested transfer doesn't keep the sunrise account alive
+        ensure!(
+          T::CurrencyTidefi::reducible_balance(CurrencyId::Tdfy, &sunrise_account_id, true)
+            >= rewards,
+          Error::<T>::InsufficientSunriseFunds
+        );
+        T::Vesting::vested_transfer(&sunrise_account_id, account_id, schedule)?;
+
+        // the rounding remainder is available right away
+        let remainder = rewards.saturating_sub(vested_rewards);
+        if !remainder.is_zero() {
+          T::CurrencyTidefi::transfer(
+            CurrencyId::Tdfy,
+            &sunrise_account_id,
+            account_id,
+            remainder,
+            true,
+          )?;
+        }
+
+        return Ok(());
+      }
+
+      T::CurrencyTidefi::transfer(
+        CurrencyId::Tdfy,
+        &sunrise_account_id,
+        account_id,
+        rewards,
+        true,
+      )?;
+      Ok(())
+    }
+
+    // Book keeping of a claimed sunrise reward, once paid
+    fn settle_claimed_reward(account_id: &T::AccountId, era_index: EraIndex, reward: Balance) {
+      Self::deposit_event(Event::<T>::SunriseClaimed {
+        era_index,
+        account_id: account_id.clone(),
+        reward,
+      });
+      PendingRewards::<T>::mutate(|pending_rewards| {
+        *pending_rewards = pending_rewards.saturating_sub(reward);
+      });
+      RewardsByEra::<T>::remove(era_index, account_id);
+    }
+
     // Based on the fee, try to select the highest matching sunrise pool
     pub(crate) fn try_select_first_eligible_sunrise_pool(
       fee: &Fee,
@@ -376,11 +1196,18 @@ pub mod pallet {
       fee: &Fee,
       currency_id: CurrencyId,
     ) -> Result<Option<Balance>, DispatchError> {
+      let remaining_era_reward = Self::remaining_era_reward(account_id, era_index);
+      if remaining_era_reward.is_zero() {
+        // the account reached its maximum rewards for the era
+        return Ok(None);
+      }
+
       if let Some(sunrise_pool_available) =
         Self::try_select_first_eligible_sunrise_pool(&fee, currency_id)?
       {
         let real_fees_in_tide_with_rebates =
-          Self::calculate_rebates_on_fees_paid(sunrise_pool_available.rebates, &fee)?;
+          Self::calculate_rebates_on_fees_paid(sunrise_pool_available.rebates, &fee)?
+            .min(remaining_era_reward);
         // Update sunrise pool
         Pools::<T>::try_mutate::<(), DispatchError, _>(|pools| {
           let sunrise_pool = pools
@@ -408,9 +1235,7 @@ pub mod pallet {
         })?;
 
         // Increment reward for the account
-        Rewards::<T>::mutate(account_id.clone(), era_index, |rewards| {
-          *rewards = rewards.saturating_add(real_fees_in_tide_with_rebates);
-        });
+        Self::add_reward(account_id, era_index, real_fees_in_tide_with_rebates);
 
         // Emit event
         Self::deposit_event(Event::<T>::SunriseRewarded {
@@ -425,13 +1250,12 @@ pub mod pallet {
         // check if we have some leftover that can be used
         let available_left_over = Self::pools_left_over();
         let real_fees_in_tide_with_rebates =
-          Self::calculate_rebates_on_fees_paid(T::LeftoverSwapRebates::get(), &fee)?;
+          Self::calculate_rebates_on_fees_paid(T::LeftoverSwapRebates::get(), &fee)?
+            .min(remaining_era_reward);
 
         if available_left_over >= real_fees_in_tide_with_rebates {
           // Increment reward for the account
-          Rewards::<T>::mutate(account_id.clone(), era_index, |rewards| {
-            *rewards = rewards.saturating_add(real_fees_in_tide_with_rebates);
-          });
+          Self::add_reward(account_id, era_index, real_fees_in_tide_with_rebates);
 
           // Reduce leftover
           PoolsLeftOverBalance::<T>::mutate(|left_over| {
@@ -460,8 +1284,9 @@ pub mod pallet {
     ) -> Result<Option<Balance>, DispatchError> {
       let amount_in_tdfy = Self::try_get_tdfy_value(currency_id, amount)?;
       Onboarding::<T>::try_mutate(|onboarging_rebates| {
-        // get the onboarding rebates
-        let rebate = Self::get_next_onboarding_rebates(amount_in_tdfy, onboarging_rebates)?;
+        // get the onboarding rebates, within the account allowance
+        let rebate = Self::get_next_onboarding_rebates(amount_in_tdfy, onboarging_rebates)?
+          .min(Self::remaining_onboarding_allowance(account_id));
         if rebate.is_zero() {
           return Ok(None);
         }
@@ -484,6 +1309,9 @@ pub mod pallet {
 
         onboarging_rebates.available_amount =
           onboarging_rebates.available_amount.saturating_sub(rebate);
+        OnboardingRefunded::<T>::mutate(account_id, |refunded| {
+          *refunded = refunded.saturating_add(rebate);
+        });
 
         Ok(Some(rebate))
       })
@@ -493,6 +1321,8 @@ pub mod pallet {
       account_id: &T::AccountId,
       era_index: EraIndex,
     ) -> Result<(), DispatchError> {
+      ensure!(!Self::is_expired(era_index), Error::<T>::RewardsExpired);
+
       Rewards::<T>::try_mutate_exists(account_id, era_index, |found_reward| {
         match found_reward {
           Some(reward) => {
@@ -501,21 +1331,10 @@ pub mod pallet {
             }
 
             // transfer funds
-            T::CurrencyTidefi::transfer(
-              CurrencyId::Tdfy,
-              &Self::account_id(),
-              account_id,
-              *reward,
-              true,
-            )?;
-
-            // emit event
-            Self::deposit_event(Event::<T>::SunriseClaimed {
-              era_index,
-              account_id: account_id.clone(),
-              reward: *reward,
-            });
-            // delete storage
+            Self::pay_rewards(account_id, *reward, true)?;
+
+            // emit event and delete storage
+            Self::settle_claimed_reward(account_id, era_index, *reward);
             *found_reward = None;
 
             log!(
@@ -534,4 +1353,139 @@ pub mod pallet {
       T::Cooldown::get()
     }
   }
+
+  impl<T: Config> SunriseRewardsClaim<T::AccountId> for Pallet<T> {
+    fn try_claim_all_sunrise_rewards(
+      account_id: &T::AccountId,
+      last_claimable_era: EraIndex,
+      max_eras: u32,
+      vest_rewards: bool,
+    ) -> Result<(u32, u32, Balance), DispatchError> {
+      // walk the eras the oldest first, as they expire first, from the oldest unexpired era which
+      // may still hold unclaimed rewards
+      let first_era = T::CurrentEra::get()
+        .map(|current_era| current_era.saturating_sub(T::RewardsClaimWindow::get()))
+        .unwrap_or_default()
+        .max(Self::oldest_claimable_era());
+      let mut scanned_eras: u32 = 0;
+      let mut claimable_eras: Vec<(EraIndex, Balance)> = Vec::new();
+      for era_index in first_era..=last_claimable_era {
+        if claimable_eras.len() as u32 >= max_eras {
+          break;
+        }
+        scanned_eras = scanned_eras.saturating_add(1);
+
+        let reward = Rewards::<T>::get(account_id, era_index);
+        if !reward.is_zero() {
+          claimable_eras.push((era_index, reward));
+        }
+      }
+      ensure!(!claimable_eras.is_empty(), Error::<T>::NoRewardsAvailable);
+
+      let total_rewards = claimable_eras
+        .iter()
+        .fold(0, |total_rewards: Balance, (_, reward)| {
+          total_rewards.saturating_add(*reward)
+        });
+      // paid at once, to create a single vesting schedule
+      Self::pay_rewards(account_id, total_rewards, vest_rewards)?;
+
+      for (era_index, reward) in claimable_eras.iter() {
+        Rewards::<T>::remove(account_id, era_index);
+        Self::settle_claimed_reward(account_id, *era_index, *reward);
+      }
+
+      Ok((scanned_eras, claimable_eras.len() as u32, total_rewards))
+    }
+
+    fn max_scanned_eras() -> u32 {
+      T::RewardsClaimWindow::get().saturating_add(1)
+    }
+
+    fn account_sunrise_rewards(account_id: &T::AccountId) -> Vec<(EraIndex, Balance)> {
+      let mut rewards = Rewards::<T>::iter_prefix(account_id)
+        .filter(|(era_index, reward)| !reward.is_zero() && !Self::is_expired(*era_index))
+        .collect::<Vec<(EraIndex, Balance)>>();
+      rewards.sort_by_key(|(era_index, _)| *era_index);
+      rewards
+    }
+
+    fn is_subject_to_cooldown(account_id: &T::AccountId, era_index: EraIndex) -> bool {
+      // the vesting schedules replace the cooldown
+      Self::rewards_vesting_schedule(account_id, Rewards::<T>::get(account_id, era_index)).is_none()
+    }
+
+    fn last_claimable_era(era_index: EraIndex) -> EraIndex {
+      era_index.saturating_add(T::RewardsClaimWindow::get())
+    }
+  }
+
+  impl<T: Config> SunriseOnboardingRefund<T::AccountId> for Pallet<T> {
+    fn try_refund_gas_for_mint(
+      account_id: &T::AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+      transaction_id: &[u8],
+    ) -> Result<Option<Balance>, DispatchError> {
+      let deposit_hash = T::Hashing::hash(transaction_id);
+      ensure!(
+        !DepositRefunds::<T>::contains_key(deposit_hash),
+        Error::<T>::DepositAlreadyRefunded
+      );
+
+      let rebate = Self::try_refund_gas_for_deposit(account_id, currency_id, amount)?;
+      if let Some(rebate) = rebate {
+        DepositRefunds::<T>::insert(deposit_hash, (account_id.clone(), rebate));
+      }
+
+      Ok(rebate)
+    }
+
+    fn remaining_onboarding_allowance(account_id: &T::AccountId) -> Balance {
+      T::MaximumOnboardingRefundPerAccount::get()
+        .saturating_sub(Self::onboarding_refunded(account_id))
+        .min(Self::onboarding().available_amount)
+    }
+  }
+
+  impl<T: Config> SunriseSwapRewards<T::AccountId> for Pallet<T> {
+    fn is_trade_rewardable(
+      account_id: &T::AccountId,
+      counterparty: &T::AccountId,
+      currency_id: CurrencyId,
+    ) -> bool {
+      if account_id == counterparty
+        || T::LinkedAccounts::are_linked(account_id, counterparty)
+        || T::LinkedAccounts::are_linked(counterparty, account_id)
+      {
+        return false;
+      }
+
+      Self::swap_acquisition(account_id, currency_id)
+        .map(|acquired_at| {
+          T::Security::get_current_block_count()
+            >= acquired_at.saturating_add(T::MinimumHoldingPeriod::get())
+        })
+        .unwrap_or(true)
+    }
+
+    fn register_swap_acquisition(account_id: &T::AccountId, currency_id: CurrencyId) {
+      let current_block = T::Security::get_current_block_count();
+      SwapAcquisitions::<T>::insert(account_id, currency_id, current_block);
+      SwapAcquisitionsByBlock::<T>::insert(current_block, (account_id.clone(), currency_id), ());
+      // don't scan the blocks before the first acquisition
+      if Self::oldest_swap_acquisition_block().is_zero() {
+        OldestSwapAcquisitionBlock::<T>::put(current_block);
+      }
+    }
+  }
+
+  impl<T: Config> SunrisePoolsFunding for Pallet<T> {
+    fn replenish_pools_left_over(amount: Balance) {
+      PoolsLeftOverBalance::<T>::mutate(|left_over| {
+        *left_over = left_over.saturating_add(amount);
+      });
+      Self::deposit_event(Event::<T>::PoolsLeftOverReplenished { amount });
+    }
+  }
 }
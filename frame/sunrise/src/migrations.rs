@@ -0,0 +1,70 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{
+  log,
+  traits::{Get, GetStorageVersion, PalletInfoAccess, StorageVersion},
+};
+use tidefi_primitives::{pallet::SecurityExt, Balance, EraIndex};
+
+/// Migrate the pallet storage to v2.
+///
+/// Index the unclaimed `Rewards` by era and initialize the `PendingRewards` with their total.
+pub fn migrate_to_v2<T: Config, P: GetStorageVersion + PalletInfoAccess>(
+) -> frame_support::weights::Weight {
+  let on_chain_storage_version = <P as GetStorageVersion>::on_chain_storage_version();
+  log!(
+    info,
+    "Running migration storage v2 with storage version {:?}",
+    on_chain_storage_version,
+  );
+
+  if on_chain_storage_version < 2 {
+    // index the unclaimed rewards by era, so they can expire, and track their total
+    let mut rewards_count: u64 = 0;
+    let mut oldest_era: Option<EraIndex> = None;
+    let mut pending_rewards: Balance = 0;
+    for (account_id, era_index, reward) in Rewards::<T>::iter() {
+      RewardsByEra::<T>::insert(era_index, account_id, ());
+      oldest_era = Some(oldest_era.map_or(era_index, |oldest_era| oldest_era.min(era_index)));
+      pending_rewards = pending_rewards.saturating_add(reward);
+      rewards_count = rewards_count.saturating_add(1);
+    }
+    OldestClaimableEra::<T>::put(oldest_era.unwrap_or_default());
+    PendingRewards::<T>::put(pending_rewards);
+
+    // update on-chain storage version
+    StorageVersion::new(2).put::<P>();
+    log!(
+      info,
+      "Running migration storage v2 with storage version {:?} was complete",
+      on_chain_storage_version,
+    );
+    // return migration weights
+    T::DbWeight::get().reads_writes(
+      rewards_count.saturating_add(1),
+      rewards_count.saturating_add(3),
+    )
+  } else {
+    log!(
+      info,
+      "Attempted to apply migration to v2 but failed because storage version is {:?}",
+      on_chain_storage_version,
+    );
+    T::DbWeight::get().reads(1)
+  }
+}
@@ -25,7 +25,7 @@ use frame_support::{
     },
     fungibles::{Inspect, InspectHold, Mutate, MutateHold, Transfer},
     tokens::{DepositConsequence, WithdrawConsequence},
-    ConstU128, ConstU32, GenesisBuild,
+    ConstU128, ConstU32, ConstU64, GenesisBuild,
   },
   PalletId,
 };
@@ -36,12 +36,12 @@ use sp_core::H256;
 use sp_runtime::{
   testing::Header,
   traits::{BlakeTwo256, IdentityLookup},
-  DispatchError, DispatchResult, FixedPointNumber, FixedU128, Permill, RuntimeDebug,
+  DispatchError, DispatchResult, FixedPointNumber, FixedU128, Percent, Permill, RuntimeDebug,
 };
 use std::marker::PhantomData;
-use system::EnsureRoot;
+use system::{EnsureRoot, EnsureSigned};
 use tidefi_primitives::{
-  assets, BlockNumber, CurrencyId, OnboardingRebates, SessionIndex, SunriseSwapPool,
+  assets, BlockNumber, CurrencyId, EraIndex, OnboardingRebates, SessionIndex, SunriseSwapPool,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -89,9 +89,10 @@ frame_support::construct_runtime!(
     Balances: pallet_balances::{Pallet, Call, Config<T>, Storage, Event<T>},
     Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
     AssetRegistry: pallet_asset_registry::{Pallet, Call, Config<T>, Storage, Event<T>},
-    Sunrise: pallet_sunrise::{Pallet, Config<T>, Storage, Event<T>},
+    Sunrise: pallet_sunrise::{Pallet, Call, Config<T>, Storage, Event<T>},
     Security: pallet_security::{Pallet, Call, Config, Storage, Event<T>},
     TidefiStaking: pallet_tidefi_stake::{Pallet, Call, Config<T>, Storage, Event<T>},
+    Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>},
   }
 );
 
@@ -179,6 +180,21 @@ impl pallet_balances::Config for Test {
   type WeightInfo = ();
 }
 
+parameter_types! {
+  pub const MinVestedTransfer: Balance = TDFY;
+  pub const MaxVestingSchedules: u32 = 10;
+}
+
+impl pallet_vesting::Config for Test {
+  type Event = Event;
+  type Currency = Balances;
+  type MinVestedTransfer = MinVestedTransfer;
+  type VestedTransferOrigin = EnsureSigned<AccountId>;
+  type WeightInfo = ();
+  type MaxVestingSchedules = MaxVestingSchedules;
+  type BlockNumberProvider = System;
+}
+
 parameter_types! {
   pub const SunrisePalletId: PalletId = PalletId(*b"sunr*pal");
   pub const TidefiPalletId: PalletId = PalletId(*b"wrpr*pal");
@@ -188,8 +204,10 @@ parameter_types! {
   pub const PeriodBasis: BlockNumber = 1000u32;
   pub const SessionsPerEra: SessionIndex = 10;
   pub const SessionsArchive: SessionIndex = 2;
-  pub const BlocksPerSession: BlockNumber = 50;
   pub const BlocksForceUnstake: BlockNumber = 10;
+  pub const CancelUnstakeFeeRefund: Percent = Percent::from_percent(50);
+  pub const UnstakeFeeDestination: Option<AccountId> = None;
+  pub const RewardsProjectionSessions: SessionIndex = 4;
   pub const StakeAccountCap: u32 = 10;
   pub const UnstakeQueueCap: u32 = 100;
   // 20 basis point
@@ -204,6 +222,22 @@ parameter_types! {
   pub const MaximumRewardPerSwap: Balance = 10_000_000_000_000_000;
   // 50%
   pub const LeftoverSwapRebates: FixedU128 = FixedU128::from_inner(500_000_000_000_000_000);
+  pub const RewardsClaimWindow: EraIndex = 10;
+  pub const RewardsExpiryNotice: EraIndex = 2;
+  pub static CurrentEra: Option<EraIndex> = None;
+  pub static ExpiredRewardsDestination: Option<AccountId> = None;
+  pub static RewardsVesting: Option<(u64, u32)> = None;
+  pub static MaximumRewardPerAccountPerEra: Balance = Balance::MAX;
+  pub static MinimumHoldingPeriod: u64 = 0;
+  pub static LinkedAccountPairs: Vec<(AccountId, AccountId)> = vec![];
+  pub static MaximumOnboardingRefundPerAccount: Balance = Balance::MAX;
+}
+
+pub struct MockLinkedAccounts;
+impl pallet_sunrise::LinkedAccounts<AccountId> for MockLinkedAccounts {
+  fn are_linked(account_id: &AccountId, other: &AccountId) -> bool {
+    LinkedAccountPairs::get().contains(&(*account_id, *other))
+  }
 }
 
 impl pallet_sunrise::Config for Test {
@@ -214,6 +248,18 @@ impl pallet_sunrise::Config for Test {
   type Cooldown = Cooldown;
   type MaximumRewardPerSwap = MaximumRewardPerSwap;
   type LeftoverSwapRebates = LeftoverSwapRebates;
+  type MaximumRewardPerAccountPerEra = MaximumRewardPerAccountPerEra;
+  type MinimumHoldingPeriod = MinimumHoldingPeriod;
+  type LinkedAccounts = MockLinkedAccounts;
+  type MaximumOnboardingRefundPerAccount = MaximumOnboardingRefundPerAccount;
+  type CurrentEra = CurrentEra;
+  type RewardsClaimWindow = RewardsClaimWindow;
+  type RewardsExpiryNotice = RewardsExpiryNotice;
+  type ExpiredRewardsDestination = ExpiredRewardsDestination;
+  type RewardsVesting = RewardsVesting;
+  type Vesting = Vesting;
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
+  type WeightInfo = pallet_sunrise::weights::SubstrateWeight<Test>;
 }
 
 impl pallet_tidefi_stake::Config for Test {
@@ -223,21 +269,33 @@ impl pallet_tidefi_stake::Config for Test {
   type StakeAccountCap = StakeAccountCap;
   type UnstakeQueueCap = UnstakeQueueCap;
   type BlocksForceUnstake = BlocksForceUnstake;
+  type CancelUnstakeFeeRefund = CancelUnstakeFeeRefund;
+  type UnstakeFeeDestination = UnstakeFeeDestination;
+  type RewardsProjectionSessions = RewardsProjectionSessions;
   type AssetRegistry = AssetRegistry;
   type StakingRewardCap = StakingRewardCap;
   type Security = Security;
+  type Sunrise = Sunrise;
+  type Fees = ();
   type WeightInfo = pallet_tidefi_stake::weights::SubstrateWeight<Test>;
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
 }
 
 impl pallet_asset_registry::Config for Test {
   type Event = Event;
   type WeightInfo = pallet_asset_registry::weights::SubstrateWeight<Test>;
   type AssetRegistryPalletId = AssetRegistryPalletId;
+  type StringLimit = ConstU32<255>;
   type CurrencyTidefi = Adapter<AccountId>;
 }
 
 impl pallet_security::Config for Test {
   type Event = Event;
+  type PauseOrigin = EnsureRoot<Self::AccountId>;
+  type EmergencyOrigin = system::EnsureSigned<Self::AccountId>;
+  type EmergencyInitiator = Self::AccountId;
+  type EmergencyPauseDuration = ConstU64<100>;
+  type PauseReasonLimit = ConstU32<256>;
   type WeightInfo = pallet_security::weights::SubstrateWeight<Test>;
 }
 
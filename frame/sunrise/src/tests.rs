@@ -15,15 +15,44 @@
 // along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-  mock::{new_test_ext, AccountId, Adapter, Sunrise, Test},
-  Error,
+  mock::{
+    new_test_ext, AccountId, Adapter, Cooldown, CurrentEra, Event as MockEvent,
+    ExpiredRewardsDestination, RewardsVesting, Sunrise, System, Test, Vesting,
+  },
+  Error, Event, OldestClaimableEra,
 };
-use frame_support::{assert_noop, assert_ok, traits::fungibles::Mutate};
+use frame_support::{
+  assert_noop, assert_ok,
+  traits::{
+    fungibles::{Inspect, Mutate},
+    Hooks,
+  },
+};
+use frame_system::RawOrigin;
 use pallet_balances::Error as BalancesError;
-use sp_runtime::{traits::CheckedDiv, FixedPointNumber, FixedU128};
+use sp_runtime::{traits::CheckedDiv, DispatchError, FixedPointNumber, FixedU128};
 use tidefi_primitives::{assets::Asset, pallet::SunriseExt, CurrencyId, Fee, OnboardingRebates};
 
 const ALICE_ACCOUNT_ID: AccountId = AccountId(1);
+const BOB_ACCOUNT_ID: AccountId = AccountId(2);
+
+// Funds the sunrise account with enough TDFY to cover the genesis pools and onboarding rebates,
+// plus `extra` TDFY available for new commitments.
+fn fund_sunrise_account(extra: u128) {
+  let committed = Sunrise::sunrise_pools()
+    .iter()
+    .fold(Sunrise::onboarding().available_amount, |total, pool| {
+      total.saturating_add(pool.balance)
+    });
+  assert_ok!(Adapter::mint_into(
+    CurrencyId::Tdfy,
+    &Sunrise::account_id(),
+    // keep the existential deposit
+    committed
+      .saturating_add(extra)
+      .saturating_add(Asset::Tdfy.saturating_mul(1)),
+  ));
+}
 
 #[test]
 pub fn test_select_first_eligible_sunrise_pool() {
@@ -390,3 +419,889 @@ pub fn test_try_allocate_rewards_for_swap() {
     );
   });
 }
+
+mod create_pool {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(1_000));
+      assert_ok!(Sunrise::create_pool(
+        RawOrigin::Root.into(),
+        3,
+        0,
+        10,
+        Asset::Tdfy.saturating_mul(1_000),
+        FixedU128::saturating_from_rational(150_u32, 100_u32),
+      ));
+
+      let pool = Sunrise::sunrise_pools()
+        .into_iter()
+        .find(|pool| pool.id == 3)
+        .unwrap();
+      assert_eq!(pool.transactions_remaining, 10);
+      assert_eq!(pool.balance, Asset::Tdfy.saturating_mul(1_000));
+    });
+  }
+
+  #[test]
+  pub fn fails_without_enough_funds() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(0);
+      assert_noop!(
+        Sunrise::create_pool(
+          RawOrigin::Root.into(),
+          3,
+          0,
+          10,
+          Asset::Tdfy.saturating_mul(1_000),
+          FixedU128::saturating_from_rational(150_u32, 100_u32),
+        ),
+        Error::<Test>::InsufficientSunriseFunds
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_with_existing_id() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(1_000));
+      assert_noop!(
+        Sunrise::create_pool(
+          RawOrigin::Root.into(),
+          1,
+          0,
+          10,
+          Asset::Tdfy.saturating_mul(1_000),
+          FixedU128::saturating_from_rational(150_u32, 100_u32),
+        ),
+        Error::<Test>::SunrisePoolAlreadyExists
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_with_bad_origin() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(1_000));
+      assert_noop!(
+        Sunrise::create_pool(
+          RawOrigin::Signed(ALICE_ACCOUNT_ID).into(),
+          3,
+          0,
+          10,
+          Asset::Tdfy.saturating_mul(1_000),
+          FixedU128::saturating_from_rational(150_u32, 100_u32),
+        ),
+        DispatchError::BadOrigin
+      );
+    });
+  }
+}
+
+mod top_up_pool {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(1_000));
+      assert_ok!(Sunrise::top_up_pool(
+        RawOrigin::Root.into(),
+        1,
+        Asset::Tdfy.saturating_mul(1_000)
+      ));
+
+      assert_eq!(
+        Sunrise::sunrise_pools()
+          .into_iter()
+          .find(|pool| pool.id == 1)
+          .unwrap()
+          .balance,
+        Asset::Tdfy.saturating_mul(67_201_000)
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_without_enough_funds() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(1_000));
+      assert_noop!(
+        Sunrise::top_up_pool(RawOrigin::Root.into(), 1, Asset::Tdfy.saturating_mul(1_001)),
+        Error::<Test>::InsufficientSunriseFunds
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_with_invalid_pool() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(1_000));
+      assert_noop!(
+        Sunrise::top_up_pool(RawOrigin::Root.into(), 3, Asset::Tdfy.saturating_mul(1)),
+        Error::<Test>::InvalidSunrisePool
+      );
+    });
+  }
+}
+
+mod modify_pool {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Sunrise::modify_pool(
+        RawOrigin::Root.into(),
+        2,
+        None,
+        Some(100),
+        Some(FixedU128::saturating_from_rational(300_u32, 100_u32)),
+      ));
+
+      let pool = Sunrise::sunrise_pools()
+        .into_iter()
+        .find(|pool| pool.id == 2)
+        .unwrap();
+      assert_eq!(pool.minimum_tdfy_value, 1_000_000_000_000_000);
+      assert_eq!(pool.transactions_remaining, 100);
+      assert_eq!(
+        pool.rebates,
+        FixedU128::saturating_from_rational(300_u32, 100_u32)
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_with_bad_origin() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Sunrise::modify_pool(
+          RawOrigin::Signed(ALICE_ACCOUNT_ID).into(),
+          2,
+          None,
+          Some(100),
+          None,
+        ),
+        DispatchError::BadOrigin
+      );
+    });
+  }
+}
+
+mod close_pool {
+  use super::*;
+
+  #[test]
+  pub fn moves_balance_to_left_over() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(Sunrise::close_pool(RawOrigin::Root.into(), 1));
+
+      assert_eq!(Sunrise::sunrise_pools().len(), 1);
+      assert_eq!(
+        Sunrise::pools_left_over(),
+        Asset::Tdfy.saturating_mul(67_200_000)
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_with_invalid_pool() {
+    new_test_ext().execute_with(|| {
+      assert_noop!(
+        Sunrise::close_pool(RawOrigin::Root.into(), 3),
+        Error::<Test>::InvalidSunrisePool
+      );
+    });
+  }
+}
+
+mod set_onboarding_rebates {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(0);
+      assert_ok!(Sunrise::set_onboarding_rebates(
+        RawOrigin::Root.into(),
+        Asset::Tdfy.saturating_mul(96_000_000),
+        Asset::Tdfy.saturating_mul(24_000_000),
+      ));
+
+      let onboarding = Sunrise::onboarding();
+      assert_eq!(
+        onboarding.initial_amount,
+        Asset::Tdfy.saturating_mul(96_000_000)
+      );
+      assert_eq!(
+        onboarding.available_amount,
+        Asset::Tdfy.saturating_mul(24_000_000)
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_with_available_above_initial() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(0);
+      assert_noop!(
+        Sunrise::set_onboarding_rebates(
+          RawOrigin::Root.into(),
+          Asset::Tdfy.saturating_mul(1_000),
+          Asset::Tdfy.saturating_mul(1_001),
+        ),
+        Error::<Test>::InvalidOnboardingRebates
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_without_enough_funds() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(0);
+      assert_noop!(
+        Sunrise::set_onboarding_rebates(
+          RawOrigin::Root.into(),
+          Asset::Tdfy.saturating_mul(96_000_000),
+          Asset::Tdfy.saturating_mul(48_000_001),
+        ),
+        Error::<Test>::InsufficientSunriseFunds
+      );
+    });
+  }
+}
+
+mod sweep_pools_left_over {
+  use super::*;
+
+  #[test]
+  pub fn succeeds() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(0);
+      assert_ok!(Sunrise::close_pool(RawOrigin::Root.into(), 1));
+      assert_ok!(Sunrise::sweep_pools_left_over(
+        RawOrigin::Root.into(),
+        BOB_ACCOUNT_ID,
+        Asset::Tdfy.saturating_mul(67_000_000),
+      ));
+
+      assert_eq!(
+        Sunrise::pools_left_over(),
+        Asset::Tdfy.saturating_mul(200_000)
+      );
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &BOB_ACCOUNT_ID),
+        Asset::Tdfy.saturating_mul(67_000_000)
+      );
+    });
+  }
+
+  #[test]
+  pub fn fails_above_left_over() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(0);
+      assert_noop!(
+        Sunrise::sweep_pools_left_over(
+          RawOrigin::Root.into(),
+          BOB_ACCOUNT_ID,
+          Asset::Tdfy.saturating_mul(1),
+        ),
+        Error::<Test>::InsufficientPoolsLeftOver
+      );
+    });
+  }
+}
+
+mod expire_rewards {
+  use super::*;
+
+  const REWARD: u128 = 1_000_000_000_000_000;
+
+  fn add_rewards() {
+    fund_sunrise_account(0);
+    assert_ok!(Adapter::mint_into(
+      CurrencyId::Tdfy,
+      &Sunrise::account_id(),
+      REWARD.saturating_mul(2),
+    ));
+    Sunrise::add_reward(&ALICE_ACCOUNT_ID, 1, REWARD);
+    Sunrise::add_reward(&BOB_ACCOUNT_ID, 1, REWARD);
+    OldestClaimableEra::<Test>::put(1);
+  }
+
+  #[test]
+  pub fn returns_rewards_to_pools_left_over() {
+    new_test_ext().execute_with(|| {
+      System::set_block_number(1);
+      add_rewards();
+
+      // last claimable era
+      CurrentEra::set(Some(11));
+      Sunrise::on_idle(1, u64::MAX);
+      assert_eq!(Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, 1), REWARD);
+      assert_eq!(Sunrise::pending_rewards(), REWARD.saturating_mul(2));
+
+      CurrentEra::set(Some(12));
+      Sunrise::on_idle(1, u64::MAX);
+      assert_eq!(Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, 1), 0);
+      assert_eq!(Sunrise::sunrise_rewards(BOB_ACCOUNT_ID, 1), 0);
+      assert_eq!(Sunrise::pending_rewards(), 0);
+      assert_eq!(Sunrise::pools_left_over(), REWARD.saturating_mul(2));
+      assert_eq!(Sunrise::oldest_claimable_era(), 2);
+
+      System::assert_has_event(MockEvent::Sunrise(Event::SunriseRewardsExpired {
+        era_index: 1,
+        account_id: ALICE_ACCOUNT_ID,
+        reward: REWARD,
+      }));
+      System::assert_has_event(MockEvent::Sunrise(Event::ExpiredRewardsReclaimed {
+        destination: None,
+        amount: REWARD.saturating_mul(2),
+      }));
+    });
+  }
+
+  #[test]
+  pub fn sends_rewards_to_destination() {
+    new_test_ext().execute_with(|| {
+      add_rewards();
+      ExpiredRewardsDestination::set(Some(AccountId(3)));
+
+      CurrentEra::set(Some(12));
+      Sunrise::on_idle(1, u64::MAX);
+      assert_eq!(Sunrise::pending_rewards(), 0);
+      assert_eq!(Sunrise::pools_left_over(), 0);
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &AccountId(3)),
+        REWARD.saturating_mul(2)
+      );
+    });
+  }
+
+  #[test]
+  pub fn stops_when_weight_is_exhausted() {
+    new_test_ext().execute_with(|| {
+      add_rewards();
+
+      CurrentEra::set(Some(12));
+      // the scan of the era and a single reward
+      Sunrise::on_idle(1, 2);
+      assert_eq!(Sunrise::pending_rewards(), REWARD);
+      assert_eq!(Sunrise::oldest_claimable_era(), 1);
+
+      Sunrise::on_idle(1, u64::MAX);
+      assert_eq!(Sunrise::pending_rewards(), 0);
+      assert_eq!(Sunrise::oldest_claimable_era(), 2);
+    });
+  }
+
+  #[test]
+  pub fn claim_fails_when_expired() {
+    new_test_ext().execute_with(|| {
+      add_rewards();
+
+      CurrentEra::set(Some(12));
+      assert_noop!(
+        Sunrise::try_claim_sunrise_rewards(&ALICE_ACCOUNT_ID, 1),
+        Error::<Test>::RewardsExpired
+      );
+
+      CurrentEra::set(Some(11));
+      assert_ok!(Sunrise::try_claim_sunrise_rewards(&ALICE_ACCOUNT_ID, 1));
+    });
+  }
+
+  #[test]
+  pub fn notifies_before_expiry() {
+    new_test_ext().execute_with(|| {
+      System::set_block_number(1);
+      add_rewards();
+
+      CurrentEra::set(Some(9));
+      Sunrise::on_initialize(1);
+      System::assert_has_event(MockEvent::Sunrise(Event::SunriseRewardsExpiringSoon {
+        era_index: 1,
+        last_claimable_era: 11,
+      }));
+      assert_eq!(Sunrise::last_expiry_notice_era(), 9);
+    });
+  }
+}
+
+mod claim_all_rewards {
+  use super::*;
+  use crate::SunriseRewardsClaim;
+
+  #[test]
+  pub fn claims_oldest_eras_first() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(15));
+      for era_index in 1..=5 {
+        Sunrise::add_reward(
+          &ALICE_ACCOUNT_ID,
+          era_index,
+          Asset::Tdfy.saturating_mul(era_index.into()),
+        );
+      }
+      OldestClaimableEra::<Test>::put(1);
+      CurrentEra::set(Some(6));
+
+      assert_eq!(
+        Sunrise::try_claim_all_sunrise_rewards(&ALICE_ACCOUNT_ID, 5, 2, true),
+        Ok((2, 2, Asset::Tdfy.saturating_mul(3)))
+      );
+      assert_eq!(Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, 1), 0);
+      assert_eq!(Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, 2), 0);
+      for era_index in 3..=5 {
+        assert_eq!(
+          Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, era_index),
+          Asset::Tdfy.saturating_mul(era_index.into())
+        );
+      }
+    });
+  }
+
+  #[test]
+  pub fn scans_unexpired_eras_only() {
+    new_test_ext().execute_with(|| {
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(30));
+      for era_index in [5, 12, 15] {
+        Sunrise::add_reward(
+          &ALICE_ACCOUNT_ID,
+          era_index,
+          Asset::Tdfy.saturating_mul(era_index.into()),
+        );
+      }
+      // the era 5 is expired, but not removed yet
+      OldestClaimableEra::<Test>::put(1);
+      CurrentEra::set(Some(20));
+
+      assert_eq!(
+        Sunrise::try_claim_all_sunrise_rewards(&ALICE_ACCOUNT_ID, 19, 100, true),
+        Ok((10, 2, Asset::Tdfy.saturating_mul(27)))
+      );
+      assert_eq!(
+        Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, 5),
+        Asset::Tdfy.saturating_mul(5)
+      );
+      assert_eq!(Sunrise::max_scanned_eras(), 11);
+    });
+  }
+}
+
+mod rewards_vesting {
+  use super::*;
+  use crate::{mock::MaxVestingSchedules, SunriseRewardsClaim};
+  use pallet_vesting::{VestedTransfer, VestingSchedule};
+
+  #[test]
+  pub fn claims_through_vesting_schedule() {
+    new_test_ext().execute_with(|| {
+      RewardsVesting::set(Some((10, 4)));
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(10));
+      Sunrise::add_reward(&ALICE_ACCOUNT_ID, 1, Asset::Tdfy.saturating_mul(10) + 3);
+
+      assert_ok!(Sunrise::try_claim_sunrise_rewards(&ALICE_ACCOUNT_ID, 1));
+
+      let schedules = Vesting::vesting_schedules(ALICE_ACCOUNT_ID);
+      assert_eq!(schedules.len(), 1);
+      assert_eq!(schedules[0].period, 10);
+      assert_eq!(schedules[0].period_count, 4);
+      assert_eq!(schedules[0].per_period, 2_500_000_000_000);
+      // the rounding remainder is transferred as well
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+        Asset::Tdfy.saturating_mul(10) + 3
+      );
+      assert_eq!(Sunrise::pending_rewards(), 0);
+      assert_eq!(Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, 1), 0);
+    });
+  }
+
+  #[test]
+  pub fn pays_lump_sum_when_not_vested() {
+    new_test_ext().execute_with(|| {
+      RewardsVesting::set(Some((10, 4)));
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(20));
+      Sunrise::add_reward(&ALICE_ACCOUNT_ID, 1, Asset::Tdfy.saturating_mul(10));
+      OldestClaimableEra::<Test>::put(1);
+      CurrentEra::set(Some(2));
+
+      assert_eq!(
+        Sunrise::try_claim_all_sunrise_rewards(&ALICE_ACCOUNT_ID, 1, 1, false),
+        Ok((1, 1, Asset::Tdfy.saturating_mul(10)))
+      );
+
+      assert!(Vesting::vesting_schedules(ALICE_ACCOUNT_ID).is_empty());
+      assert_eq!(
+        Adapter::reducible_balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID, false),
+        Asset::Tdfy.saturating_mul(10)
+      );
+    });
+  }
+
+  #[test]
+  pub fn pays_small_rewards_as_lump_sum() {
+    new_test_ext().execute_with(|| {
+      RewardsVesting::set(Some((10, 4)));
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(10));
+      assert_ok!(Adapter::mint_into(
+        CurrencyId::Tdfy,
+        &ALICE_ACCOUNT_ID,
+        Asset::Tdfy.saturating_mul(10)
+      ));
+      // below the minimum vested transfer
+      Sunrise::add_reward(&ALICE_ACCOUNT_ID, 1, Asset::Tdfy.saturating_mul(1) / 2);
+
+      assert_ok!(Sunrise::try_claim_sunrise_rewards(&ALICE_ACCOUNT_ID, 1));
+
+      assert!(Vesting::vesting_schedules(ALICE_ACCOUNT_ID).is_empty());
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+        Asset::Tdfy.saturating_mul(10) + Asset::Tdfy.saturating_mul(1) / 2
+      );
+    });
+  }
+
+  #[test]
+  pub fn replaces_cooldown() {
+    new_test_ext().execute_with(|| {
+      assert_eq!(Sunrise::cooldown_blocks_count(), Cooldown::get());
+      Sunrise::add_reward(&ALICE_ACCOUNT_ID, 1, Asset::Tdfy.saturating_mul(10));
+      // below the minimum vested transfer
+      Sunrise::add_reward(&BOB_ACCOUNT_ID, 1, Asset::Tdfy.saturating_mul(1) / 2);
+      assert!(Sunrise::is_subject_to_cooldown(&ALICE_ACCOUNT_ID, 1));
+
+      RewardsVesting::set(Some((10, 4)));
+      assert_eq!(Sunrise::cooldown_blocks_count(), Cooldown::get());
+      assert!(!Sunrise::is_subject_to_cooldown(&ALICE_ACCOUNT_ID, 1));
+      assert!(Sunrise::is_subject_to_cooldown(&BOB_ACCOUNT_ID, 1));
+    });
+  }
+
+  #[test]
+  pub fn pays_lump_sum_when_vesting_schedules_are_full() {
+    new_test_ext().execute_with(|| {
+      RewardsVesting::set(Some((10, 4)));
+      fund_sunrise_account(Asset::Tdfy.saturating_mul(20));
+      for _ in 0..MaxVestingSchedules::get() {
+        assert_ok!(<Vesting as VestedTransfer<_, _, _>>::vested_transfer(
+          &Sunrise::account_id(),
+          &ALICE_ACCOUNT_ID,
+          VestingSchedule {
+            start: 0,
+            period: 10,
+            period_count: 1,
+            per_period: Asset::Tdfy.saturating_mul(1),
+          },
+        ));
+      }
+      assert!(!<Vesting as VestedTransfer<_, _, _>>::can_add_vesting_schedule(&ALICE_ACCOUNT_ID));
+      Sunrise::add_reward(&ALICE_ACCOUNT_ID, 1, Asset::Tdfy.saturating_mul(10));
+
+      // the cooldown applies as no schedule can be added
+      assert!(Sunrise::is_subject_to_cooldown(&ALICE_ACCOUNT_ID, 1));
+      assert_ok!(Sunrise::try_claim_sunrise_rewards(&ALICE_ACCOUNT_ID, 1));
+      assert_eq!(
+        Vesting::vesting_schedules(ALICE_ACCOUNT_ID).len() as u32,
+        MaxVestingSchedules::get()
+      );
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+        Asset::Tdfy.saturating_mul(10 + MaxVestingSchedules::get() as u128)
+      );
+    });
+  }
+}
+
+mod anti_wash_trading {
+  use super::*;
+  use crate::{
+    mock::{LinkedAccountPairs, MaximumRewardPerAccountPerEra, MinimumHoldingPeriod},
+    SunriseSwapRewards,
+  };
+  use pallet_security::CurrentBlockCount;
+
+  // 1 BTC paid in fees for a 1_000 BTC swap, with 1 BTC = 500 TDFY
+  fn btc_swap_fee() -> Fee {
+    assert_ok!(Sunrise::register_exchange_rate(vec![(
+      2,
+      500_000_000_000_000_u128
+    )]));
+    Fee {
+      amount: 100_000_000_000,
+      fee: 100_000_000,
+      fee_tdfy: Sunrise::try_get_tdfy_value(CurrencyId::Wrapped(2), 100_000_000).unwrap(),
+    }
+  }
+
+  #[test]
+  pub fn self_matched_trades_are_not_rewardable() {
+    new_test_ext().execute_with(|| {
+      assert!(!Sunrise::is_trade_rewardable(
+        &ALICE_ACCOUNT_ID,
+        &ALICE_ACCOUNT_ID,
+        CurrencyId::Wrapped(2)
+      ));
+      assert!(Sunrise::is_trade_rewardable(
+        &ALICE_ACCOUNT_ID,
+        &BOB_ACCOUNT_ID,
+        CurrencyId::Wrapped(2)
+      ));
+    });
+  }
+
+  #[test]
+  pub fn linked_accounts_trades_are_not_rewardable() {
+    new_test_ext().execute_with(|| {
+      LinkedAccountPairs::set(vec![(BOB_ACCOUNT_ID, ALICE_ACCOUNT_ID)]);
+
+      assert!(!Sunrise::is_trade_rewardable(
+        &ALICE_ACCOUNT_ID,
+        &BOB_ACCOUNT_ID,
+        CurrencyId::Wrapped(2)
+      ));
+      assert!(!Sunrise::is_trade_rewardable(
+        &BOB_ACCOUNT_ID,
+        &ALICE_ACCOUNT_ID,
+        CurrencyId::Wrapped(2)
+      ));
+    });
+  }
+
+  #[test]
+  pub fn assets_sold_before_holding_period_are_not_rewardable() {
+    new_test_ext().execute_with(|| {
+      MinimumHoldingPeriod::set(10);
+      CurrentBlockCount::<Test>::put(5);
+      Sunrise::register_swap_acquisition(&ALICE_ACCOUNT_ID, CurrencyId::Wrapped(2));
+      assert_eq!(
+        Sunrise::swap_acquisition(ALICE_ACCOUNT_ID, CurrencyId::Wrapped(2)),
+        Some(5)
+      );
+
+      CurrentBlockCount::<Test>::put(14);
+      assert!(!Sunrise::is_trade_rewardable(
+        &ALICE_ACCOUNT_ID,
+        &BOB_ACCOUNT_ID,
+        CurrencyId::Wrapped(2)
+      ));
+      // the holding period only applies to the asset acquired
+      assert!(Sunrise::is_trade_rewardable(
+        &ALICE_ACCOUNT_ID,
+        &BOB_ACCOUNT_ID,
+        CurrencyId::Tdfy
+      ));
+
+      CurrentBlockCount::<Test>::put(15);
+      assert!(Sunrise::is_trade_rewardable(
+        &ALICE_ACCOUNT_ID,
+        &BOB_ACCOUNT_ID,
+        CurrencyId::Wrapped(2)
+      ));
+    });
+  }
+
+  #[test]
+  pub fn swap_acquisitions_are_pruned_after_holding_period() {
+    new_test_ext().execute_with(|| {
+      MinimumHoldingPeriod::set(10);
+      CurrentBlockCount::<Test>::put(5);
+      Sunrise::register_swap_acquisition(&ALICE_ACCOUNT_ID, CurrencyId::Wrapped(2));
+      Sunrise::register_swap_acquisition(&BOB_ACCOUNT_ID, CurrencyId::Wrapped(2));
+      CurrentBlockCount::<Test>::put(8);
+      // restarts the holding period
+      Sunrise::register_swap_acquisition(&BOB_ACCOUNT_ID, CurrencyId::Wrapped(2));
+
+      CurrentBlockCount::<Test>::put(15);
+      Sunrise::on_idle(1, u64::MAX);
+      assert_eq!(
+        Sunrise::swap_acquisition(ALICE_ACCOUNT_ID, CurrencyId::Wrapped(2)),
+        Some(5)
+      );
+
+      CurrentBlockCount::<Test>::put(16);
+      Sunrise::on_idle(1, u64::MAX);
+      assert_eq!(
+        Sunrise::swap_acquisition(ALICE_ACCOUNT_ID, CurrencyId::Wrapped(2)),
+        None
+      );
+      assert_eq!(
+        Sunrise::swap_acquisition(BOB_ACCOUNT_ID, CurrencyId::Wrapped(2)),
+        Some(8)
+      );
+      assert_eq!(Sunrise::oldest_swap_acquisition_block(), 6);
+
+      CurrentBlockCount::<Test>::put(19);
+      Sunrise::on_idle(1, u64::MAX);
+      assert_eq!(
+        Sunrise::swap_acquisition(BOB_ACCOUNT_ID, CurrencyId::Wrapped(2)),
+        None
+      );
+    });
+  }
+
+  #[test]
+  pub fn rewards_are_capped_by_era() {
+    new_test_ext().execute_with(|| {
+      let fee = btc_swap_fee();
+      // 1 BTC @ 200% (pool id 2) = 1_000 TDFY, capped to 600 TDFY
+      MaximumRewardPerAccountPerEra::set(600_000_000_000_000);
+
+      assert_eq!(
+        Sunrise::try_allocate_rewards_for_swap(&ALICE_ACCOUNT_ID, 1, &fee, CurrencyId::Wrapped(2))
+          .unwrap(),
+        Some(600_000_000_000_000)
+      );
+      assert_eq!(
+        Sunrise::sunrise_rewards(ALICE_ACCOUNT_ID, 1),
+        600_000_000_000_000
+      );
+
+      // the cap is reached, the pools are left untouched
+      let pools = Sunrise::sunrise_pools();
+      assert_eq!(
+        Sunrise::try_allocate_rewards_for_swap(&ALICE_ACCOUNT_ID, 1, &fee, CurrencyId::Wrapped(2))
+          .unwrap(),
+        None
+      );
+      assert_eq!(Sunrise::sunrise_pools(), pools);
+
+      // the cap applies by era and by account
+      assert!(Sunrise::try_allocate_rewards_for_swap(
+        &ALICE_ACCOUNT_ID,
+        2,
+        &fee,
+        CurrencyId::Wrapped(2)
+      )
+      .unwrap()
+      .is_some());
+      assert!(Sunrise::try_allocate_rewards_for_swap(
+        &BOB_ACCOUNT_ID,
+        1,
+        &fee,
+        CurrencyId::Wrapped(2)
+      )
+      .unwrap()
+      .is_some());
+    });
+  }
+}
+
+mod onboarding_refunds {
+  use super::*;
+  use crate::{mock::MaximumOnboardingRefundPerAccount, SunriseOnboardingRefund};
+
+  // 100k TDFY = 1 BTC
+  fn set_btc_exchange_rate() {
+    assert_ok!(Adapter::mint_into(
+      CurrencyId::Tdfy,
+      &Sunrise::account_id(),
+      1_000_000_000_000_000_000,
+    ));
+    assert_ok!(Sunrise::register_exchange_rate(vec![(
+      2,
+      100_000_000_000_000_000_u128
+    )]));
+  }
+
+  #[test]
+  pub fn refunds_each_deposit_once() {
+    new_test_ext().execute_with(|| {
+      set_btc_exchange_rate();
+
+      assert_eq!(
+        Sunrise::try_refund_gas_for_mint(
+          &ALICE_ACCOUNT_ID,
+          CurrencyId::Wrapped(2),
+          200_000_000,
+          b"0x01"
+        ),
+        Ok(Some(200_000_000_000_000_000))
+      );
+      assert_eq!(
+        Sunrise::onboarding_refunded(ALICE_ACCOUNT_ID),
+        200_000_000_000_000_000
+      );
+
+      assert_noop!(
+        Sunrise::try_refund_gas_for_mint(
+          &ALICE_ACCOUNT_ID,
+          CurrencyId::Wrapped(2),
+          200_000_000,
+          b"0x01"
+        ),
+        Error::<Test>::DepositAlreadyRefunded
+      );
+
+      assert_eq!(
+        Sunrise::try_refund_gas_for_mint(
+          &ALICE_ACCOUNT_ID,
+          CurrencyId::Wrapped(2),
+          200_000_000,
+          b"0x02"
+        ),
+        Ok(Some(200_000_000_000_000_000))
+      );
+    });
+  }
+
+  #[test]
+  pub fn caps_refunds_by_account() {
+    new_test_ext().execute_with(|| {
+      set_btc_exchange_rate();
+      MaximumOnboardingRefundPerAccount::set(300_000_000_000_000_000);
+      assert_eq!(
+        Sunrise::remaining_onboarding_allowance(&ALICE_ACCOUNT_ID),
+        300_000_000_000_000_000
+      );
+
+      assert_eq!(
+        Sunrise::try_refund_gas_for_mint(
+          &ALICE_ACCOUNT_ID,
+          CurrencyId::Wrapped(2),
+          200_000_000,
+          b"0x01"
+        ),
+        Ok(Some(200_000_000_000_000_000))
+      );
+      // only the remaining allowance is refunded
+      assert_eq!(
+        Sunrise::try_refund_gas_for_mint(
+          &ALICE_ACCOUNT_ID,
+          CurrencyId::Wrapped(2),
+          200_000_000,
+          b"0x02"
+        ),
+        Ok(Some(100_000_000_000_000_000))
+      );
+      assert_eq!(
+        Sunrise::remaining_onboarding_allowance(&ALICE_ACCOUNT_ID),
+        0
+      );
+      assert_eq!(
+        Sunrise::try_refund_gas_for_mint(
+          &ALICE_ACCOUNT_ID,
+          CurrencyId::Wrapped(2),
+          200_000_000,
+          b"0x03"
+        ),
+        Ok(None)
+      );
+
+      // the allowance is by account
+      assert_eq!(
+        Sunrise::try_refund_gas_for_mint(
+          &BOB_ACCOUNT_ID,
+          CurrencyId::Wrapped(2),
+          200_000_000,
+          b"0x04"
+        ),
+        Ok(Some(200_000_000_000_000_000))
+      );
+    });
+  }
+}
@@ -0,0 +1,67 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for `pallet_sunrise`.
+pub trait WeightInfo {
+   fn create_pool() -> Weight;
+   fn top_up_pool() -> Weight;
+   fn modify_pool() -> Weight;
+   fn close_pool() -> Weight;
+   fn set_onboarding_rebates() -> Weight;
+   fn sweep_pools_left_over() -> Weight;
+}
+
+/// Weights for `pallet_sunrise` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+   fn create_pool() -> Weight {
+      31_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(5_u64))
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn top_up_pool() -> Weight {
+      30_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(5_u64))
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn modify_pool() -> Weight {
+      22_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(1_u64))
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn close_pool() -> Weight {
+      24_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(2_u64))
+          .saturating_add(T::DbWeight::get().writes(2_u64))
+  }
+   fn set_onboarding_rebates() -> Weight {
+      28_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(4_u64))
+          .saturating_add(T::DbWeight::get().writes(1_u64))
+  }
+   fn sweep_pools_left_over() -> Weight {
+      45_000_000_u64
+          .saturating_add(T::DbWeight::get().reads(3_u64))
+          .saturating_add(T::DbWeight::get().writes(3_u64))
+  }
+}
@@ -15,19 +15,162 @@
 // along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
 
 #![cfg_attr(not(feature = "std"), no_std)]
-use codec::Codec;
+use codec::{Codec, Decode, Encode};
 use frame_support::inherent::Vec;
-use sp_runtime::DispatchError;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{DispatchError, FixedU128, RuntimeDebug};
 use tidefi_primitives::{
-  BalanceInfo, BlockNumber, CurrencyBalance, CurrencyId, CurrencyMetadata, Stake,
+  ActiveEraInfo, Balance, BalanceInfo, BlockNumber, CurrencyBalance, CurrencyId, CurrencyMetadata,
+  EraIndex, Fee, Hash, OnboardingRebates, Stake, SunriseSwapPool,
 };
 
+/// Fees registered for a currency, serialized for the RPC
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FeeInfo {
+  /// Total amount before fees
+  pub amount: BalanceInfo,
+  /// Fees paid
+  pub fee: BalanceInfo,
+  /// Fees paid, in TDFY
+  pub fee_tdfy: BalanceInfo,
+}
+
+impl From<Fee> for FeeInfo {
+  fn from(fee: Fee) -> Self {
+    Self {
+      amount: BalanceInfo { amount: fee.amount },
+      fee: BalanceInfo { amount: fee.fee },
+      fee_tdfy: BalanceInfo {
+        amount: fee.fee_tdfy,
+      },
+    }
+  }
+}
+
+/// Unclaimed sunrise rewards of an era, serialized for the RPC
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SunriseRewardInfo {
+  /// Era of the swaps rewarded
+  pub era_index: EraIndex,
+  /// Rewards, in TDFY
+  pub reward: BalanceInfo,
+  /// Block from which the rewards can be claimed, `None` while the era is active
+  pub claimable_at: Option<BlockNumber>,
+  /// Last era the rewards can be claimed before they expire
+  pub last_claimable_era: EraIndex,
+}
+
+/// External chain information of an asset, serialized for the RPC
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetChainInfo {
+  /// Chain the asset originates from
+  pub origin_chain: Vec<u8>,
+  /// Contract address of the asset on its origin chain
+  pub contract_address: Option<Vec<u8>>,
+  /// Minimum amount of a deposit
+  pub minimum_deposit: BalanceInfo,
+  /// Minimum amount of a withdrawal
+  pub minimum_withdrawal: BalanceInfo,
+  /// Fee taken on the withdrawals, in the asset
+  pub withdrawal_fee: BalanceInfo,
+  /// Number of confirmations required on the origin chain before a deposit is minted
+  pub confirmations_required: u32,
+}
+
+/// Sunrise pool state, serialized for the RPC
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SunrisePoolInfo {
+  /// Pool id
+  pub id: u8,
+  /// Minimum trade value, in TDFY, eligible to the pool
+  pub minimum_tdfy_value: BalanceInfo,
+  /// Number of swaps the pool can still reward
+  pub transactions_remaining: u32,
+  /// Rewards remaining in the pool, in TDFY
+  pub balance: BalanceInfo,
+  /// Rebates applied on the fees paid
+  pub rebates: FixedU128,
+}
+
+impl From<SunriseSwapPool> for SunrisePoolInfo {
+  fn from(pool: SunriseSwapPool) -> Self {
+    Self {
+      id: pool.id,
+      minimum_tdfy_value: BalanceInfo {
+        amount: pool.minimum_tdfy_value,
+      },
+      transactions_remaining: pool.transactions_remaining,
+      balance: BalanceInfo {
+        amount: pool.balance,
+      },
+      rebates: pool.rebates,
+    }
+  }
+}
+
+/// Sunrise pools, left-over and onboarding rebates state, serialized for the RPC
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SunrisePoolsInfo {
+  /// Active sunrise pools
+  pub pools: Vec<SunrisePoolInfo>,
+  /// Balance available as left-over from the pools
+  pub pools_left_over: BalanceInfo,
+  /// Initial onboarding rebates
+  pub onboarding_initial_amount: BalanceInfo,
+  /// Onboarding rebates still available
+  pub onboarding_available_amount: BalanceInfo,
+}
+
+impl SunrisePoolsInfo {
+  pub fn new(
+    pools: Vec<SunriseSwapPool>,
+    pools_left_over: Balance,
+    onboarding: OnboardingRebates,
+  ) -> Self {
+    Self {
+      pools: pools.into_iter().map(Into::into).collect(),
+      pools_left_over: BalanceInfo {
+        amount: pools_left_over,
+      },
+      onboarding_initial_amount: BalanceInfo {
+        amount: onboarding.initial_amount,
+      },
+      onboarding_available_amount: BalanceInfo {
+        amount: onboarding.available_amount,
+      },
+    }
+  }
+}
+
 sp_api::decl_runtime_apis! {
+    /// Version 2 returns the reward recipient of the account stakes and the chain info of the
+    /// assets, and adds the staking, fees, sunrise and request id methods.
+    #[api_version(2)]
     pub trait TidefiApi<AccountId> where AccountId: Codec,
         {
             fn get_account_balance(account_id: AccountId, asset_id: CurrencyId) -> Result<CurrencyBalance<BalanceInfo>, DispatchError>;
             fn get_account_balances(account_id: AccountId) -> Result<Vec<(CurrencyId, CurrencyBalance<BalanceInfo>)>, DispatchError>;
+            #[changed_in(2)]
             fn get_account_stakes(account_id: AccountId) -> Result<Vec<(CurrencyId, Stake<BalanceInfo, BlockNumber>)>, DispatchError>;
+            fn get_account_stakes(account_id: AccountId) -> Result<Vec<(CurrencyId, Stake<BalanceInfo, BlockNumber>, Option<AccountId>)>, DispatchError>;
+            #[changed_in(2)]
             fn get_assets() -> Result<Vec<(CurrencyId, CurrencyMetadata<Vec<u8>>)>, DispatchError>;
+            fn get_assets() -> Result<Vec<(CurrencyId, CurrencyMetadata<Vec<u8>>, Option<AssetChainInfo>)>, DispatchError>;
+            fn get_unstake_queue_position(stake_id: Hash) -> Result<Option<(u32, BlockNumber)>, DispatchError>;
+            fn estimate_stake_rewards(currency_id: CurrencyId, amount: Balance, duration: BlockNumber) -> Result<BalanceInfo, DispatchError>;
+            fn get_account_stakes_rewards(account_id: AccountId) -> Result<Vec<(Hash, BalanceInfo)>, DispatchError>;
+            fn get_era_fees(era_index: EraIndex) -> Result<Vec<(CurrencyId, FeeInfo)>, DispatchError>;
+            fn get_account_fees(account_id: AccountId, start_era: EraIndex, end_era: EraIndex) -> Result<Vec<(EraIndex, Vec<(CurrencyId, FeeInfo)>)>, DispatchError>;
+            fn get_current_era() -> Result<Option<ActiveEraInfo<BlockNumber>>, DispatchError>;
+            fn get_sunrise_rewards(account_id: AccountId) -> Result<Vec<SunriseRewardInfo>, DispatchError>;
+            fn get_sunrise_pools() -> Result<SunrisePoolsInfo, DispatchError>;
+            fn get_onboarding_allowance(account_id: AccountId) -> Result<BalanceInfo, DispatchError>;
+            fn get_request_id(account_id: AccountId, nonce: u32, index: u32) -> Result<Hash, DispatchError>;
         }
 }
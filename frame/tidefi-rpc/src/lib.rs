@@ -21,13 +21,16 @@ use jsonrpsee::{
   proc_macros::rpc,
   types::{error::CallError, ErrorObject},
 };
-pub use pallet_tidefi_rpc_runtime_api::TidefiApi as TidefiRuntimeApi;
-use sp_api::ProvideRuntimeApi;
+pub use pallet_tidefi_rpc_runtime_api::{
+  AssetChainInfo, FeeInfo, SunrisePoolsInfo, SunriseRewardInfo, TidefiApi as TidefiRuntimeApi,
+};
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use std::sync::Arc;
 use tidefi_primitives::{
-  BalanceInfo, BlockNumber, CurrencyBalance, CurrencyId, CurrencyMetadata, Stake,
+  ActiveEraInfo, BalanceInfo, BlockNumber, CurrencyBalance, CurrencyId, CurrencyMetadata, EraIndex,
+  Hash, Stake,
 };
 
 #[rpc(client, server)]
@@ -36,7 +39,13 @@ pub trait TidefiApi<BlockHash, AccountId> {
   fn get_assets(
     &self,
     at: Option<BlockHash>,
-  ) -> RpcResult<Vec<(CurrencyId, CurrencyMetadata<Vec<u8>>)>>;
+  ) -> RpcResult<
+    Vec<(
+      CurrencyId,
+      CurrencyMetadata<Vec<u8>>,
+      Option<AssetChainInfo>,
+    )>,
+  >;
 
   #[method(name = "tidefi_getAccountBalance")]
   fn get_account_balance(
@@ -58,7 +67,81 @@ pub trait TidefiApi<BlockHash, AccountId> {
     &self,
     account_id: AccountId,
     at: Option<BlockHash>,
-  ) -> RpcResult<Vec<(CurrencyId, Stake<BalanceInfo, BlockNumber>)>>;
+  ) -> RpcResult<
+    Vec<(
+      CurrencyId,
+      Stake<BalanceInfo, BlockNumber>,
+      Option<AccountId>,
+    )>,
+  >;
+
+  #[method(name = "tidefi_getUnstakeQueuePosition")]
+  fn get_unstake_queue_position(
+    &self,
+    stake_id: Hash,
+    at: Option<BlockHash>,
+  ) -> RpcResult<Option<(u32, BlockNumber)>>;
+
+  #[method(name = "tidefi_estimateStakeRewards")]
+  fn estimate_stake_rewards(
+    &self,
+    currency_id: CurrencyId,
+    amount: BalanceInfo,
+    duration: BlockNumber,
+    at: Option<BlockHash>,
+  ) -> RpcResult<BalanceInfo>;
+
+  #[method(name = "tidefi_getAccountStakesRewards")]
+  fn get_account_stakes_rewards(
+    &self,
+    account_id: AccountId,
+    at: Option<BlockHash>,
+  ) -> RpcResult<Vec<(Hash, BalanceInfo)>>;
+
+  #[method(name = "tidefi_getEraFees")]
+  fn get_era_fees(
+    &self,
+    era_index: EraIndex,
+    at: Option<BlockHash>,
+  ) -> RpcResult<Vec<(CurrencyId, FeeInfo)>>;
+
+  #[method(name = "tidefi_getAccountFees")]
+  fn get_account_fees(
+    &self,
+    account_id: AccountId,
+    era_range: (EraIndex, EraIndex),
+    at: Option<BlockHash>,
+  ) -> RpcResult<Vec<(EraIndex, Vec<(CurrencyId, FeeInfo)>)>>;
+
+  #[method(name = "tidefi_getCurrentEra")]
+  fn get_current_era(&self, at: Option<BlockHash>)
+    -> RpcResult<Option<ActiveEraInfo<BlockNumber>>>;
+
+  #[method(name = "tidefi_getSunriseRewards")]
+  fn get_sunrise_rewards(
+    &self,
+    account_id: AccountId,
+    at: Option<BlockHash>,
+  ) -> RpcResult<Vec<SunriseRewardInfo>>;
+
+  #[method(name = "tidefi_getSunrisePools")]
+  fn get_sunrise_pools(&self, at: Option<BlockHash>) -> RpcResult<SunrisePoolsInfo>;
+
+  #[method(name = "tidefi_getOnboardingAllowance")]
+  fn get_onboarding_allowance(
+    &self,
+    account_id: AccountId,
+    at: Option<BlockHash>,
+  ) -> RpcResult<BalanceInfo>;
+
+  #[method(name = "tidefi_getRequestId")]
+  fn get_request_id(
+    &self,
+    account_id: AccountId,
+    nonce: u32,
+    index: u32,
+    at: Option<BlockHash>,
+  ) -> RpcResult<Hash>;
 }
 
 /// A struct that implements the [`TidefiApi`].
@@ -77,6 +160,26 @@ impl<C, B> TidefiRpc<C, B> {
   }
 }
 
+impl<C, Block> TidefiRpc<C, Block>
+where
+  Block: BlockT,
+  C: ProvideRuntimeApi<Block>,
+{
+  /// Version of the `TidefiApi` of the runtime at the block.
+  fn api_version<AccountId>(&self, at: &BlockId<Block>) -> Result<u32, Error>
+  where
+    AccountId: Codec,
+    C::Api: TidefiRuntimeApi<Block, AccountId>,
+  {
+    self
+      .client
+      .runtime_api()
+      .api_version::<dyn TidefiRuntimeApi<Block, AccountId>>(at)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .ok_or_else(|| Error::Call("TidefiApi is not available".into()))
+  }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
   #[error("{0}")]
@@ -126,27 +229,121 @@ where
     &self,
     account_id: AccountId,
     at: Option<<Block as BlockT>::Hash>,
-  ) -> RpcResult<Vec<(CurrencyId, Stake<BalanceInfo, BlockNumber>)>> {
+  ) -> RpcResult<
+    Vec<(
+      CurrencyId,
+      Stake<BalanceInfo, BlockNumber>,
+      Option<AccountId>,
+    )>,
+  > {
     let api = self.client.runtime_api();
     let at = BlockId::hash(at.unwrap_or(
       // If the block hash is not supplied assume the best block.
       self.client.info().best_hash,
     ));
+
+    if self.api_version::<AccountId>(&at)? < 2 {
+      // the reward recipient is not returned before the version 2
+      #[allow(deprecated)]
+      return api
+        .get_account_stakes_before_version_2(&at, account_id)
+        .map_err(|err| Error::Call(format!("{:?}", err)))?
+        .map(|stakes| {
+          stakes
+            .into_iter()
+            .map(|(currency_id, stake)| (currency_id, stake, None))
+            .collect()
+        })
+        .map_err(|err| Error::Dispatch(format!("{:?}", err)).into());
+    }
+
     api
       .get_account_stakes(&at, account_id)
       .map_err(|err| Error::Call(format!("{:?}", err)))?
       .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
   }
 
+  fn get_unstake_queue_position(
+    &self,
+    stake_id: Hash,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<Option<(u32, BlockNumber)>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_unstake_queue_position(&at, stake_id)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn estimate_stake_rewards(
+    &self,
+    currency_id: CurrencyId,
+    amount: BalanceInfo,
+    duration: BlockNumber,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<BalanceInfo> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .estimate_stake_rewards(&at, currency_id, amount.amount, duration)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn get_account_stakes_rewards(
+    &self,
+    account_id: AccountId,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<Vec<(Hash, BalanceInfo)>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_account_stakes_rewards(&at, account_id)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
   fn get_assets(
     &self,
     at: Option<<Block as BlockT>::Hash>,
-  ) -> RpcResult<Vec<(CurrencyId, CurrencyMetadata<Vec<u8>>)>> {
+  ) -> RpcResult<
+    Vec<(
+      CurrencyId,
+      CurrencyMetadata<Vec<u8>>,
+      Option<AssetChainInfo>,
+    )>,
+  > {
     let api = self.client.runtime_api();
     let at = BlockId::hash(at.unwrap_or(
       // If the block hash is not supplied assume the best block.
       self.client.info().best_hash,
     ));
+
+    if self.api_version::<AccountId>(&at)? < 2 {
+      // the chain info is not returned before the version 2
+      #[allow(deprecated)]
+      return api
+        .get_assets_before_version_2(&at)
+        .map_err(|err| Error::Call(format!("{:?}", err)))?
+        .map(|assets| {
+          assets
+            .into_iter()
+            .map(|(currency_id, metadata)| (currency_id, metadata, None))
+            .collect()
+        })
+        .map_err(|err| Error::Dispatch(format!("{:?}", err)).into());
+    }
+
     api
       .get_assets(&at)
       .map_err(|err| Error::Call(format!("{:?}", err)))?
@@ -168,4 +365,115 @@ where
       .map_err(|err| Error::Call(format!("{:?}", err)))?
       .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
   }
+
+  fn get_era_fees(
+    &self,
+    era_index: EraIndex,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<Vec<(CurrencyId, FeeInfo)>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_era_fees(&at, era_index)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn get_account_fees(
+    &self,
+    account_id: AccountId,
+    era_range: (EraIndex, EraIndex),
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<Vec<(EraIndex, Vec<(CurrencyId, FeeInfo)>)>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    let (start_era, end_era) = era_range;
+    api
+      .get_account_fees(&at, account_id, start_era, end_era)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn get_current_era(
+    &self,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<Option<ActiveEraInfo<BlockNumber>>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_current_era(&at)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn get_sunrise_rewards(
+    &self,
+    account_id: AccountId,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<Vec<SunriseRewardInfo>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_sunrise_rewards(&at, account_id)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn get_sunrise_pools(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<SunrisePoolsInfo> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_sunrise_pools(&at)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn get_onboarding_allowance(
+    &self,
+    account_id: AccountId,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<BalanceInfo> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_onboarding_allowance(&at, account_id)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
+
+  fn get_request_id(
+    &self,
+    account_id: AccountId,
+    nonce: u32,
+    index: u32,
+    at: Option<<Block as BlockT>::Hash>,
+  ) -> RpcResult<Hash> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or(
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash,
+    ));
+    api
+      .get_request_id(&at, account_id, nonce, index)
+      .map_err(|err| Error::Call(format!("{:?}", err)))?
+      .map_err(|err| Error::Dispatch(format!("{:?}", err)).into())
+  }
 }
@@ -16,10 +16,11 @@
 
 //! Benchmarking setup for pallet-tidefi
 use crate::*;
-use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_support::traits::fungibles::Mutate;
 use frame_system::RawOrigin;
-use tidefi_primitives::CurrencyId;
+use sp_std::vec;
+use tidefi_primitives::{pallet::SunriseExt, CurrencyId};
 
 const INITIAL_AMOUNT: u128 = 500_000_000;
 const IA_MULTIPLIER: u32 = 2;
@@ -32,13 +33,49 @@ benchmarks! {
       <T as Config>::CurrencyTidefi::mint_into(CurrencyId::Wrapped(TEST_TOKEN), &caller, balance as u128).expect("Unable to mint token");
    }: _(RawOrigin::Signed(caller), CurrencyId::Wrapped(TEST_TOKEN), INITIAL_AMOUNT, (14400_u32 * 15_u32).into())
 
+   stake_for {
+      let caller: T::AccountId = whitelisted_caller();
+      let beneficiary: T::AccountId = account("beneficiary", 0, 0);
+      let reward_recipient: T::AccountId = account("reward_recipient", 0, 0);
+      let balance = INITIAL_AMOUNT.saturating_mul(IA_MULTIPLIER.into());
+      <T as Config>::CurrencyTidefi::mint_into(CurrencyId::Wrapped(TEST_TOKEN), &caller, balance as u128).expect("Unable to mint token");
+      Pallet::<T>::approve_stake_funder(RawOrigin::Signed(beneficiary.clone()).into(), caller.clone(), true).expect("Unable to approve funder");
+   }: _(RawOrigin::Signed(caller), beneficiary, CurrencyId::Wrapped(TEST_TOKEN), INITIAL_AMOUNT, (14400_u32 * 15_u32).into(), Some(reward_recipient))
+
+   approve_stake_funder {
+      let caller: T::AccountId = whitelisted_caller();
+      let funder: T::AccountId = account("funder", 0, 0);
+   }: _(RawOrigin::Signed(caller), funder, true)
+
    unstake {
       let caller: T::AccountId = whitelisted_caller();
       let balance = INITIAL_AMOUNT.saturating_mul(IA_MULTIPLIER.into());
       <T as Config>::CurrencyTidefi::mint_into(CurrencyId::Wrapped(TEST_TOKEN), &caller, balance as u128).expect("Unable to mint token");
 
-      let stake_id = Pallet::<T>::add_account_stake(&caller, CurrencyId::Wrapped(TEST_TOKEN), INITIAL_AMOUNT, (14400_u32 * 15_u32).into()).expect("Unable to stake asset");
+      let stake_id = Pallet::<T>::add_account_stake(&caller, &caller, CurrencyId::Wrapped(TEST_TOKEN), INITIAL_AMOUNT, (14400_u32 * 15_u32).into()).expect("Unable to stake asset");
+   }: _(RawOrigin::Signed(caller), stake_id, true)
+
+   unstake_with_tdfy_fee {
+      let caller: T::AccountId = whitelisted_caller();
+      let balance = INITIAL_AMOUNT.saturating_mul(IA_MULTIPLIER.into());
+      <T as Config>::CurrencyTidefi::mint_into(CurrencyId::Wrapped(TEST_TOKEN), &caller, balance as u128).expect("Unable to mint token");
+      <T as Config>::CurrencyTidefi::mint_into(CurrencyId::Tdfy, &caller, balance as u128).expect("Unable to mint token");
+      <T as Config>::Sunrise::register_exchange_rate(vec![(TEST_TOKEN, 1_000_000_000_000)]).expect("Unable to register exchange rate");
+
+      let stake_id = Pallet::<T>::add_account_stake(&caller, &caller, CurrencyId::Wrapped(TEST_TOKEN), INITIAL_AMOUNT, (14400_u32 * 15_u32).into()).expect("Unable to stake asset");
    }: _(RawOrigin::Signed(caller), stake_id, true)
+
+   cancel_unstake {
+      let caller: T::AccountId = whitelisted_caller();
+      let balance = INITIAL_AMOUNT.saturating_mul(IA_MULTIPLIER.into());
+      <T as Config>::CurrencyTidefi::mint_into(CurrencyId::Wrapped(TEST_TOKEN), &caller, balance as u128).expect("Unable to mint token");
+
+      let stake_id = Pallet::<T>::add_account_stake(&caller, &caller, CurrencyId::Wrapped(TEST_TOKEN), INITIAL_AMOUNT, (14400_u32 * 15_u32).into()).expect("Unable to stake asset");
+      Pallet::<T>::unstake(RawOrigin::Signed(caller.clone()).into(), stake_id, true).expect("Unable to queue unstake");
+   }: _(RawOrigin::Signed(caller), stake_id)
+
+   set_receipt_currency {
+   }: _(RawOrigin::Root, CurrencyId::Tdfy, Some(CurrencyId::Wrapped(TEST_TOKEN)))
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -60,17 +60,49 @@ pub mod pallet {
     BoundedVec, PalletId,
   };
   use frame_system::pallet_prelude::*;
+  use pallet_asset_registry::AssetChainInfoExt;
+  use pallet_security::{ExtrinsicIdExt, Subsystem, SubsystemsStatusExt};
   use sp_runtime::{
-    traits::{AccountIdConversion, Saturating},
+    traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
     ArithmeticError, Percent, Perquintill,
   };
+  use sp_std::collections::btree_map::BTreeMap;
   use tidefi_primitives::{
-    pallet::{AssetRegistryExt, SecurityExt, StakingExt},
+    pallet::{AssetRegistryExt, SecurityExt, StakingExt, SunriseExt},
     Balance, BalanceInfo, CurrencyId, Hash, SessionIndex, Stake, StakeCurrencyMeta, StakeStatus,
   };
 
   /// The current storage version.
-  const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+  const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
+  /// Interface used by other pallets to stake funds on behalf of an account.
+  pub trait StakeFunds<AccountId, BlockNumber> {
+    /// Stake `amount` of `currency_id` of the account for `duration` blocks.
+    fn stake_funds(
+      account_id: &AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+      duration: BlockNumber,
+    ) -> Result<Hash, DispatchError>;
+  }
+
+  /// Interface used to read the fees sessions, to project the staking rewards.
+  pub trait FeeSessionsInfo<BlockNumber> {
+    /// Number of blocks of a fees session.
+    fn session_blocks_count() -> BlockNumber;
+    /// Share of the session fees transferred to the staking account for the stakers rewards.
+    fn stakers_fee_share() -> Percent;
+  }
+
+  impl<BlockNumber: Zero> FeeSessionsInfo<BlockNumber> for () {
+    fn session_blocks_count() -> BlockNumber {
+      Zero::zero()
+    }
+
+    fn stakers_fee_share() -> Percent {
+      Percent::zero()
+    }
+  }
 
   #[pallet::config]
   /// Configure the pallet by specifying the parameters and types on which it depends.
@@ -98,19 +130,42 @@ pub mod pallet {
     #[pallet::constant]
     type BlocksForceUnstake: Get<Self::BlockNumber>;
 
+    /// Percentage of the force-unstake fee refunded when a queued unstake is cancelled.
+    #[pallet::constant]
+    type CancelUnstakeFeeRefund: Get<Percent>;
+
+    /// Account receiving the force-unstake fees (fees pallet, treasury...).
+    /// When `None`, the fees are kept in the staking pool account.
+    type UnstakeFeeDestination: Get<Option<Self::AccountId>>;
+
+    /// Number of past sessions used to average the session fees when projecting the rewards.
+    #[pallet::constant]
+    type RewardsProjectionSessions: Get<SessionIndex>;
+
     /// Weight information for extrinsics in this pallet.
     type WeightInfo: WeightInfo;
 
     /// Asset registry traits
-    type AssetRegistry: AssetRegistryExt;
+    type AssetRegistry: AssetRegistryExt + AssetChainInfoExt;
 
     /// Security traits
-    type Security: SecurityExt<Self::AccountId, Self::BlockNumber>;
+    type Security: SecurityExt<Self::AccountId, Self::BlockNumber>
+      + SubsystemsStatusExt
+      + ExtrinsicIdExt;
+
+    /// Tidefi sunrise traits
+    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>;
+
+    /// Fees sessions, used to project the staking rewards
+    type Fees: FeeSessionsInfo<Self::BlockNumber>;
 
     /// Tidechain currency wrapper
     type CurrencyTidefi: Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
       + Mutate<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
       + Transfer<Self::AccountId, AssetId = CurrencyId, Balance = Balance>;
+
+    /// The origin which may enable the liquid staking receipts
+    type ForceOrigin: EnsureOrigin<Self::Origin>;
   }
 
   #[pallet::pallet]
@@ -123,6 +178,13 @@ pub mod pallet {
   #[pallet::getter(fn staking_pool)]
   pub type StakingPool<T: Config> = StorageMap<_, Blake2_128Concat, CurrencyId, Balance>;
 
+  /// Balance owed by the staking account, by currency: the principal of all stakes
+  /// and the queued unstake fees. The staking account balance should never be lower.
+  #[pallet::storage]
+  #[pallet::getter(fn staking_liabilities)]
+  pub type StakingLiabilities<T: Config> =
+    StorageMap<_, Blake2_128Concat, CurrencyId, Balance, ValueQuery>;
+
   /// Staking rewards defined by the council
   #[pallet::storage]
   #[pallet::getter(fn staking_rewards)]
@@ -154,6 +216,43 @@ pub mod pallet {
     ValueQuery,
   >;
 
+  /// Force-unstake fee paid for each queued unstake, kept until the unstake is processed
+  /// or cancelled.
+  #[pallet::storage]
+  #[pallet::getter(fn queued_unstake_fee)]
+  pub type QueuedUnstakeFees<T: Config> =
+    StorageMap<_, Blake2_128Concat, Hash, (CurrencyId, Balance)>;
+
+  /// Account receiving the staking rewards of a stake, when different from the stake owner.
+  #[pallet::storage]
+  #[pallet::getter(fn stake_reward_recipient)]
+  pub type StakeRewardRecipients<T: Config> = StorageMap<_, Blake2_128Concat, Hash, T::AccountId>;
+
+  /// Funders approved by an account to stake on its behalf.
+  #[pallet::storage]
+  #[pallet::getter(fn approved_stake_funder)]
+  pub type ApprovedStakeFunders<T: Config> =
+    StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, ()>;
+
+  /// Receipt currency minted to the stakers of a currency, when liquid staking is enabled.
+  #[pallet::storage]
+  #[pallet::getter(fn receipt_currency)]
+  pub type ReceiptCurrencies<T: Config> = StorageMap<_, Blake2_128Concat, CurrencyId, CurrencyId>;
+
+  /// Staked currency of each receipt currency in use.
+  #[pallet::storage]
+  #[pallet::getter(fn receipt_staked_currency)]
+  pub type ReceiptStakedCurrencies<T: Config> =
+    StorageMap<_, Blake2_128Concat, CurrencyId, CurrencyId>;
+
+  /// Receipt minted for each stake (stake owner, receipt currency and amount), burned from the
+  /// owner when the unstake is queued or processed, and minted back when a queued unstake is
+  /// cancelled.
+  #[pallet::storage]
+  #[pallet::getter(fn stake_receipt)]
+  pub type StakeReceipts<T: Config> =
+    StorageMap<_, Blake2_128Concat, Hash, (T::AccountId, CurrencyId, Balance)>;
+
   /// Map from all pending stored sessions.
   // When all stake that are bounded for this sessions are compounded, they got removed from the map.
   // When the map is empty, the `do_next_compound_interest_operation` is not triggered.
@@ -244,11 +343,39 @@ pub mod pallet {
       amount: Balance,
       duration: T::BlockNumber,
     },
+    /// The assets get staked on behalf of the beneficiary
+    StakedOnBehalf {
+      request_id: Hash,
+      funder: T::AccountId,
+      beneficiary: T::AccountId,
+      reward_recipient: Option<T::AccountId>,
+      currency_id: CurrencyId,
+      amount: Balance,
+      duration: T::BlockNumber,
+    },
+    /// The account approved or revoked a funder staking on its behalf
+    StakeFunderApprovalUpdated {
+      account_id: T::AccountId,
+      funder: T::AccountId,
+      approved: bool,
+    },
+    /// The receipt currency of a staked currency has been updated
+    ReceiptCurrencyUpdated {
+      currency_id: CurrencyId,
+      receipt_currency_id: Option<CurrencyId>,
+    },
     /// The assets unstaking has been queued
     UnstakeQueued {
       request_id: Hash,
       account_id: T::AccountId,
     },
+    /// The queued unstaking has been cancelled and the stake is active again
+    UnstakeCancelled {
+      request_id: Hash,
+      account_id: T::AccountId,
+      currency_id: CurrencyId,
+      refunded_fee: Balance,
+    },
     /// The assets get `unstaked` successfully
     Unstaked {
       request_id: Hash,
@@ -282,12 +409,33 @@ pub mod pallet {
     AmountTooSmall,
     /// The staked amount is above the maximum stake amount for this currency.
     AmountTooLarge,
+    /// The stake is already queued for unstaking.
+    UnstakeAlreadyQueued,
+    /// The stake is not queued for unstaking.
+    UnstakeNotQueued,
+    /// Invalid TDFY value in the order book
+    InvalidTdfyValue,
+    /// The funder is not approved by the beneficiary to stake on its behalf
+    StakeFunderNotApproved,
+    /// The receipt currency is not a registered wrapped currency, or is already in use.
+    InvalidReceiptCurrency,
+    /// Not enough receipt tokens to unstake.
+    InsufficientReceiptBalance,
+    /// Staking is paused
+    StakingPaused,
+    /// The currency is paused
+    CurrencyPaused,
   }
 
   #[pallet::hooks]
   impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
     fn on_runtime_upgrade() -> frame_support::weights::Weight {
-      migrations::migrate_to_v1::<T, Self>()
+      migrations::migrate_to_v1::<T, Self>().saturating_add(migrations::migrate_to_v2::<T, Self>())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade() -> Result<(), &'static str> {
+      Self::check_solvency()
     }
 
     /// Try to compute when chain is idle
@@ -362,6 +510,9 @@ pub mod pallet {
     ///
     /// Emits `Staked` event when successful.
     ///
+    /// The `request_id` of the event is predictable before the submission, with the
+    /// `tidefi_getRequestId` RPC for the signer and the extrinsic nonce.
+    ///
     /// Weight: `O(1)`
     #[pallet::weight(<T as pallet::Config>::WeightInfo::stake())]
     pub fn stake(
@@ -373,35 +524,73 @@ pub mod pallet {
       // 1. Make sure the transaction is signed
       let account_id = ensure_signed(origin)?;
 
-      // 2. Make sure the duration exist on chain
+      // 2. Stake the funds of the signer
+      let unique_stake_request_id =
+        Self::do_stake(&account_id, &account_id, currency_id, amount, duration)?;
+
+      // 3. Emit event on chain
+      Self::deposit_event(Event::<T>::Staked {
+        request_id: unique_stake_request_id,
+        account_id,
+        currency_id,
+        amount,
+        duration,
+      });
+
+      Ok(().into())
+    }
+
+    /// Stake currency on behalf of another account
+    ///
+    /// The funds are taken from the signer, but the stake belongs to the beneficiary,
+    /// who is the only one able to unstake it. The beneficiary must have approved the signer
+    /// with `approve_stake_funder`.
+    ///
+    /// - `beneficiary`: The owner of the stake
+    /// - `currency_id`: The currency to stake
+    /// - `amount`: The amount to stake
+    /// - `duration`: The duration is in numbers of blocks. (blocks are ~6seconds)
+    /// - `reward_recipient`: The account receiving the staking rewards when unstaked,
+    /// the beneficiary receives them when `None`
+    ///
+    /// Emits `StakedOnBehalf` event when successful.
+    ///
+    /// Weight: `O(1)`
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::stake_for())]
+    pub fn stake_for(
+      origin: OriginFor<T>,
+      beneficiary: T::AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+      duration: T::BlockNumber,
+      reward_recipient: Option<T::AccountId>,
+    ) -> DispatchResultWithPostInfo {
+      // 1. Make sure the transaction is signed
+      let account_id = ensure_signed(origin)?;
+
+      // 2. Make sure the beneficiary approved the signer, so its stakes cap can't be filled
       ensure!(
-        StakingPeriodRewards::<T>::get()
-          .into_iter()
-          .find(|(iter_duration, _)| *iter_duration == duration)
-          .is_some(),
-        Error::<T>::InvalidDuration
+        account_id == beneficiary
+          || ApprovedStakeFunders::<T>::contains_key(&beneficiary, &account_id),
+        Error::<T>::StakeFunderNotApproved
       );
 
-      // 3. If we have the metadata of this currency, make sure the amount isn't too low or too high
-      if let Some(currency_meta) = StakingCurrencyMeta::<T>::get(currency_id) {
-        ensure!(
-          amount >= currency_meta.minimum_amount,
-          Error::<T>::AmountTooSmall
-        );
-        ensure!(
-          amount <= currency_meta.maximum_amount,
-          Error::<T>::AmountTooLarge
-        );
-      }
-
-      // create unique hash
+      // 3. Stake the funds of the signer for the beneficiary
       let unique_stake_request_id =
-        Self::add_account_stake(&account_id, currency_id, amount, duration)?;
+        Self::do_stake(&account_id, &beneficiary, currency_id, amount, duration)?;
 
-      // 4. Emit event on chain
-      Self::deposit_event(Event::<T>::Staked {
+      // 4. Keep track of the reward recipient, if it's not the beneficiary
+      let reward_recipient = reward_recipient.filter(|recipient| *recipient != beneficiary);
+      if let Some(recipient) = &reward_recipient {
+        StakeRewardRecipients::<T>::insert(unique_stake_request_id, recipient);
+      }
+
+      // 5. Emit event on chain
+      Self::deposit_event(Event::<T>::StakedOnBehalf {
         request_id: unique_stake_request_id,
-        account_id,
+        funder: account_id,
+        beneficiary,
+        reward_recipient,
         currency_id,
         amount,
         duration,
@@ -410,8 +599,45 @@ pub mod pallet {
       Ok(().into())
     }
 
+    /// Approve or revoke a funder staking on behalf of the signer with `stake_for`
+    ///
+    /// - `funder`: The account funding the stakes
+    /// - `approved`: Whether the funder is allowed to stake on behalf of the signer
+    ///
+    /// Emits `StakeFunderApprovalUpdated` event when successful.
+    ///
+    /// Weight: `O(1)`
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::approve_stake_funder())]
+    pub fn approve_stake_funder(
+      origin: OriginFor<T>,
+      funder: T::AccountId,
+      approved: bool,
+    ) -> DispatchResultWithPostInfo {
+      // 1. Make sure the transaction is signed
+      let account_id = ensure_signed(origin)?;
+
+      // 2. Update the approval, existing stakes are not affected
+      if approved {
+        ApprovedStakeFunders::<T>::insert(&account_id, &funder, ());
+      } else {
+        ApprovedStakeFunders::<T>::remove(&account_id, &funder);
+      }
+
+      // 3. Emit event on chain
+      Self::deposit_event(Event::<T>::StakeFunderApprovalUpdated {
+        account_id,
+        funder,
+        approved,
+      });
+
+      Ok(().into())
+    }
+
     /// Unstake
     ///
+    /// The receipt of a stake with a liquid staking receipt is tied to this stake, its owner
+    /// must hold the receipt amount, which is burned.
+    ///
     /// - `stake_id`: Unique Stake ID
     /// - `force_unstake`: Unstake with extra fees, even if the staking is not expired
     ///
@@ -427,69 +653,163 @@ pub mod pallet {
       // 1. Make sure the transaction is signed
       let account_id = ensure_signed(origin)?;
 
-      // 2. Get Staking request for this user
+      // 2. Unstake, the force-unstake fees are paid in the staked currency
+      Self::do_unstake(account_id, stake_id, force_unstake, false)?;
+
+      Ok(().into())
+    }
+
+    /// Unstake, paying the force-unstake fees in TDFY
+    ///
+    /// The fees are converted with the current exchange rate of the staked currency.
+    ///
+    /// - `stake_id`: Unique Stake ID
+    /// - `force_unstake`: Unstake with extra fees, even if the staking is not expired
+    ///
+    /// Emits `Unstaked` event when successful.
+    ///
+    /// Weight: `O(1)`
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::unstake_with_tdfy_fee())]
+    pub fn unstake_with_tdfy_fee(
+      origin: OriginFor<T>,
+      stake_id: Hash,
+      force_unstake: bool,
+    ) -> DispatchResultWithPostInfo {
+      // 1. Make sure the transaction is signed
+      let account_id = ensure_signed(origin)?;
+
+      // 2. Unstake, the force-unstake fees are paid in TDFY
+      Self::do_unstake(account_id, stake_id, force_unstake, true)?;
+
+      Ok(().into())
+    }
+
+    /// Cancel a queued unstake
+    ///
+    /// The stake is active again and the force-unstake fee is partially refunded,
+    /// based on the `CancelUnstakeFeeRefund` policy. The receipt burned when the unstake
+    /// was queued is minted back to the stake owner.
+    ///
+    /// - `stake_id`: Unique Stake ID
+    ///
+    /// Emits `UnstakeCancelled` event when successful.
+    ///
+    /// Weight: `O(1)`
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::cancel_unstake())]
+    pub fn cancel_unstake(origin: OriginFor<T>, stake_id: Hash) -> DispatchResultWithPostInfo {
+      // 1. Make sure the transaction is signed
+      let account_id = ensure_signed(origin)?;
+
+      // 2. Get Staking request for this user and make sure it's queued
       let stake =
         Self::get_account_stake(&account_id, stake_id).ok_or(Error::<T>::InvalidStakeId)?;
+      ensure!(
+        stake.status != StakeStatus::Staked,
+        Error::<T>::UnstakeNotQueued
+      );
 
-      // 3. Check the expiration and if we are forcing it (queue)
-      let expected_block_expiration = stake.initial_block.saturating_add(stake.duration);
-      let staking_is_expired = T::Security::get_current_block_count() >= expected_block_expiration;
+      // 3. Remove from the unstake queue
+      Self::remove_from_unstake_queue(&account_id, stake_id)?;
 
-      if staking_is_expired {
-        // we can process to unstaking immediately
-        Self::process_unstake(&account_id, stake_id)?;
-        Self::deposit_event(Event::<T>::Unstaked {
-          request_id: stake_id,
-          account_id,
-          currency_id: stake.currency_id,
-          initial_balance: stake.initial_balance,
-          final_balance: stake.principal,
-        });
-      } else {
-        ensure!(force_unstake, Error::<T>::UnstakingNotReady);
+      // 4. Update `AccountStakes` status
+      AccountStakes::<T>::try_mutate(account_id.clone(), |stakes| -> DispatchResult {
+        let stake = stakes
+          .iter_mut()
+          .find(|stake| stake.unique_id == stake_id)
+          .ok_or(Error::<T>::InvalidStakeId)?;
+        stake.status = StakeStatus::Staked;
+        Ok(())
+      })?;
 
-        // FIXME: Validate not already queued
+      // 5. Mint back the receipt burned when the unstake was queued
+      if let Some((_, receipt_currency_id, receipt_amount)) = Self::stake_receipt(stake_id) {
+        T::CurrencyTidefi::mint_into(receipt_currency_id, &account_id, receipt_amount)?;
+      }
 
-        // we should add to unstaking queue and take immeditately the extra fees
-        // for the queue storage
+      // 6. Refund the fee based on the refund policy
+      let mut refunded_fee = 0;
+      if let Some((fee_currency_id, unstaking_fee)) = QueuedUnstakeFees::<T>::take(stake_id) {
+        Self::decrease_liabilities(fee_currency_id, unstaking_fee);
+        refunded_fee = T::CancelUnstakeFeeRefund::get() * unstaking_fee;
+        if refunded_fee > 0 {
+          T::CurrencyTidefi::transfer(
+            fee_currency_id,
+            &Self::account_id(),
+            &account_id,
+            refunded_fee,
+            false,
+          )
+          .map_err(|_| Error::<T>::TransferFeesFailed)?;
+        }
+        Self::transfer_unstake_fee_to_destination(
+          fee_currency_id,
+          unstaking_fee.saturating_sub(refunded_fee),
+        )?;
+      }
 
-        // take the fee
-        // FIXME: would be great to convert to TDFY
-        let unstaking_fee = Self::unstake_fee() * stake.initial_balance;
-        T::CurrencyTidefi::can_withdraw(stake.currency_id, &account_id, unstaking_fee)
-          .into_result()
-          .map_err(|_| Error::<T>::InsufficientBalance)?;
+      // 7. Emit event on chain
+      Self::deposit_event(Event::<T>::UnstakeCancelled {
+        request_id: stake_id,
+        account_id,
+        currency_id: stake.currency_id,
+        refunded_fee,
+      });
 
-        let expected_block_end =
-          T::Security::get_current_block_count().saturating_add(T::BlocksForceUnstake::get());
-        UnstakeQueue::<T>::try_append((account_id.clone(), stake_id, expected_block_end))
-          .map_err(|_| Error::<T>::UnstakeQueueCapExceeded)?;
+      Ok(().into())
+    }
 
-        // update `AccountStakes` status
-        AccountStakes::<T>::try_mutate(account_id.clone(), |stakes| -> DispatchResult {
-          let stake = stakes
-            .iter_mut()
-            .find(|stake| stake.unique_id == stake_id)
-            .ok_or(Error::<T>::InvalidStakeId)?;
-          stake.status = StakeStatus::PendingUnlock(expected_block_end);
-          Ok(())
-        })?;
+    /// Enable or disable the liquid staking receipts of a currency
+    ///
+    /// When enabled, the receipt currency is minted to the stake owner when staking, and
+    /// burned from the stake owner when unstaking. The receipt currency must be a dedicated
+    /// currency registered in the asset registry, not bridged from an external chain, and
+    /// without any issuance.
+    ///
+    /// - `currency_id`: The staked currency
+    /// - `receipt_currency_id`: The receipt currency, `None` to disable the receipts
+    ///
+    /// Emits `ReceiptCurrencyUpdated` event when successful.
+    ///
+    /// Weight: `O(1)`
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::set_receipt_currency())]
+    pub fn set_receipt_currency(
+      origin: OriginFor<T>,
+      currency_id: CurrencyId,
+      receipt_currency_id: Option<CurrencyId>,
+    ) -> DispatchResultWithPostInfo {
+      // 1. Make sure the origin is allowed
+      T::ForceOrigin::ensure_origin(origin)?;
 
-        T::CurrencyTidefi::transfer(
-          stake.currency_id,
-          &account_id,
-          &Self::account_id(),
-          unstaking_fee,
-          true,
-        )
-        .map_err(|_| Error::<T>::TransferFeesFailed)?;
+      // 2. Make sure the receipt currency is a dedicated registered wrapped currency, no one
+      // can hold receipts not minted by the staking pallet
+      if let Some(receipt_currency_id) = receipt_currency_id {
+        ensure!(
+          receipt_currency_id != CurrencyId::Tdfy
+            && receipt_currency_id != currency_id
+            && T::AssetRegistry::is_currency_enabled(receipt_currency_id)
+            && !T::AssetRegistry::is_bridged(receipt_currency_id)
+            && T::CurrencyTidefi::total_issuance(receipt_currency_id).is_zero()
+            && StakingPool::<T>::get(receipt_currency_id).is_none()
+            && ReceiptStakedCurrencies::<T>::get(receipt_currency_id).is_none(),
+          Error::<T>::InvalidReceiptCurrency
+        );
+      }
 
-        Self::deposit_event(Event::<T>::UnstakeQueued {
-          request_id: stake_id,
-          account_id,
-        });
+      // 3. Update the receipt currency, existing stakes keep their receipts
+      if let Some(previous_receipt_currency_id) = ReceiptCurrencies::<T>::take(currency_id) {
+        ReceiptStakedCurrencies::<T>::remove(previous_receipt_currency_id);
+      }
+      if let Some(receipt_currency_id) = receipt_currency_id {
+        ReceiptCurrencies::<T>::insert(currency_id, receipt_currency_id);
+        ReceiptStakedCurrencies::<T>::insert(receipt_currency_id, currency_id);
       }
 
+      // 4. Emit event on chain
+      Self::deposit_event(Event::<T>::ReceiptCurrencyUpdated {
+        currency_id,
+        receipt_currency_id,
+      });
+
       Ok(().into())
     }
   }
@@ -500,21 +820,66 @@ pub mod pallet {
       <T as pallet::Config>::StakePalletId::get().into_account_truncating()
     }
 
-    pub fn add_account_stake(
+    fn do_stake(
+      funder: &T::AccountId,
       account_id: &T::AccountId,
       currency_id: CurrencyId,
       amount: Balance,
       duration: T::BlockNumber,
     ) -> Result<Hash, DispatchError> {
-      // create unique hash
-      let unique_stake_request_id = <T as Config>::Security::get_unique_id(account_id.clone());
+      // 0. Make sure the staking and the currency are not paused
+      ensure!(
+        T::Security::is_subsystem_running(Subsystem::Staking),
+        Error::<T>::StakingPaused
+      );
+      ensure!(
+        T::Security::is_currency_running(currency_id),
+        Error::<T>::CurrencyPaused
+      );
+
+      // 1. Make sure the duration exist on chain
+      ensure!(
+        StakingPeriodRewards::<T>::get()
+          .into_iter()
+          .find(|(iter_duration, _)| *iter_duration == duration)
+          .is_some(),
+        Error::<T>::InvalidDuration
+      );
+
+      // 2. If we have the metadata of this currency, make sure the amount isn't too low or too high
+      if let Some(currency_meta) = StakingCurrencyMeta::<T>::get(currency_id) {
+        ensure!(
+          amount >= currency_meta.minimum_amount,
+          Error::<T>::AmountTooSmall
+        );
+        ensure!(
+          amount <= currency_meta.maximum_amount,
+          Error::<T>::AmountTooLarge
+        );
+      }
 
+      // 3. Transfer the funds and insert the stake, the caller emits the event
+      Self::add_account_stake(funder, account_id, currency_id, amount, duration)
+    }
+
+    pub fn add_account_stake(
+      funder: &T::AccountId,
+      account_id: &T::AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+      duration: T::BlockNumber,
+    ) -> Result<Hash, DispatchError> {
       // 3. Transfer the funds into the staking pool
-      T::CurrencyTidefi::can_withdraw(currency_id, account_id, amount)
+      T::CurrencyTidefi::can_withdraw(currency_id, funder, amount)
         .into_result()
         .map_err(|_| Error::<T>::InsufficientBalance)?;
 
-      T::CurrencyTidefi::transfer(currency_id, account_id, &Self::account_id(), amount, false)?;
+      // create unique hash, derived from the signer of the extrinsic when the stake is
+      // requested by a signed extrinsic, so the signer can predict it
+      let unique_stake_request_id = <T as Config>::Security::get_extrinsic_unique_id()
+        .unwrap_or_else(|| <T as Config>::Security::get_unique_id(funder.clone()));
+
+      T::CurrencyTidefi::transfer(currency_id, funder, &Self::account_id(), amount, false)?;
 
       // 4. Update our `StakingPool` storage
       StakingPool::<T>::try_mutate(currency_id, |balance| -> DispatchResult {
@@ -525,6 +890,7 @@ pub mod pallet {
         }
         Ok(())
       })?;
+      Self::increase_liabilities(currency_id, amount);
 
       // 5. Insert the new staking
       let initial_block = T::Security::get_current_block_count();
@@ -543,9 +909,114 @@ pub mod pallet {
           .map_err(|_| DispatchError::Other("Invalid stake; eqd"))
       })?;
 
+      // 6. Mint the receipt to the stake owner, when liquid staking is enabled
+      if let Some(receipt_currency_id) = Self::receipt_currency(currency_id) {
+        T::CurrencyTidefi::mint_into(receipt_currency_id, account_id, amount)?;
+        StakeReceipts::<T>::insert(
+          unique_stake_request_id,
+          (account_id.clone(), receipt_currency_id, amount),
+        );
+      }
+
       Ok(unique_stake_request_id)
     }
 
+    fn do_unstake(
+      account_id: T::AccountId,
+      stake_id: Hash,
+      force_unstake: bool,
+      pay_fee_in_tdfy: bool,
+    ) -> DispatchResult {
+      // 1. Get Staking request for this user
+      let stake =
+        Self::get_account_stake(&account_id, stake_id).ok_or(Error::<T>::InvalidStakeId)?;
+
+      // 2. Check the expiration and if we are forcing it (queue)
+      let expected_block_expiration = stake.initial_block.saturating_add(stake.duration);
+      let staking_is_expired = T::Security::get_current_block_count() >= expected_block_expiration;
+
+      if staking_is_expired {
+        // the stake may have expired while waiting in the unstake queue,
+        // the receipt has been burned already in this case
+        if let StakeStatus::PendingUnlock(_) = stake.status {
+          Self::remove_from_unstake_queue(&account_id, stake_id)?;
+          Self::release_queued_unstake_fee(stake_id);
+        } else {
+          Self::burn_stake_receipt(&account_id, stake_id)?;
+        }
+
+        // we can process to unstaking immediately
+        Self::process_unstake(&account_id, stake_id)?;
+        Self::deposit_event(Event::<T>::Unstaked {
+          request_id: stake_id,
+          account_id,
+          currency_id: stake.currency_id,
+          initial_balance: stake.initial_balance,
+          final_balance: stake.principal,
+        });
+      } else {
+        ensure!(force_unstake, Error::<T>::UnstakingNotReady);
+        ensure!(
+          stake.status == StakeStatus::Staked,
+          Error::<T>::UnstakeAlreadyQueued
+        );
+
+        // we should add to unstaking queue and take immeditately the extra fees
+        // for the queue storage
+
+        // take the fee
+        let mut unstaking_fee = Self::unstake_fee() * stake.initial_balance;
+        let mut fee_currency_id = stake.currency_id;
+        if pay_fee_in_tdfy && stake.currency_id != CurrencyId::Tdfy {
+          unstaking_fee = T::Sunrise::try_get_tdfy_value(stake.currency_id, unstaking_fee)?;
+          ensure!(unstaking_fee > 0, Error::<T>::InvalidTdfyValue);
+          fee_currency_id = CurrencyId::Tdfy;
+        }
+
+        T::CurrencyTidefi::can_withdraw(fee_currency_id, &account_id, unstaking_fee)
+          .into_result()
+          .map_err(|_| Error::<T>::InsufficientBalance)?;
+
+        let expected_block_end =
+          T::Security::get_current_block_count().saturating_add(T::BlocksForceUnstake::get());
+        UnstakeQueue::<T>::try_append((account_id.clone(), stake_id, expected_block_end))
+          .map_err(|_| Error::<T>::UnstakeQueueCapExceeded)?;
+
+        // update `AccountStakes` status
+        AccountStakes::<T>::try_mutate(account_id.clone(), |stakes| -> DispatchResult {
+          let stake = stakes
+            .iter_mut()
+            .find(|stake| stake.unique_id == stake_id)
+            .ok_or(Error::<T>::InvalidStakeId)?;
+          stake.status = StakeStatus::PendingUnlock(expected_block_end);
+          Ok(())
+        })?;
+
+        // the fee is kept in the staking account until the unstake is processed or cancelled
+        T::CurrencyTidefi::transfer(
+          fee_currency_id,
+          &account_id,
+          &Self::account_id(),
+          unstaking_fee,
+          true,
+        )
+        .map_err(|_| Error::<T>::TransferFeesFailed)?;
+
+        QueuedUnstakeFees::<T>::insert(stake_id, (fee_currency_id, unstaking_fee));
+        Self::increase_liabilities(fee_currency_id, unstaking_fee);
+
+        // the receipt can't be used anymore once the unstake is queued
+        Self::burn_stake_receipt(&account_id, stake_id)?;
+
+        Self::deposit_event(Event::<T>::UnstakeQueued {
+          request_id: stake_id,
+          account_id,
+        });
+      }
+
+      Ok(())
+    }
+
     fn get_account_stake(
       account_id: &T::AccountId,
       stake_id: Hash,
@@ -555,6 +1026,118 @@ pub mod pallet {
         .find(|stake| stake.unique_id == stake_id)
     }
 
+    fn increase_liabilities(currency_id: CurrencyId, amount: Balance) {
+      StakingLiabilities::<T>::mutate(currency_id, |liabilities| {
+        *liabilities = liabilities.saturating_add(amount)
+      });
+    }
+
+    fn decrease_liabilities(currency_id: CurrencyId, amount: Balance) {
+      StakingLiabilities::<T>::mutate(currency_id, |liabilities| {
+        *liabilities = liabilities.saturating_sub(amount)
+      });
+    }
+
+    // Balance of the staking account not owed to anyone, available for the stakers rewards
+    pub fn unallocated_rewards(currency_id: CurrencyId) -> Balance {
+      T::CurrencyTidefi::balance(currency_id, &Self::account_id())
+        .saturating_sub(Self::staking_liabilities(currency_id))
+    }
+
+    // Make sure the liabilities match all the stakes and queued fees, and that the
+    // staking account is able to pay them back, for each currency
+    pub fn check_solvency() -> Result<(), &'static str> {
+      let mut expected_liabilities: BTreeMap<CurrencyId, Balance> = BTreeMap::new();
+      for (_, account_stakes) in AccountStakes::<T>::iter() {
+        for stake in account_stakes {
+          let liabilities = expected_liabilities.entry(stake.currency_id).or_default();
+          *liabilities = liabilities.saturating_add(stake.principal);
+        }
+      }
+      for (_, (currency_id, unstaking_fee)) in QueuedUnstakeFees::<T>::iter() {
+        let liabilities = expected_liabilities.entry(currency_id).or_default();
+        *liabilities = liabilities.saturating_add(unstaking_fee);
+      }
+
+      for (currency_id, liabilities) in StakingLiabilities::<T>::iter() {
+        ensure!(
+          expected_liabilities.get(&currency_id).copied().unwrap_or(0) == liabilities,
+          "Staking liabilities doesn't match the stakes"
+        );
+      }
+
+      for (currency_id, liabilities) in expected_liabilities {
+        ensure!(
+          Self::staking_liabilities(currency_id) == liabilities,
+          "Staking liabilities doesn't match the stakes"
+        );
+        ensure!(
+          T::CurrencyTidefi::balance(currency_id, &Self::account_id()) >= liabilities,
+          "Staking account is insolvent"
+        );
+      }
+
+      Ok(())
+    }
+
+    // Burn the receipt minted for the stake, the stake owner must hold the full receipt amount.
+    // The receipt is kept until the stake is unstaked, to mint it back if the unstake is cancelled.
+    fn burn_stake_receipt(account_id: &T::AccountId, stake_id: Hash) -> DispatchResult {
+      if let Some((_, receipt_currency_id, receipt_amount)) = Self::stake_receipt(stake_id) {
+        T::CurrencyTidefi::can_withdraw(receipt_currency_id, account_id, receipt_amount)
+          .into_result()
+          .map_err(|_| Error::<T>::InsufficientReceiptBalance)?;
+        T::CurrencyTidefi::burn_from(receipt_currency_id, account_id, receipt_amount)?;
+      }
+      Ok(())
+    }
+
+    // Release the fee taken for a queued unstake to the fee destination, the unstake isn't
+    // blocked if the transfer fails, the fee stays queued and in the liabilities instead
+    fn release_queued_unstake_fee(stake_id: Hash) {
+      if let Some((currency_id, unstaking_fee)) = Self::queued_unstake_fee(stake_id) {
+        match Self::transfer_unstake_fee_to_destination(currency_id, unstaking_fee) {
+          Ok(()) => {
+            QueuedUnstakeFees::<T>::remove(stake_id);
+            Self::decrease_liabilities(currency_id, unstaking_fee);
+          }
+          Err(err) => log!(error, "Unable to transfer unstake fee {:?}", err),
+        }
+      }
+    }
+
+    fn transfer_unstake_fee_to_destination(
+      currency_id: CurrencyId,
+      amount: Balance,
+    ) -> DispatchResult {
+      if let Some(destination) = T::UnstakeFeeDestination::get() {
+        if amount > 0 {
+          T::CurrencyTidefi::transfer(
+            currency_id,
+            &Self::account_id(),
+            &destination,
+            amount,
+            false,
+          )
+          .map_err(|_| Error::<T>::TransferFeesFailed)?;
+        }
+      }
+      Ok(())
+    }
+
+    fn remove_from_unstake_queue(account_id: &T::AccountId, stake_id: Hash) -> DispatchResult {
+      UnstakeQueue::<T>::try_mutate(|queue| -> DispatchResult {
+        let position = queue
+          .iter()
+          .position(|(queued_account_id, queued_stake_id, _)| {
+            queued_account_id == account_id && *queued_stake_id == stake_id
+          })
+          .ok_or(Error::<T>::UnstakeNotQueued)?;
+        queue.remove(position);
+        Ok(())
+      })
+    }
+
     fn process_unstake(account_id: &T::AccountId, stake_id: Hash) -> DispatchResult {
       let current_stake =
         Self::get_account_stake(&account_id, stake_id).ok_or(Error::<T>::InvalidStakeId)?;
@@ -569,10 +1152,11 @@ pub mod pallet {
           .into_result()
           .map_err(|_| Error::<T>::InsufficientBalance)?;
 
+          // the pool only tracks the staked amounts, the compounded rewards are never added to it
           StakingPool::<T>::try_mutate(current_stake.currency_id, |balance| -> DispatchResult {
             if let Some(b) = balance {
               *balance = Some(
-                b.checked_sub(current_stake.principal)
+                b.checked_sub(current_stake.initial_balance)
                   .ok_or(ArithmeticError::Underflow)?,
               )
             }
@@ -580,15 +1164,37 @@ pub mod pallet {
           })
           .map_err(|_| Error::<T>::NotEnoughInPoolToUnstake)?;
 
+          // the rewards are paid to the reward recipient, if any
+          let mut owner_amount = current_stake.principal;
+          if let Some(reward_recipient) = StakeRewardRecipients::<T>::take(stake_id) {
+            let rewards = current_stake
+              .principal
+              .saturating_sub(current_stake.initial_balance);
+            if rewards > 0 {
+              T::CurrencyTidefi::transfer(
+                current_stake.currency_id,
+                &Self::account_id(),
+                &reward_recipient,
+                rewards,
+                false,
+              )
+              .map_err(|_| Error::<T>::TransferFailed)?;
+              owner_amount = owner_amount.saturating_sub(rewards);
+            }
+          }
+
           T::CurrencyTidefi::transfer(
             current_stake.currency_id,
             &Self::account_id(),
             &account_id,
-            current_stake.principal,
+            owner_amount,
             false,
           )
           .map_err(|_| Error::<T>::TransferFailed)?;
 
+          StakeReceipts::<T>::remove(stake_id);
+          Self::decrease_liabilities(current_stake.currency_id, current_stake.principal);
+
           if stakes.len() > 1 {
             stakes.retain(|stake| stake.unique_id != stake_id);
           } else {
@@ -672,9 +1278,12 @@ pub mod pallet {
                           staking_pool_for_this_currency,
                         );
 
-                        let proportional_reward = staking_pool_percentage * available_reward;
+                        // the rewards can't exceed what has been transferred by the fees pallet
+                        let proportional_reward = (staking_pool_percentage * available_reward)
+                          .min(Self::unallocated_rewards(currency_id));
                         active_stake.principal =
                           active_stake.principal.saturating_add(proportional_reward);
+                        Self::increase_liabilities(currency_id, proportional_reward);
                       }
                       // update the last session index for this stake
                       active_stake.last_session_index_compound = session_to_index;
@@ -767,16 +1376,22 @@ pub mod pallet {
         return Ok(());
       }
 
+      // release the fee first, so the staking account is not reaped with the fee in it
+      Self::release_queued_unstake_fee(*stake_id);
       Self::process_unstake(&account_id, *stake_id)?;
       UnstakeQueue::<T>::mutate(|v| v.remove(0));
 
       Ok(())
     }
 
-    // Get all stakes for the account, serialized for quick RPC call
+    // Get all stakes for the account with their reward recipient, serialized for quick RPC call
     pub fn get_account_stakes(
       account_id: &T::AccountId,
-    ) -> Vec<(CurrencyId, Stake<BalanceInfo, T::BlockNumber>)> {
+    ) -> Vec<(
+      CurrencyId,
+      Stake<BalanceInfo, T::BlockNumber>,
+      Option<T::AccountId>,
+    )> {
       let mut final_stakes = Vec::new();
 
       // we need to re-organize as our storage use a unique AccountId / CurrencyId key
@@ -797,11 +1412,154 @@ pub mod pallet {
             duration: account_stake.duration,
             status: account_stake.status,
           },
+          Self::stake_reward_recipient(account_stake.unique_id),
         ));
       }
 
       final_stakes
     }
+
+    // Estimate the rewards of a new stake until its expiration, based on the recent session fees
+    pub fn estimate_stake_rewards(
+      currency_id: CurrencyId,
+      amount: Balance,
+      duration: T::BlockNumber,
+    ) -> Result<Balance, DispatchError> {
+      // the stake would be rejected with an unknown duration
+      ensure!(
+        Self::staking_rewards()
+          .into_iter()
+          .any(|(period, _)| period == duration),
+        Error::<T>::InvalidDuration
+      );
+
+      // the new stake is added to the staking pool
+      let staking_pool = Self::staking_pool(currency_id)
+        .unwrap_or(0)
+        .saturating_add(amount);
+      Ok(Self::project_rewards(
+        currency_id,
+        amount,
+        staking_pool,
+        duration,
+        duration,
+      ))
+    }
+
+    // Estimate the total rewards of all stakes of the account at their expiration,
+    // including the rewards already compounded in the principal
+    pub fn estimate_account_stakes_rewards(account_id: &T::AccountId) -> Vec<(Hash, BalanceInfo)> {
+      let current_block = T::Security::get_current_block_count();
+      Self::account_stakes(account_id)
+        .into_iter()
+        .map(|stake| {
+          let staking_pool = Self::staking_pool(stake.currency_id).unwrap_or(0);
+          let remaining_blocks = stake
+            .initial_block
+            .saturating_add(stake.duration)
+            .saturating_sub(current_block);
+          let projected_rewards = Self::project_rewards(
+            stake.currency_id,
+            stake.initial_balance,
+            staking_pool,
+            stake.duration,
+            remaining_blocks,
+          );
+
+          (
+            stake.unique_id,
+            BalanceInfo {
+              amount: stake
+                .principal
+                .saturating_sub(stake.initial_balance)
+                .saturating_add(projected_rewards),
+            },
+          )
+        })
+        .collect()
+    }
+
+    // Project the rewards for the remaining blocks with the same math used when compounding,
+    // the stakes with a duration not rewarded anymore don't get any rewards
+    fn project_rewards(
+      currency_id: CurrencyId,
+      stake_amount: Balance,
+      staking_pool: Balance,
+      duration: T::BlockNumber,
+      remaining_blocks: T::BlockNumber,
+    ) -> Balance {
+      let reward_rate = Self::staking_rewards()
+        .into_iter()
+        .find(|(period, _)| *period == duration)
+        .map(|(_, reward)| reward)
+        .unwrap_or_else(Percent::zero);
+
+      let blocks_per_session = T::Fees::session_blocks_count();
+      if blocks_per_session.is_zero() {
+        return 0;
+      }
+      let remaining_sessions: Balance = (remaining_blocks / blocks_per_session).saturated_into();
+
+      let average_session_fees = Self::average_session_fees(currency_id);
+      let available_reward = reward_rate * average_session_fees;
+      let session_reward =
+        Perquintill::from_rational(stake_amount, staking_pool) * available_reward;
+
+      // the rewards can't exceed what the fees pallet transfers to the staking account for the
+      // stakers, and what is not allocated yet
+      let stakers_session_fees = T::Fees::stakers_fee_share() * average_session_fees;
+      session_reward.saturating_mul(remaining_sessions).min(
+        Self::unallocated_rewards(currency_id)
+          .saturating_add(stakers_session_fees.saturating_mul(remaining_sessions)),
+      )
+    }
+
+    // Average fees of the currency for the last `RewardsProjectionSessions` sessions
+    fn average_session_fees(currency_id: CurrencyId) -> Balance {
+      let projection_sessions = T::RewardsProjectionSessions::get();
+      if projection_sessions.is_zero() {
+        return 0;
+      }
+
+      let last_session = Self::interest_compound_last_session();
+      let first_session = last_session.saturating_sub(projection_sessions.saturating_sub(1));
+      let total_fees = (first_session..=last_session).fold(0, |total: Balance, session_index| {
+        total.saturating_add(Self::session_total_fees(session_index, currency_id))
+      });
+
+      total_fees / Balance::from(last_session.saturating_sub(first_session).saturating_add(1))
+    }
+
+    // Get the position in the unstake queue and the expected unlock block of a queued stake
+    pub fn get_unstake_queue_position(stake_id: Hash) -> Option<(u32, T::BlockNumber)> {
+      Self::unstake_queue()
+        .iter()
+        .enumerate()
+        .find(|(_, (_, queued_stake_id, _))| *queued_stake_id == stake_id)
+        .map(|(position, (_, _, expected_block_end))| (position as u32, *expected_block_end))
+    }
+  }
+
+  impl<T: Config> StakeFunds<T::AccountId, T::BlockNumber> for Pallet<T> {
+    fn stake_funds(
+      account_id: &T::AccountId,
+      currency_id: CurrencyId,
+      amount: Balance,
+      duration: T::BlockNumber,
+    ) -> Result<Hash, DispatchError> {
+      let unique_stake_request_id =
+        Self::do_stake(account_id, account_id, currency_id, amount, duration)?;
+
+      Self::deposit_event(Event::<T>::Staked {
+        request_id: unique_stake_request_id,
+        account_id: account_id.clone(),
+        currency_id,
+        amount,
+        duration,
+      });
+
+      Ok(unique_stake_request_id)
+    }
   }
 
   // implement the `StakingExt` functions
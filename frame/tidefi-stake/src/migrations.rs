@@ -84,3 +84,57 @@ pub fn migrate_to_v1<T: Config, P: GetStorageVersion + PalletInfoAccess>(
     T::DbWeight::get().reads(1)
   }
 }
+
+/// Migrate the pallet storage to v2.
+///
+/// Initialize the `StakingLiabilities` with the principal of all stakes and the queued
+/// unstake fees.
+pub fn migrate_to_v2<T: Config, P: GetStorageVersion + PalletInfoAccess>(
+) -> frame_support::weights::Weight {
+  let on_chain_storage_version = <P as GetStorageVersion>::on_chain_storage_version();
+  log!(
+    info,
+    "Running migration storage v2 with storage version {:?}",
+    on_chain_storage_version,
+  );
+
+  if on_chain_storage_version < 2 {
+    let mut reads: u64 = 0;
+    let mut writes: u64 = 0;
+
+    for (_, account_stakes) in AccountStakes::<T>::iter() {
+      reads += 1;
+      for stake in account_stakes {
+        StakingLiabilities::<T>::mutate(stake.currency_id, |liabilities| {
+          *liabilities = liabilities.saturating_add(stake.principal)
+        });
+        writes += 1;
+      }
+    }
+
+    for (_, (currency_id, unstaking_fee)) in QueuedUnstakeFees::<T>::iter() {
+      reads += 1;
+      StakingLiabilities::<T>::mutate(currency_id, |liabilities| {
+        *liabilities = liabilities.saturating_add(unstaking_fee)
+      });
+      writes += 1;
+    }
+
+    // update on-chain storage version
+    StorageVersion::new(2).put::<P>();
+    log!(
+      info,
+      "Running migration storage v2 with storage version {:?} was complete",
+      on_chain_storage_version,
+    );
+    // return migration weights
+    T::DbWeight::get().reads_writes(reads.saturating_add(1), writes.saturating_add(1))
+  } else {
+    log!(
+      info,
+      "Attempted to apply migration to v2 but failed because storage version is {:?}",
+      on_chain_storage_version,
+    );
+    T::DbWeight::get().reads(1)
+  }
+}
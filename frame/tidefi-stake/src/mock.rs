@@ -38,9 +38,12 @@ use sp_runtime::{
 };
 use std::marker::PhantomData;
 use system::EnsureRoot;
-use tidefi_primitives::{BlockNumber, CurrencyId, StakeCurrencyMeta};
+use tidefi_primitives::{
+  pallet::SunriseExt, AssetId, BlockNumber, CurrencyId, EraIndex, Fee, SessionIndex,
+  StakeCurrencyMeta,
+};
 
-use crate::pallet as pallet_tidefi_stake;
+use crate::{pallet as pallet_tidefi_stake, FeeSessionsInfo};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -113,6 +116,9 @@ parameter_types! {
   pub const QuorumPalletId: PalletId = PalletId(*b"qurm*pal");
   pub const AssetRegistryPalletId: PalletId = PalletId(*b"asst*pal");
   pub const BlocksForceUnstake: BlockNumber = 256;
+  pub const CancelUnstakeFeeRefund: Percent = Percent::from_percent(50);
+  pub const UnstakeFeeDestination: Option<AccountId> = Some(99);
+  pub const RewardsProjectionSessions: SessionIndex = 2;
   pub const MinimumPeriod: u64 = 5;
   pub const StakeAccountCap: u32 = 10;
   pub const UnstakeQueueCap: u32 = 100;
@@ -157,6 +163,11 @@ impl pallet_timestamp::Config for Test {
 
 impl pallet_security::Config for Test {
   type Event = Event;
+  type PauseOrigin = EnsureRoot<Self::AccountId>;
+  type EmergencyOrigin = system::EnsureSigned<Self::AccountId>;
+  type EmergencyInitiator = Self::AccountId;
+  type EmergencyPauseDuration = ConstU32<100>;
+  type PauseReasonLimit = ConstU32<256>;
   type WeightInfo = pallet_security::weights::SubstrateWeight<Test>;
 }
 
@@ -168,18 +179,97 @@ impl pallet_tidefi_stake::Config for Test {
   type StakeAccountCap = StakeAccountCap;
   type UnstakeQueueCap = UnstakeQueueCap;
   type BlocksForceUnstake = BlocksForceUnstake;
+  type CancelUnstakeFeeRefund = CancelUnstakeFeeRefund;
+  type UnstakeFeeDestination = UnstakeFeeDestination;
+  type RewardsProjectionSessions = RewardsProjectionSessions;
   type StakingRewardCap = StakingRewardCap;
   type AssetRegistry = AssetRegistry;
   type Security = Security;
+  type Sunrise = MockSunrise;
+  type Fees = MockFees;
+  type ForceOrigin = EnsureRoot<Self::AccountId>;
 }
 
 impl pallet_asset_registry::Config for Test {
   type Event = Event;
   type WeightInfo = pallet_asset_registry::weights::SubstrateWeight<Test>;
   type AssetRegistryPalletId = AssetRegistryPalletId;
+  type StringLimit = ConstU32<255>;
   type CurrencyTidefi = Adapter<AccountId>;
 }
 
+parameter_types! {
+  pub const BlocksPerSession: BlockNumber = 50;
+  pub static StakersFeeShare: Percent = Percent::from_percent(100);
+}
+
+// minimal fees implementation, only the sessions are used by the staking pallet
+pub struct MockFees;
+
+impl FeeSessionsInfo<BlockNumber> for MockFees {
+  fn session_blocks_count() -> BlockNumber {
+    BlocksPerSession::get()
+  }
+
+  fn stakers_fee_share() -> Percent {
+    StakersFeeShare::get()
+  }
+}
+
+// 1 test token (100) = 1 TDFY
+pub const TEST_TOKEN_TDFY_RATE: Balance = TDFY / 100;
+
+// minimal sunrise implementation, only the exchange rate is used by the staking pallet
+pub struct MockSunrise;
+
+impl SunriseExt<AccountId, BlockNumber> for MockSunrise {
+  fn account_id() -> AccountId {
+    0
+  }
+
+  fn try_get_tdfy_value(
+    currency_id: CurrencyId,
+    amount: Balance,
+  ) -> Result<Balance, DispatchError> {
+    match currency_id {
+      CurrencyId::Tdfy => Ok(amount),
+      CurrencyId::Wrapped(_) => Ok(amount.saturating_mul(TEST_TOKEN_TDFY_RATE)),
+    }
+  }
+
+  fn register_exchange_rate(_prices: Vec<(AssetId, Balance)>) -> Result<(), DispatchError> {
+    Ok(())
+  }
+
+  fn try_allocate_rewards_for_swap(
+    _account_id: &AccountId,
+    _era_index: EraIndex,
+    _fee: &Fee,
+    _currency_id: CurrencyId,
+  ) -> Result<Option<Balance>, DispatchError> {
+    Ok(None)
+  }
+
+  fn try_refund_gas_for_deposit(
+    _account_id: &AccountId,
+    _currency_id: CurrencyId,
+    _amount: Balance,
+  ) -> Result<Option<Balance>, DispatchError> {
+    Ok(None)
+  }
+
+  fn try_claim_sunrise_rewards(
+    _account_id: &AccountId,
+    _era_index: EraIndex,
+  ) -> Result<(), DispatchError> {
+    Ok(())
+  }
+
+  fn cooldown_blocks_count() -> BlockNumber {
+    0
+  }
+}
+
 // this is only the mock for benchmarking, it's implemented directly in the runtime
 pub struct Adapter<AccountId> {
   phantom: PhantomData<AccountId>,
@@ -368,13 +458,22 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
   .unwrap();
 
   pallet_asset_registry::GenesisConfig::<Test> {
-    assets: vec![(
-      CurrencyId::Wrapped(2),
-      "Test".into(),
-      "TEST".into(),
-      8,
-      vec![],
-    )],
+    assets: vec![
+      (
+        CurrencyId::Wrapped(2),
+        "Test".into(),
+        "TEST".into(),
+        8,
+        vec![],
+      ),
+      (
+        CurrencyId::Wrapped(3),
+        "Staked TDFY".into(),
+        "STDFY".into(),
+        12,
+        vec![],
+      ),
+    ],
     account: 0,
   }
   .assimilate_storage(&mut t)
@@ -16,28 +16,34 @@
 
 use crate::{
   mock::{
-    new_test_ext, AccountId, Adapter, Balance, Origin, Security, StakeAccountCap, Test,
-    TidefiStaking, UnstakeQueueCap,
+    new_test_ext, AccountId, Adapter, AssetRegistry, Balance, CancelUnstakeFeeRefund, Event,
+    Origin, Security, StakeAccountCap, StakersFeeShare, System, Test, TidefiStaking,
+    UnstakeFeeDestination, UnstakeQueueCap, TEST_TOKEN_TDFY_RATE,
   },
-  pallet as pallet_tidefi_stake, AccountStakes, Error, StakingPool, UnstakeQueue,
+  pallet as pallet_tidefi_stake, AccountStakes, Error, StakingPeriodRewards, StakingPool,
+  UnstakeQueue,
 };
 use frame_support::{
   assert_noop, assert_ok,
   traits::{
-    fungibles::{Inspect, Mutate},
+    fungibles::{Inspect, Mutate, Transfer},
     Hooks,
   },
   BoundedVec,
 };
+use pallet_asset_registry::AssetChainInfo;
 use sp_runtime::{
   traits::{AccountIdConversion, BadOrigin},
   ArithmeticError, DispatchError, Percent,
 };
 use std::str::FromStr;
-use tidefi_primitives::{pallet::StakingExt, BlockNumber, CurrencyId, Hash, Stake, StakeStatus};
+use tidefi_primitives::{
+  pallet::StakingExt, BalanceInfo, BlockNumber, CurrencyId, Hash, Stake, StakeStatus,
+};
 
 const TEST_TOKEN: u32 = 2;
 const TEST_TOKEN_CURRENCY_ID: CurrencyId = CurrencyId::Wrapped(TEST_TOKEN);
+const RECEIPT_CURRENCY_ID: CurrencyId = CurrencyId::Wrapped(3);
 const FIFTEEN_DAYS: BlockNumber = 14400 * 15;
 const BLOCKS_FORCE_UNLOCK: BlockNumber = 256;
 
@@ -417,6 +423,293 @@ mod stake {
   }
 }
 
+mod stake_for {
+  use super::*;
+
+  mod succeeds {
+    use super::*;
+
+    #[test]
+    fn for_native_asset() {
+      new_test_ext().execute_with(|| {
+        let context =
+          Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+        assert_ok!(TidefiStaking::approve_stake_funder(
+          Origin::signed(BOB_ACCOUNT_ID),
+          ALICE_ACCOUNT_ID,
+          true
+        ));
+        assert_ok!(TidefiStaking::stake_for(
+          Origin::signed(ALICE_ACCOUNT_ID),
+          BOB_ACCOUNT_ID,
+          CurrencyId::Tdfy,
+          context.tdfy_amount,
+          context.duration,
+          Some(CHARLIE_ACCOUNT_ID)
+        ));
+
+        // funds are taken from the funder
+        assert_eq!(
+          Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+          ALICE_INITIAL_ONE_THOUSAND_TDFYS - context.tdfy_amount
+        );
+        assert_eq!(
+          TidefiStaking::staking_pool(CurrencyId::Tdfy),
+          Some(context.tdfy_amount)
+        );
+
+        // the stake belongs to the beneficiary
+        assert!(TidefiStaking::account_stakes(ALICE_ACCOUNT_ID).is_empty());
+        let bob_stakes = TidefiStaking::get_account_stakes(&BOB_ACCOUNT_ID);
+        assert_eq!(bob_stakes.len(), 1);
+        assert_eq!(bob_stakes[0].1.initial_balance.amount, context.tdfy_amount);
+
+        assert_eq!(
+          TidefiStaking::stake_reward_recipient(bob_stakes[0].1.unique_id),
+          Some(CHARLIE_ACCOUNT_ID)
+        );
+        assert_eq!(bob_stakes[0].2, Some(CHARLIE_ACCOUNT_ID));
+
+        // only the on behalf event is emitted
+        assert!(!System::events().iter().any(|record| matches!(
+          record.event,
+          Event::TidefiStaking(pallet_tidefi_stake::Event::Staked { .. })
+        )));
+        System::assert_last_event(Event::TidefiStaking(
+          pallet_tidefi_stake::Event::StakedOnBehalf {
+            request_id: bob_stakes[0].1.unique_id,
+            funder: ALICE_ACCOUNT_ID,
+            beneficiary: BOB_ACCOUNT_ID,
+            reward_recipient: Some(CHARLIE_ACCOUNT_ID),
+            currency_id: CurrencyId::Tdfy,
+            amount: context.tdfy_amount,
+            duration: context.duration,
+          },
+        ));
+      });
+    }
+
+    #[test]
+    fn without_distinct_reward_recipient() {
+      new_test_ext().execute_with(|| {
+        let context =
+          Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+        assert_ok!(TidefiStaking::approve_stake_funder(
+          Origin::signed(BOB_ACCOUNT_ID),
+          ALICE_ACCOUNT_ID,
+          true
+        ));
+        assert_ok!(TidefiStaking::stake_for(
+          Origin::signed(ALICE_ACCOUNT_ID),
+          BOB_ACCOUNT_ID,
+          CurrencyId::Tdfy,
+          context.tdfy_amount,
+          context.duration,
+          Some(BOB_ACCOUNT_ID)
+        ));
+
+        let bob_stake_id = TidefiStaking::account_stakes(BOB_ACCOUNT_ID)
+          .first()
+          .unwrap()
+          .unique_id;
+        assert_eq!(TidefiStaking::stake_reward_recipient(bob_stake_id), None);
+      });
+    }
+
+    #[test]
+    fn and_rewards_are_paid_to_the_reward_recipient() {
+      new_test_ext().execute_with(|| {
+        const REWARDS: Balance = 2 * ONE_TDFY;
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+          .mint_tdfy(CHARLIE_ACCOUNT_ID, CHARLIE_INITIAL_ONE_THOUSAND_TDFYS);
+
+        assert_ok!(TidefiStaking::approve_stake_funder(
+          Origin::signed(BOB_ACCOUNT_ID),
+          ALICE_ACCOUNT_ID,
+          true
+        ));
+        assert_ok!(TidefiStaking::stake_for(
+          Origin::signed(ALICE_ACCOUNT_ID),
+          BOB_ACCOUNT_ID,
+          CurrencyId::Tdfy,
+          context.tdfy_amount,
+          context.duration,
+          Some(CHARLIE_ACCOUNT_ID)
+        ));
+
+        // compound some rewards in the stake
+        assert_ok!(Adapter::mint_into(
+          CurrencyId::Tdfy,
+          &context.staking_pallet_account,
+          REWARDS
+        ));
+        StakingPool::<Test>::insert(CurrencyId::Tdfy, context.tdfy_amount + REWARDS);
+        AccountStakes::<Test>::mutate(BOB_ACCOUNT_ID, |stakes| {
+          stakes.as_mut()[0].principal = context.tdfy_amount + REWARDS
+        });
+        let bob_stake_id = TidefiStaking::account_stakes(BOB_ACCOUNT_ID)
+          .first()
+          .unwrap()
+          .unique_id;
+
+        set_current_block(context.duration + 1);
+        assert_ok!(TidefiStaking::unstake(
+          Origin::signed(BOB_ACCOUNT_ID),
+          bob_stake_id,
+          false
+        ));
+
+        assert_eq!(
+          Adapter::balance(CurrencyId::Tdfy, &BOB_ACCOUNT_ID),
+          context.tdfy_amount
+        );
+        assert_eq!(
+          Adapter::balance(CurrencyId::Tdfy, &CHARLIE_ACCOUNT_ID),
+          CHARLIE_INITIAL_ONE_THOUSAND_TDFYS + REWARDS
+        );
+        assert_eq!(TidefiStaking::stake_reward_recipient(bob_stake_id), None);
+      });
+    }
+  }
+
+  mod fails_when {
+    use super::*;
+
+    #[test]
+    fn not_signed() {
+      new_test_ext().execute_with(|| {
+        let context =
+          Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+        assert_noop!(
+          TidefiStaking::stake_for(
+            Origin::none(),
+            BOB_ACCOUNT_ID,
+            CurrencyId::Tdfy,
+            context.tdfy_amount,
+            context.duration,
+            None
+          ),
+          BadOrigin
+        );
+      });
+    }
+
+    #[test]
+    fn funder_has_insufficient_balance() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default().mint_tdfy(BOB_ACCOUNT_ID, BOB_INITIAL_ONE_THOUSAND_TDFYS);
+        assert_ok!(TidefiStaking::approve_stake_funder(
+          Origin::signed(BOB_ACCOUNT_ID),
+          ALICE_ACCOUNT_ID,
+          true
+        ));
+
+        assert_noop!(
+          TidefiStaking::stake_for(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            BOB_ACCOUNT_ID,
+            CurrencyId::Tdfy,
+            context.tdfy_amount,
+            context.duration,
+            None
+          ),
+          Error::<Test>::InsufficientBalance
+        );
+      });
+    }
+
+    #[test]
+    fn funder_is_not_approved() {
+      new_test_ext().execute_with(|| {
+        let context =
+          Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+        assert_noop!(
+          TidefiStaking::stake_for(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            BOB_ACCOUNT_ID,
+            CurrencyId::Tdfy,
+            context.tdfy_amount,
+            context.duration,
+            None
+          ),
+          Error::<Test>::StakeFunderNotApproved
+        );
+      });
+    }
+
+    #[test]
+    fn funder_approval_is_revoked() {
+      new_test_ext().execute_with(|| {
+        let context =
+          Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+        assert_ok!(TidefiStaking::approve_stake_funder(
+          Origin::signed(BOB_ACCOUNT_ID),
+          ALICE_ACCOUNT_ID,
+          true
+        ));
+        assert_ok!(TidefiStaking::approve_stake_funder(
+          Origin::signed(BOB_ACCOUNT_ID),
+          ALICE_ACCOUNT_ID,
+          false
+        ));
+        assert_eq!(
+          TidefiStaking::approved_stake_funder(BOB_ACCOUNT_ID, ALICE_ACCOUNT_ID),
+          None
+        );
+
+        assert_noop!(
+          TidefiStaking::stake_for(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            BOB_ACCOUNT_ID,
+            CurrencyId::Tdfy,
+            context.tdfy_amount,
+            context.duration,
+            None
+          ),
+          Error::<Test>::StakeFunderNotApproved
+        );
+      });
+    }
+
+    #[test]
+    fn funder_unstakes() {
+      new_test_ext().execute_with(|| {
+        let context =
+          Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+        assert_ok!(TidefiStaking::approve_stake_funder(
+          Origin::signed(BOB_ACCOUNT_ID),
+          ALICE_ACCOUNT_ID,
+          true
+        ));
+        assert_ok!(TidefiStaking::stake_for(
+          Origin::signed(ALICE_ACCOUNT_ID),
+          BOB_ACCOUNT_ID,
+          CurrencyId::Tdfy,
+          context.tdfy_amount,
+          context.duration,
+          None
+        ));
+        let bob_stake_id = TidefiStaking::account_stakes(BOB_ACCOUNT_ID)
+          .first()
+          .unwrap()
+          .unique_id;
+
+        assert_noop!(
+          TidefiStaking::unstake(Origin::signed(ALICE_ACCOUNT_ID), bob_stake_id, true),
+          Error::<Test>::InvalidStakeId
+        );
+      });
+    }
+  }
+}
+
 mod unstake {
   use super::*;
 
@@ -553,6 +846,41 @@ mod unstake {
           );
         });
       }
+
+      #[test]
+      fn for_wrapped_asset_with_fee_in_tdfy() {
+        new_test_ext().execute_with(|| {
+          let context = Context::default()
+            .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+            .mint_test_token(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TEST_TOKENS)
+            .stake_test_tokens();
+
+          let staker_test_token_balance_before =
+            Adapter::balance(TEST_TOKEN_CURRENCY_ID, &context.staker);
+          let staker_tdfy_balance_before = Adapter::balance(CurrencyId::Tdfy, &context.staker);
+
+          assert_ok!(TidefiStaking::unstake_with_tdfy_fee(
+            Origin::signed(context.staker),
+            context.stake_id,
+            true
+          ));
+
+          let unstaking_fee_in_tdfy =
+            TidefiStaking::unstake_fee() * context.test_token_amount * TEST_TOKEN_TDFY_RATE;
+          assert_eq!(
+            TidefiStaking::queued_unstake_fee(context.stake_id),
+            Some((CurrencyId::Tdfy, unstaking_fee_in_tdfy))
+          );
+          assert_eq!(
+            staker_tdfy_balance_before - unstaking_fee_in_tdfy,
+            Adapter::balance(CurrencyId::Tdfy, &context.staker)
+          );
+          assert_eq!(
+            staker_test_token_balance_before,
+            Adapter::balance(TEST_TOKEN_CURRENCY_ID, &context.staker)
+          );
+        });
+      }
     }
   }
 
@@ -630,6 +958,26 @@ mod unstake {
       });
     }
 
+    #[test]
+    fn unstake_is_already_queued() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+          .stake_tdfy();
+
+        assert_ok!(TidefiStaking::unstake(
+          Origin::signed(context.staker),
+          context.stake_id,
+          true
+        ));
+
+        assert_noop!(
+          TidefiStaking::unstake(Origin::signed(context.staker), context.stake_id, true),
+          Error::<Test>::UnstakeAlreadyQueued
+        );
+      });
+    }
+
     #[test]
     fn unstake_queue_exceeds_its_cap() {
       new_test_ext().execute_with(|| {
@@ -647,64 +995,698 @@ mod unstake {
   }
 }
 
-#[test]
-pub fn should_stake_and_unstake() {
-  new_test_ext().execute_with(|| {
-    Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
-
-    assert_ok!(TidefiStaking::stake(
-      Origin::signed(ALICE_ACCOUNT_ID),
-      CurrencyId::Tdfy,
-      ALICE_STAKE_ONE_TDFY,
-      FIFTEEN_DAYS
-    ));
+mod cancel_unstake {
+  use super::*;
 
-    assert_eq!(
-      Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
-      ALICE_INITIAL_ONE_THOUSAND_TDFYS - ALICE_STAKE_ONE_TDFY
-    );
+  mod succeeds {
+    use super::*;
 
-    let stake_id = TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
-      .first()
-      .unwrap()
-      .unique_id;
+    #[test]
+    fn for_native_asset() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+          .stake_tdfy();
 
-    // make sure the staking pool has been updated
-    assert_eq!(
-      TidefiStaking::staking_pool(CurrencyId::Tdfy),
-      Some(ALICE_STAKE_ONE_TDFY)
-    );
+        let staker_balance_before = Adapter::balance(CurrencyId::Tdfy, &context.staker);
 
-    // make sure the staking has been recorded in the storage
-    assert!(TidefiStaking::account_stakes(ALICE_ACCOUNT_ID).len() == 1);
-    assert!(
-      TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
-        .first()
-        .unwrap()
-        .initial_balance
-        == ALICE_STAKE_ONE_TDFY
-    );
+        assert_ok!(TidefiStaking::unstake(
+          Origin::signed(context.staker),
+          context.stake_id,
+          true
+        ));
+        assert_eq!(TidefiStaking::unstake_queue().len(), 1);
 
-    set_current_block(FIFTEEN_DAYS + 1);
+        assert_ok!(TidefiStaking::cancel_unstake(
+          Origin::signed(context.staker),
+          context.stake_id
+        ));
 
-    assert_eq!(Security::current_block_number(), FIFTEEN_DAYS + 1);
-    assert_ok!(TidefiStaking::unstake(
-      Origin::signed(ALICE_ACCOUNT_ID),
-      stake_id,
-      false
-    ));
-    assert!(TidefiStaking::account_stakes(ALICE_ACCOUNT_ID).len() == 0);
-    // balance is returned
-    assert_eq!(
-      Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
-      ALICE_INITIAL_ONE_THOUSAND_TDFYS
-    );
-  });
-}
+        assert!(TidefiStaking::unstake_queue().is_empty());
+        assert_eq!(TidefiStaking::queued_unstake_fee(context.stake_id), None);
+        assert_eq!(
+          AccountStakes::<Test>::get(context.staker)
+            .first()
+            .unwrap()
+            .status,
+          StakeStatus::Staked
+        );
 
-#[test]
-pub fn should_stake_and_unstake_queue() {
-  new_test_ext().execute_with(|| {
+        // half of the fee is refunded
+        let unstaking_fee = TidefiStaking::unstake_fee() * context.tdfy_amount;
+        let refunded_fee = CancelUnstakeFeeRefund::get() * unstaking_fee;
+        assert_eq!(
+          staker_balance_before - unstaking_fee + refunded_fee,
+          Adapter::balance(CurrencyId::Tdfy, &context.staker)
+        );
+      });
+    }
+
+    #[test]
+    fn and_can_be_queued_again() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+          .stake_tdfy();
+
+        assert_ok!(TidefiStaking::unstake(
+          Origin::signed(context.staker),
+          context.stake_id,
+          true
+        ));
+        assert_ok!(TidefiStaking::cancel_unstake(
+          Origin::signed(context.staker),
+          context.stake_id
+        ));
+        assert_ok!(TidefiStaking::unstake(
+          Origin::signed(context.staker),
+          context.stake_id,
+          true
+        ));
+
+        assert_eq!(
+          TidefiStaking::get_unstake_queue_position(context.stake_id),
+          Some((0, BLOCKS_FORCE_UNLOCK))
+        );
+      });
+    }
+  }
+
+  mod fails_when {
+    use super::*;
+
+    #[test]
+    fn not_signed() {
+      new_test_ext().execute_with(|| {
+        Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+          .stake_tdfy();
+
+        assert_noop!(
+          TidefiStaking::cancel_unstake(Origin::none(), Hash::zero()),
+          BadOrigin
+        );
+      });
+    }
+
+    #[test]
+    fn stake_id_is_invalid() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+          .stake_tdfy();
+
+        assert_noop!(
+          TidefiStaking::cancel_unstake(Origin::signed(context.staker), Hash::zero()),
+          Error::<Test>::InvalidStakeId
+        );
+      });
+    }
+
+    #[test]
+    fn unstake_is_not_queued() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+          .stake_tdfy();
+
+        assert_noop!(
+          TidefiStaking::cancel_unstake(Origin::signed(context.staker), context.stake_id),
+          Error::<Test>::UnstakeNotQueued
+        );
+      });
+    }
+  }
+}
+
+mod set_receipt_currency {
+  use super::*;
+
+  mod succeeds {
+    use super::*;
+
+    #[test]
+    fn when_enabled_and_disabled() {
+      new_test_ext().execute_with(|| {
+        assert_ok!(TidefiStaking::set_receipt_currency(
+          Origin::root(),
+          CurrencyId::Tdfy,
+          Some(RECEIPT_CURRENCY_ID)
+        ));
+        assert_eq!(
+          TidefiStaking::receipt_currency(CurrencyId::Tdfy),
+          Some(RECEIPT_CURRENCY_ID)
+        );
+
+        assert_ok!(TidefiStaking::set_receipt_currency(
+          Origin::root(),
+          CurrencyId::Tdfy,
+          None
+        ));
+        assert_eq!(TidefiStaking::receipt_currency(CurrencyId::Tdfy), None);
+      });
+    }
+  }
+
+  mod fails_when {
+    use super::*;
+
+    #[test]
+    fn not_root() {
+      new_test_ext().execute_with(|| {
+        assert_noop!(
+          TidefiStaking::set_receipt_currency(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            CurrencyId::Tdfy,
+            Some(RECEIPT_CURRENCY_ID)
+          ),
+          BadOrigin
+        );
+      });
+    }
+
+    #[test]
+    fn receipt_currency_is_invalid() {
+      new_test_ext().execute_with(|| {
+        for receipt_currency_id in [
+          CurrencyId::Tdfy,
+          TEST_TOKEN_CURRENCY_ID,
+          // not registered
+          CurrencyId::Wrapped(99),
+        ] {
+          assert_noop!(
+            TidefiStaking::set_receipt_currency(
+              Origin::root(),
+              TEST_TOKEN_CURRENCY_ID,
+              Some(receipt_currency_id)
+            ),
+            Error::<Test>::InvalidReceiptCurrency
+          );
+        }
+      });
+    }
+
+    #[test]
+    fn receipt_currency_has_issuance() {
+      new_test_ext().execute_with(|| {
+        Context::mint_asset_for_accounts(vec![BOB_ACCOUNT_ID], RECEIPT_CURRENCY_ID, ONE_TDFY);
+
+        assert_noop!(
+          TidefiStaking::set_receipt_currency(
+            Origin::root(),
+            CurrencyId::Tdfy,
+            Some(RECEIPT_CURRENCY_ID)
+          ),
+          Error::<Test>::InvalidReceiptCurrency
+        );
+      });
+    }
+
+    #[test]
+    fn receipt_currency_is_bridged() {
+      new_test_ext().execute_with(|| {
+        assert_ok!(AssetRegistry::set_chain_info(
+          Origin::signed(0),
+          RECEIPT_CURRENCY_ID,
+          Some(AssetChainInfo {
+            origin_chain: b"Ethereum".to_vec(),
+            contract_address: None,
+            minimum_deposit: 0,
+            minimum_withdrawal: 0,
+            withdrawal_fee: 0,
+            confirmations_required: 1,
+          })
+        ));
+
+        assert_noop!(
+          TidefiStaking::set_receipt_currency(
+            Origin::root(),
+            CurrencyId::Tdfy,
+            Some(RECEIPT_CURRENCY_ID)
+          ),
+          Error::<Test>::InvalidReceiptCurrency
+        );
+      });
+    }
+
+    #[test]
+    fn receipt_currency_is_already_used() {
+      new_test_ext().execute_with(|| {
+        assert_ok!(TidefiStaking::set_receipt_currency(
+          Origin::root(),
+          CurrencyId::Tdfy,
+          Some(RECEIPT_CURRENCY_ID)
+        ));
+
+        assert_noop!(
+          TidefiStaking::set_receipt_currency(
+            Origin::root(),
+            TEST_TOKEN_CURRENCY_ID,
+            Some(RECEIPT_CURRENCY_ID)
+          ),
+          Error::<Test>::InvalidReceiptCurrency
+        );
+      });
+    }
+  }
+}
+
+#[test]
+pub fn should_mint_and_burn_stake_receipts() {
+  new_test_ext().execute_with(|| {
+    let context = Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+    assert_ok!(TidefiStaking::set_receipt_currency(
+      Origin::root(),
+      CurrencyId::Tdfy,
+      Some(RECEIPT_CURRENCY_ID)
+    ));
+
+    let context = context.stake_tdfy();
+    let stake_id = TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+      .first()
+      .unwrap()
+      .unique_id;
+
+    assert_eq!(
+      Adapter::balance(RECEIPT_CURRENCY_ID, &ALICE_ACCOUNT_ID),
+      context.tdfy_amount
+    );
+    assert_eq!(
+      TidefiStaking::stake_receipt(stake_id),
+      Some((ALICE_ACCOUNT_ID, RECEIPT_CURRENCY_ID, context.tdfy_amount))
+    );
+
+    // the receipts are transferable
+    assert_ok!(Adapter::transfer(
+      RECEIPT_CURRENCY_ID,
+      &ALICE_ACCOUNT_ID,
+      &BOB_ACCOUNT_ID,
+      context.tdfy_amount,
+      false
+    ));
+
+    set_current_block(context.duration + 1);
+    assert_noop!(
+      TidefiStaking::unstake(Origin::signed(ALICE_ACCOUNT_ID), stake_id, false),
+      Error::<Test>::InsufficientReceiptBalance
+    );
+
+    // the receipt is tied to the stake, its holder can't take the stake over
+    assert_noop!(
+      TidefiStaking::unstake(Origin::signed(BOB_ACCOUNT_ID), stake_id, false),
+      Error::<Test>::InvalidStakeId
+    );
+
+    // the stake owner redeems the receipts
+    assert_ok!(Adapter::transfer(
+      RECEIPT_CURRENCY_ID,
+      &BOB_ACCOUNT_ID,
+      &ALICE_ACCOUNT_ID,
+      context.tdfy_amount,
+      false
+    ));
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      stake_id,
+      false
+    ));
+
+    assert_eq!(Adapter::balance(RECEIPT_CURRENCY_ID, &ALICE_ACCOUNT_ID), 0);
+    assert_eq!(Adapter::total_issuance(RECEIPT_CURRENCY_ID), 0);
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+      ALICE_INITIAL_ONE_THOUSAND_TDFYS
+    );
+    assert!(TidefiStaking::account_stakes(ALICE_ACCOUNT_ID).is_empty());
+    assert_eq!(TidefiStaking::stake_receipt(stake_id), None);
+  });
+}
+
+#[test]
+pub fn should_burn_stake_receipts_when_unstake_is_queued() {
+  new_test_ext().execute_with(|| {
+    let context = Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+    assert_ok!(TidefiStaking::set_receipt_currency(
+      Origin::root(),
+      CurrencyId::Tdfy,
+      Some(RECEIPT_CURRENCY_ID)
+    ));
+
+    let context = context.stake_tdfy();
+    let stake_id = TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+      .first()
+      .unwrap()
+      .unique_id;
+
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      stake_id,
+      true
+    ));
+    assert_eq!(Adapter::balance(RECEIPT_CURRENCY_ID, &ALICE_ACCOUNT_ID), 0);
+    assert_eq!(Adapter::total_issuance(RECEIPT_CURRENCY_ID), 0);
+
+    // the receipts are minted back when cancelled
+    assert_ok!(TidefiStaking::cancel_unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      stake_id
+    ));
+    assert_eq!(
+      Adapter::balance(RECEIPT_CURRENCY_ID, &ALICE_ACCOUNT_ID),
+      context.tdfy_amount
+    );
+    assert_eq!(
+      TidefiStaking::stake_receipt(stake_id),
+      Some((ALICE_ACCOUNT_ID, RECEIPT_CURRENCY_ID, context.tdfy_amount))
+    );
+
+    // and burned again when the unstake is queued again
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      stake_id,
+      true
+    ));
+    assert_eq!(Adapter::balance(RECEIPT_CURRENCY_ID, &ALICE_ACCOUNT_ID), 0);
+    assert_eq!(Adapter::total_issuance(RECEIPT_CURRENCY_ID), 0);
+
+    set_current_block(BLOCKS_FORCE_UNLOCK + 1);
+    run_on_idle_hook(1, 1_000 * ONE_TDFY);
+    assert!(TidefiStaking::account_stakes(ALICE_ACCOUNT_ID).is_empty());
+    assert_eq!(TidefiStaking::stake_receipt(stake_id), None);
+  });
+}
+
+#[test]
+pub fn should_send_unstake_fee_to_destination() {
+  new_test_ext().execute_with(|| {
+    let fees_account_id = UnstakeFeeDestination::get().unwrap();
+    let context = Context::default()
+      .mint_tdfy(fees_account_id, ONE_TDFY)
+      .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+      .stake_tdfy();
+
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(context.staker),
+      context.stake_id,
+      true
+    ));
+
+    // the fee is kept in the staking account while the unstake is queued
+    let unstaking_fee = TidefiStaking::unstake_fee() * context.tdfy_amount;
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &context.staking_pallet_account),
+      context.tdfy_amount + unstaking_fee
+    );
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &fees_account_id),
+      ONE_TDFY
+    );
+
+    set_current_block(BLOCKS_FORCE_UNLOCK + 1);
+    run_on_idle_hook(1, 1_000 * ONE_TDFY);
+
+    assert!(TidefiStaking::unstake_queue().is_empty());
+    assert_eq!(TidefiStaking::queued_unstake_fee(context.stake_id), None);
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &fees_account_id),
+      ONE_TDFY + unstaking_fee
+    );
+  });
+}
+
+#[test]
+pub fn should_send_unrefunded_unstake_fee_to_destination_on_cancel() {
+  new_test_ext().execute_with(|| {
+    let fees_account_id = UnstakeFeeDestination::get().unwrap();
+    let context = Context::default()
+      .mint_tdfy(fees_account_id, ONE_TDFY)
+      .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+      .stake_tdfy();
+
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(context.staker),
+      context.stake_id,
+      true
+    ));
+    assert_ok!(TidefiStaking::cancel_unstake(
+      Origin::signed(context.staker),
+      context.stake_id
+    ));
+
+    let unstaking_fee = TidefiStaking::unstake_fee() * context.tdfy_amount;
+    let refunded_fee = CancelUnstakeFeeRefund::get() * unstaking_fee;
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &fees_account_id),
+      ONE_TDFY + unstaking_fee - refunded_fee
+    );
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &context.staking_pallet_account),
+      context.tdfy_amount
+    );
+  });
+}
+
+#[test]
+pub fn should_return_unstake_queue_position() {
+  new_test_ext().execute_with(|| {
+    Context::default()
+      .mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS)
+      .mint_tdfy(BOB_ACCOUNT_ID, BOB_INITIAL_ONE_THOUSAND_TDFYS);
+
+    assert_ok!(TidefiStaking::stake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      CurrencyId::Tdfy,
+      ALICE_STAKE_ONE_TDFY,
+      FIFTEEN_DAYS
+    ));
+    assert_ok!(TidefiStaking::stake(
+      Origin::signed(BOB_ACCOUNT_ID),
+      CurrencyId::Tdfy,
+      BOB_STAKE_QUARTER_TDFY,
+      FIFTEEN_DAYS
+    ));
+
+    let alice_stake_id = TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+      .first()
+      .unwrap()
+      .unique_id;
+    let bob_stake_id = TidefiStaking::account_stakes(BOB_ACCOUNT_ID)
+      .first()
+      .unwrap()
+      .unique_id;
+
+    assert_eq!(
+      TidefiStaking::get_unstake_queue_position(alice_stake_id),
+      None
+    );
+
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      alice_stake_id,
+      true
+    ));
+    set_current_block(10);
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(BOB_ACCOUNT_ID),
+      bob_stake_id,
+      true
+    ));
+
+    assert_eq!(
+      TidefiStaking::get_unstake_queue_position(alice_stake_id),
+      Some((0, BLOCKS_FORCE_UNLOCK))
+    );
+    assert_eq!(
+      TidefiStaking::get_unstake_queue_position(bob_stake_id),
+      Some((1, 10 + BLOCKS_FORCE_UNLOCK))
+    );
+
+    assert_ok!(TidefiStaking::cancel_unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      alice_stake_id
+    ));
+
+    assert_eq!(
+      TidefiStaking::get_unstake_queue_position(alice_stake_id),
+      None
+    );
+    assert_eq!(
+      TidefiStaking::get_unstake_queue_position(bob_stake_id),
+      Some((0, 10 + BLOCKS_FORCE_UNLOCK))
+    );
+  });
+}
+
+#[test]
+pub fn should_estimate_stake_rewards() {
+  new_test_ext().execute_with(|| {
+    const ALICE_STAKE_ONE_HUNDRED_TDFYS: Balance = 100 * ONE_TDFY;
+    const SESSION_TRADE_VALUE_ONE_HUNDRED_TDFYS: Balance = 100 * ONE_TDFY;
+    // 15 days with 50 blocks per session
+    const FIFTEEN_DAYS_SESSIONS: Balance = 4_320;
+
+    Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+    set_current_block(1);
+
+    assert_ok!(TidefiStaking::stake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      CurrencyId::Tdfy,
+      ALICE_STAKE_ONE_HUNDRED_TDFYS,
+      FIFTEEN_DAYS
+    ));
+    let alice_stake_id = TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+      .first()
+      .unwrap()
+      .unique_id;
+
+    // no fees collected yet
+    assert_eq!(
+      TidefiStaking::estimate_stake_rewards(CurrencyId::Tdfy, ONE_TDFY, FIFTEEN_DAYS),
+      Ok(0)
+    );
+
+    // 100 for TDFY in fees for sessions 1 and 2
+    // 15 days should get 2%, so 2 tides per session
+    assert_ok!(TidefiStaking::on_session_end(
+      1,
+      vec![(CurrencyId::Tdfy, SESSION_TRADE_VALUE_ONE_HUNDRED_TDFYS)]
+    ));
+    assert_ok!(TidefiStaking::on_session_end(
+      2,
+      vec![(CurrencyId::Tdfy, SESSION_TRADE_VALUE_ONE_HUNDRED_TDFYS)]
+    ));
+    // the fees pallet transferred the stakers rewards
+    assert_ok!(Adapter::mint_into(
+      CurrencyId::Tdfy,
+      &Context::default().staking_pallet_account,
+      4 * ONE_TDFY
+    ));
+    run_on_idle_hook(1, 1_000 * ONE_TDFY);
+
+    // a new stake of 100 TDFY would own half of the pool
+    assert_eq!(
+      TidefiStaking::estimate_stake_rewards(
+        CurrencyId::Tdfy,
+        ALICE_STAKE_ONE_HUNDRED_TDFYS,
+        FIFTEEN_DAYS
+      ),
+      Ok(FIFTEEN_DAYS_SESSIONS * ONE_TDFY)
+    );
+
+    // 4 tides already compounded and 2 tides for each remaining session
+    assert_eq!(
+      TidefiStaking::estimate_account_stakes_rewards(&ALICE_ACCOUNT_ID),
+      vec![(
+        alice_stake_id,
+        BalanceInfo {
+          amount: 4 * ONE_TDFY + FIFTEEN_DAYS_SESSIONS * 2 * ONE_TDFY
+        }
+      )]
+    );
+
+    // half of the stake duration elapsed
+    set_current_block(1 + FIFTEEN_DAYS / 2);
+    assert_eq!(
+      TidefiStaking::estimate_account_stakes_rewards(&ALICE_ACCOUNT_ID),
+      vec![(
+        alice_stake_id,
+        BalanceInfo {
+          amount: 4 * ONE_TDFY + FIFTEEN_DAYS_SESSIONS / 2 * 2 * ONE_TDFY
+        }
+      )]
+    );
+
+    // the stakers only receive 1% of the fees, 1 tide for each remaining session
+    StakersFeeShare::set(Percent::from_percent(1));
+    assert_eq!(
+      TidefiStaking::estimate_account_stakes_rewards(&ALICE_ACCOUNT_ID),
+      vec![(
+        alice_stake_id,
+        BalanceInfo {
+          amount: 4 * ONE_TDFY + FIFTEEN_DAYS_SESSIONS / 2 * ONE_TDFY
+        }
+      )]
+    );
+    StakersFeeShare::set(Percent::from_percent(100));
+
+    assert_eq!(
+      TidefiStaking::estimate_stake_rewards(CurrencyId::Tdfy, ONE_TDFY, FIFTEEN_DAYS + 1),
+      Err(Error::<Test>::InvalidDuration.into())
+    );
+
+    // the duration of the stake isn't rewarded anymore, only the compounded rewards are left
+    StakingPeriodRewards::<Test>::kill();
+    assert_eq!(
+      TidefiStaking::estimate_account_stakes_rewards(&ALICE_ACCOUNT_ID),
+      vec![(
+        alice_stake_id,
+        BalanceInfo {
+          amount: 4 * ONE_TDFY
+        }
+      )]
+    );
+  });
+}
+
+#[test]
+pub fn should_stake_and_unstake() {
+  new_test_ext().execute_with(|| {
+    Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+    assert_ok!(TidefiStaking::stake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      CurrencyId::Tdfy,
+      ALICE_STAKE_ONE_TDFY,
+      FIFTEEN_DAYS
+    ));
+
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+      ALICE_INITIAL_ONE_THOUSAND_TDFYS - ALICE_STAKE_ONE_TDFY
+    );
+
+    let stake_id = TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+      .first()
+      .unwrap()
+      .unique_id;
+
+    // make sure the staking pool has been updated
+    assert_eq!(
+      TidefiStaking::staking_pool(CurrencyId::Tdfy),
+      Some(ALICE_STAKE_ONE_TDFY)
+    );
+
+    // make sure the staking has been recorded in the storage
+    assert!(TidefiStaking::account_stakes(ALICE_ACCOUNT_ID).len() == 1);
+    assert!(
+      TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+        .first()
+        .unwrap()
+        .initial_balance
+        == ALICE_STAKE_ONE_TDFY
+    );
+
+    set_current_block(FIFTEEN_DAYS + 1);
+
+    assert_eq!(Security::current_block_number(), FIFTEEN_DAYS + 1);
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      stake_id,
+      false
+    ));
+    assert!(TidefiStaking::account_stakes(ALICE_ACCOUNT_ID).len() == 0);
+    // balance is returned
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+      ALICE_INITIAL_ONE_THOUSAND_TDFYS
+    );
+  });
+}
+
+#[test]
+pub fn should_stake_and_unstake_queue() {
+  new_test_ext().execute_with(|| {
     Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
 
     assert_ok!(TidefiStaking::stake(
@@ -911,6 +1893,95 @@ pub fn should_stake_multiple_and_unstake_queue() {
   });
 }
 
+#[test]
+pub fn should_not_compound_more_rewards_than_transferred() {
+  new_test_ext().execute_with(|| {
+    const ALICE_STAKE_ONE_HUNDRED_TDFYS: Balance = 100 * ONE_TDFY;
+    const SESSION_TRADE_VALUE_ONE_HUNDRED_TDFYS: Balance = 100 * ONE_TDFY;
+    let context = Context::default().mint_tdfy(ALICE_ACCOUNT_ID, ALICE_INITIAL_ONE_THOUSAND_TDFYS);
+
+    set_current_block(1);
+    assert_ok!(TidefiStaking::stake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      CurrencyId::Tdfy,
+      ALICE_STAKE_ONE_HUNDRED_TDFYS,
+      FIFTEEN_DAYS
+    ));
+    assert_eq!(
+      TidefiStaking::staking_liabilities(CurrencyId::Tdfy),
+      ALICE_STAKE_ONE_HUNDRED_TDFYS
+    );
+
+    // no rewards transferred by the fees pallet
+    assert_ok!(TidefiStaking::on_session_end(
+      1,
+      vec![(CurrencyId::Tdfy, SESSION_TRADE_VALUE_ONE_HUNDRED_TDFYS)]
+    ));
+    run_on_idle_hook(1, 1_000 * ONE_TDFY);
+
+    assert_eq!(
+      TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+        .first()
+        .unwrap()
+        .principal,
+      ALICE_STAKE_ONE_HUNDRED_TDFYS
+    );
+    assert_ok!(TidefiStaking::check_solvency());
+
+    // only 1 TDFY transferred, while 2 TDFY are expected
+    assert_ok!(Adapter::mint_into(
+      CurrencyId::Tdfy,
+      &context.staking_pallet_account,
+      ONE_TDFY
+    ));
+    assert_eq!(
+      TidefiStaking::unallocated_rewards(CurrencyId::Tdfy),
+      ONE_TDFY
+    );
+
+    assert_ok!(TidefiStaking::on_session_end(
+      2,
+      vec![(CurrencyId::Tdfy, SESSION_TRADE_VALUE_ONE_HUNDRED_TDFYS)]
+    ));
+    run_on_idle_hook(1, 1_000 * ONE_TDFY);
+
+    let alice_stake = TidefiStaking::account_stakes(ALICE_ACCOUNT_ID)
+      .first()
+      .unwrap()
+      .clone();
+    assert_eq!(
+      alice_stake.principal,
+      ALICE_STAKE_ONE_HUNDRED_TDFYS + ONE_TDFY
+    );
+    assert_eq!(
+      TidefiStaking::staking_liabilities(CurrencyId::Tdfy),
+      ALICE_STAKE_ONE_HUNDRED_TDFYS + ONE_TDFY
+    );
+    assert_eq!(TidefiStaking::unallocated_rewards(CurrencyId::Tdfy), 0);
+    assert_ok!(TidefiStaking::check_solvency());
+    // the compounded rewards are not part of the pool
+    assert_eq!(
+      TidefiStaking::staking_pool(CurrencyId::Tdfy),
+      Some(ALICE_STAKE_ONE_HUNDRED_TDFYS)
+    );
+
+    // the staking account is able to pay back the principal
+    set_current_block(1 + FIFTEEN_DAYS);
+    assert_ok!(TidefiStaking::unstake(
+      Origin::signed(ALICE_ACCOUNT_ID),
+      alice_stake.unique_id,
+      false
+    ));
+    assert_eq!(
+      Adapter::balance(CurrencyId::Tdfy, &ALICE_ACCOUNT_ID),
+      ALICE_INITIAL_ONE_THOUSAND_TDFYS + ONE_TDFY
+    );
+    assert_eq!(TidefiStaking::staking_liabilities(CurrencyId::Tdfy), 0);
+    assert_eq!(TidefiStaking::staking_pool(CurrencyId::Tdfy), Some(0));
+    assert_ok!(TidefiStaking::check_solvency());
+  });
+}
+
 #[test]
 pub fn should_calculate_rewards() {
   new_test_ext().execute_with(|| {
@@ -926,6 +1997,14 @@ pub fn should_calculate_rewards() {
 
     set_current_block(1);
 
+    // the fees pallet transferred the stakers rewards
+    let staking_pallet_account = Context::default().staking_pallet_account;
+    assert_ok!(Adapter::mint_into(
+      CurrencyId::Tdfy,
+      &staking_pallet_account,
+      10 * ONE_TDFY
+    ));
+
     assert_ok!(TidefiStaking::stake(
       Origin::signed(ALICE_ACCOUNT_ID),
       CurrencyId::Tdfy,
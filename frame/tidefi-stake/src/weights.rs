@@ -24,7 +24,12 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for `pallet_tidefi`.
 pub trait WeightInfo {
    fn stake() -> Weight;
+   fn stake_for() -> Weight;
+   fn approve_stake_funder() -> Weight;
    fn unstake() -> Weight;
+   fn unstake_with_tdfy_fee() -> Weight;
+   fn cancel_unstake() -> Weight;
+   fn set_receipt_currency() -> Weight;
 }
 
 /// Weights for `pallet_tidefi` using the Substrate node and recommended hardware.
@@ -36,9 +41,33 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
       .saturating_add(T::DbWeight::get().reads(6_u64))
       .saturating_add(T::DbWeight::get().writes(5_u64))
    }
+   fn stake_for() -> Weight {
+      15_300_000_u64
+      .saturating_add(T::DbWeight::get().reads(7_u64))
+      .saturating_add(T::DbWeight::get().writes(6_u64))
+   }
+   fn approve_stake_funder() -> Weight {
+      8_200_000_u64
+      .saturating_add(T::DbWeight::get().writes(1_u64))
+   }
    fn unstake() -> Weight {
       18_100_000_u64
-      .saturating_add(T::DbWeight::get().reads(6_u64))
+      .saturating_add(T::DbWeight::get().reads(8_u64))
+      .saturating_add(T::DbWeight::get().writes(7_u64))
+   }
+   fn unstake_with_tdfy_fee() -> Weight {
+      19_400_000_u64
+      .saturating_add(T::DbWeight::get().reads(9_u64))
+      .saturating_add(T::DbWeight::get().writes(7_u64))
+   }
+   fn cancel_unstake() -> Weight {
+      16_400_000_u64
+      .saturating_add(T::DbWeight::get().reads(5_u64))
       .saturating_add(T::DbWeight::get().writes(5_u64))
    }
+   fn set_receipt_currency() -> Weight {
+      12_600_000_u64
+      .saturating_add(T::DbWeight::get().reads(5_u64))
+      .saturating_add(T::DbWeight::get().writes(3_u64))
+   }
 }
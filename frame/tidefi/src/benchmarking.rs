@@ -21,13 +21,13 @@ use frame_support::{
   traits::{fungibles::Mutate, Hooks},
 };
 use frame_system::RawOrigin;
-use tidefi_primitives::{pallet::SunriseExt, CurrencyId, SwapType};
+use tidefi_primitives::{pallet::SunriseExt, ActiveEraInfo, CurrencyId, SwapType};
 const INITIAL_AMOUNT: u128 = 500_000_000_000_000;
 const IA_MULTIPLIER: u32 = 2;
 const TEST_TOKEN: u32 = 2;
 
 benchmarks! {
-   where_clause { where T: pallet_fees::Config + pallet_sunrise::Config + pallet_security::Config }
+   where_clause { where T: pallet_fees::Config + pallet_sunrise::Config + pallet_security::Config + pallet_tidefi_stake::Config }
 
    withdrawal {
       let caller: T::AccountId = whitelisted_caller();
@@ -61,6 +61,27 @@ benchmarks! {
       pallet_security::CurrentBlockCount::<T>::put(frame_system::Pallet::<T>::block_number());
 
    }: _(RawOrigin::Signed(caller), 1)
+   claim_all_sunrise_rewards {
+      let e in 1 .. MAX_SUNRISE_CLAIM_ERAS;
+      let caller: T::AccountId = whitelisted_caller();
+      pallet_fees::ActiveEra::<T>::put(ActiveEraInfo::<T::BlockNumber> {
+        index: e + 2,
+        start_block: Some(T::BlockNumber::from(1_u32)),
+        start_session_index: None,
+        last_session_block: None,
+        start: None,
+      });
+
+      <T as Config>::CurrencyTidefi::mint_into(CurrencyId::Tdfy, &<T as Config>::Sunrise::account_id(), 1_000_000_000_000_000_000).unwrap();
+      for era_index in 1..=e {
+        <pallet_sunrise::Rewards<T>>::insert(&caller, era_index, 1_000_000_000_000);
+        <pallet_sunrise::RewardsByEra<T>>::insert(era_index, &caller, ());
+      }
+      // stake the claimed rewards, the worst case
+      let stake_duration = pallet_tidefi_stake::StakingPeriodRewards::<T>::get()
+        .first()
+        .map(|(duration, _)| *duration);
+   }: _(RawOrigin::Signed(caller), stake_duration)
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
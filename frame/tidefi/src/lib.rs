@@ -35,6 +35,7 @@ pub use pallet::*;
 pub mod pallet {
   use super::*;
   use frame_support::{
+    dispatch::PostDispatchInfo,
     inherent::Vec,
     pallet_prelude::*,
     traits::tokens::{
@@ -43,6 +44,10 @@ pub mod pallet {
     },
   };
   use frame_system::pallet_prelude::*;
+  use pallet_fees::SwapFeesExt;
+  use pallet_security::{RateLimitsExt, Subsystem, SubsystemsStatusExt};
+  use pallet_sunrise::SunriseRewardsClaim;
+  use pallet_tidefi_stake::StakeFunds;
   use sp_io::hashing::blake2_256;
   use sp_runtime::{traits::Saturating, Permill};
   use tidefi_primitives::{
@@ -50,6 +55,9 @@ pub mod pallet {
     Balance, CurrencyId, EraIndex, Hash, SwapType,
   };
 
+  /// Maximum number of eras claimed at once by `claim_all_sunrise_rewards`.
+  pub const MAX_SUNRISE_CLAIM_ERAS: u32 = 100;
+
   /// Tidefi configuration
   #[pallet::config]
   pub trait Config: frame_system::Config {
@@ -69,13 +77,19 @@ pub mod pallet {
     type AssetRegistry: AssetRegistryExt;
 
     /// Fees traits
-    type Fees: FeesExt<Self::AccountId, Self::BlockNumber>;
+    type Fees: FeesExt<Self::AccountId, Self::BlockNumber> + SwapFeesExt<Self::AccountId>;
 
     /// Tidefi sunrise traits
-    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>;
+    type Sunrise: SunriseExt<Self::AccountId, Self::BlockNumber>
+      + SunriseRewardsClaim<Self::AccountId>;
+
+    /// Tidefi stake traits, used to stake the claimed sunrise rewards
+    type Staking: StakeFunds<Self::AccountId, Self::BlockNumber>;
 
     /// Security traits
-    type Security: SecurityExt<Self::AccountId, Self::BlockNumber>;
+    type Security: SecurityExt<Self::AccountId, Self::BlockNumber>
+      + SubsystemsStatusExt
+      + RateLimitsExt<Self::AccountId>;
 
     /// Tidechain currency wrapper
     type CurrencyTidefi: Inspect<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
@@ -154,6 +168,14 @@ pub mod pallet {
     EraNotReady,
     /// Invalid era
     InvalidEra,
+    /// Swaps are paused
+    SwapsPaused,
+    /// Withdrawals are paused
+    WithdrawalsPaused,
+    /// The currency is paused
+    CurrencyPaused,
+    /// The withdrawal exceeds the currency rate limits, try again later
+    WithdrawalRateLimitExceeded,
   }
 
   #[pallet::call]
@@ -177,11 +199,15 @@ pub mod pallet {
       // 1. Make sure the transaction is signed
       let account_id = ensure_signed(origin)?;
 
-      // 2. Make sure the currency is not disabled
+      // 2. Make sure the currency is not disabled or paused
       ensure!(
         T::AssetRegistry::is_currency_enabled(currency_id),
         Error::<T>::AssetDisabled
       );
+      ensure!(
+        T::Security::is_currency_running(currency_id),
+        Error::<T>::CurrencyPaused
+      );
 
       // 3. Transfer the request currency, only if the funds are available and the recipient can receive it.
       T::CurrencyTidefi::transfer(currency_id, &account_id, &destination_id, amount, false)?;
@@ -198,6 +224,8 @@ pub mod pallet {
 
     /// Submit asset withdrawal to the Quorum.
     ///
+    /// The withdrawal is rejected when it exceeds the currency rate limits.
+    ///
     /// - `currency_id`: The currency to withdraw.
     /// - `amount`: The amount to transfer
     /// - `external_address`: External address where to send funds.
@@ -215,14 +243,22 @@ pub mod pallet {
       // 1. Make sure the transaction is signed
       let account_id = ensure_signed(origin)?;
 
-      // 2. Make sure the quorum is enabled
+      // 2. Make sure the quorum is enabled and the withdrawals are not paused
       ensure!(T::Quorum::is_quorum_enabled(), Error::<T>::QuorumPaused);
+      ensure!(
+        T::Security::is_subsystem_running(Subsystem::Withdrawals),
+        Error::<T>::WithdrawalsPaused
+      );
 
-      // 3. Make sure the currency is not disabled
+      // 3. Make sure the currency is not disabled or paused
       ensure!(
         T::AssetRegistry::is_currency_enabled(currency_id),
         Error::<T>::AssetDisabled
       );
+      ensure!(
+        T::Security::is_currency_running(currency_id),
+        Error::<T>::CurrencyPaused
+      );
 
       // 4. Make sure the currency not a TDFY as it's not supported.
       ensure!(
@@ -233,6 +269,17 @@ pub mod pallet {
       // 5. Make sure the account have enough funds
       match T::CurrencyTidefi::can_withdraw(currency_id, &account_id, amount) {
         WithdrawConsequence::Success | WithdrawConsequence::ReducedToZero(_) => {
+          // Make sure the withdrawal is within the currency rate limits
+          ensure!(
+            T::Security::try_consume_rate_limit(
+              Subsystem::Withdrawals,
+              &account_id,
+              currency_id,
+              amount
+            ),
+            Error::<T>::WithdrawalRateLimitExceeded
+          );
+
           // Add withdrawal in queue
           T::Quorum::add_new_withdrawal_in_queue(
             account_id.clone(),
@@ -273,8 +320,13 @@ pub mod pallet {
     ///
     /// Emits `Swap` event when successful.
     ///
-    /// Weight: `O(1)`
-    #[pallet::weight(<T as pallet::Config>::WeightInfo::swap())]
+    /// The `request_id` of the event is predictable before the submission, with the
+    /// `tidefi_getRequestId` RPC for the signer and the extrinsic nonce.
+    ///
+    /// Weight: `O(V)` where `V` is the number of `VolumeTierEras` of the fees history read
+    /// to compute the volume discount of the account, once per era
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::swap()
+      .saturating_add(T::Fees::swap_fee_rate_weight()))]
     pub fn swap(
       origin: OriginFor<T>,
       currency_id_from: CurrencyId,
@@ -287,20 +339,32 @@ pub mod pallet {
       // 1. Make sure the transaction is signed
       let account_id = ensure_signed(origin)?;
 
-      // 2. Make sure the oracle is enabled
+      // 2. Make sure the oracle is enabled and the swaps are not paused
       ensure!(T::Oracle::is_oracle_enabled(), Error::<T>::OraclePaused);
+      ensure!(
+        T::Security::is_subsystem_running(Subsystem::Swaps),
+        Error::<T>::SwapsPaused
+      );
 
-      // 3. Make sure the `currency_id_from` is not disabled
+      // 3. Make sure the `currency_id_from` is not disabled or paused
       ensure!(
         T::AssetRegistry::is_currency_enabled(currency_id_from),
         Error::<T>::AssetDisabled
       );
+      ensure!(
+        T::Security::is_currency_running(currency_id_from),
+        Error::<T>::CurrencyPaused
+      );
 
-      // 4. Make sure the `currency_id_to` is not disabled
+      // 4. Make sure the `currency_id_to` is not disabled or paused
       ensure!(
         T::AssetRegistry::is_currency_enabled(currency_id_to),
         Error::<T>::AssetDisabled
       );
+      ensure!(
+        T::Security::is_currency_running(currency_id_to),
+        Error::<T>::CurrencyPaused
+      );
 
       // 5. Grab the extrinsic hash of the current extrinsic for better traceability
       let extrinsic_hash = blake2_256(&<frame_system::Pallet<T>>::extrinsic_data(
@@ -411,9 +475,11 @@ pub mod pallet {
         return Err(Error::<T>::InvalidEra.into());
       }
 
-      // Unable to claim previous era if the `T::Cooldown` cooldown isnt completed
+      // Unable to claim previous era if the `T::Cooldown` cooldown isnt completed, unless the
+      // rewards are released through a vesting schedule
       if era_index == current_era.index.saturating_sub(1)
         && starting_block.saturating_add(T::Sunrise::cooldown_blocks_count()) > current_block
+        && T::Sunrise::is_subject_to_cooldown(&account_id, era_index)
       {
         return Err(Error::<T>::EraNotReady.into());
       }
@@ -424,5 +490,106 @@ pub mod pallet {
       // Don't take tx fees on success
       Ok(Pays::No.into())
     }
+
+    /// Claim the sunrise rewards of all the claimable eras, up to `MAX_SUNRISE_CLAIM_ERAS` eras
+    /// at once.
+    ///
+    /// - `stake_duration`: Stake the claimed TDFY for this staking period, in blocks,
+    /// when provided. The staked rewards are not vested, so the previous era waits for the
+    /// `Cooldown`.
+    ///
+    /// Emits `SunriseClaimed` events, and a `Staked` event when the rewards are staked.
+    ///
+    /// Weight: `O(E + S)` where `E` is the number of eras claimed and `S` the number of eras
+    /// scanned without rewards
+    #[pallet::weight(<T as pallet::Config>::WeightInfo::claim_all_sunrise_rewards(MAX_SUNRISE_CLAIM_ERAS)
+      .saturating_add(T::DbWeight::get().reads(T::Sunrise::max_scanned_eras().into())))]
+    pub fn claim_all_sunrise_rewards(
+      origin: OriginFor<T>,
+      stake_duration: Option<T::BlockNumber>,
+    ) -> DispatchResultWithPostInfo {
+      // 1. Make sure the transaction is signed
+      let account_id = ensure_signed(origin)?;
+
+      // 2. Claim rewards of all the eras ready to be claimed, the staked rewards are not vested
+      let vest_rewards = stake_duration.is_none();
+      let (scanned_eras, claimed_eras, rewards) = T::Sunrise::try_claim_all_sunrise_rewards(
+        &account_id,
+        Self::last_claimable_era(&account_id, vest_rewards)?,
+        MAX_SUNRISE_CLAIM_ERAS,
+        vest_rewards,
+      )?;
+
+      // 3. Stake the claimed rewards
+      if let Some(duration) = stake_duration {
+        T::Staking::stake_funds(&account_id, CurrencyId::Tdfy, rewards, duration)?;
+      }
+
+      // Don't take tx fees on success, the eras scanned without rewards are read only
+      Ok(PostDispatchInfo {
+        actual_weight: Some(
+          <T as pallet::Config>::WeightInfo::claim_all_sunrise_rewards(claimed_eras)
+            .saturating_add(
+              T::DbWeight::get().reads(scanned_eras.saturating_sub(claimed_eras).into()),
+            ),
+        ),
+        pays_fee: Pays::No,
+      })
+    }
+  }
+
+  impl<T: Config> Pallet<T> {
+    // The last era with sunrise rewards of the account ready to be claimed, the previous era can
+    // only be claimed once the `Cooldown` is completed, unless its rewards are vested
+    pub(crate) fn last_claimable_era(
+      account_id: &T::AccountId,
+      vest_rewards: bool,
+    ) -> Result<EraIndex, DispatchError> {
+      let current_era = T::Fees::current_era().ok_or(Error::<T>::NoActiveEra)?;
+      let starting_block = current_era.start_block.ok_or(Error::<T>::NoActiveEra)?;
+      let current_block = T::Security::get_current_block_count();
+      let previous_era = current_era.index.saturating_sub(1);
+
+      let previous_eras = if starting_block.saturating_add(T::Sunrise::cooldown_blocks_count())
+        > current_block
+        && (!vest_rewards || T::Sunrise::is_subject_to_cooldown(account_id, previous_era))
+      {
+        2
+      } else {
+        1
+      };
+
+      current_era
+        .index
+        .checked_sub(previous_eras)
+        .ok_or_else(|| Error::<T>::EraNotReady.into())
+    }
+
+    /// Unclaimed sunrise rewards of the account, by era, with the block they can be claimed
+    /// from, `None` while the era is active
+    pub fn get_account_sunrise_rewards(
+      account_id: &T::AccountId,
+    ) -> Vec<(EraIndex, Balance, Option<T::BlockNumber>)> {
+      let current_era = T::Fees::current_era();
+      T::Sunrise::account_sunrise_rewards(account_id)
+        .into_iter()
+        .map(|(era_index, reward)| {
+          let claimable_at = current_era.as_ref().and_then(|current_era| {
+            let start_block = current_era.start_block?;
+            if era_index >= current_era.index {
+              None
+            } else if era_index == current_era.index.saturating_sub(1)
+              && T::Sunrise::is_subject_to_cooldown(account_id, era_index)
+            {
+              // the previous era can be claimed once the `Cooldown` is completed
+              Some(start_block.saturating_add(T::Sunrise::cooldown_blocks_count()))
+            } else {
+              Some(start_block)
+            }
+          });
+          (era_index, reward, claimable_at)
+        })
+        .collect()
+    }
   }
 }
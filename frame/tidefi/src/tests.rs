@@ -16,8 +16,9 @@
 
 use crate::{
   mock::{
-    new_test_ext, Adapter, Assets, Balances, Event as MockEvent, ExistentialDeposit, Fees, Oracle,
-    Origin, Quorum, Sunrise, SwapLimitByAccount, System, Test, Tidefi,
+    new_test_ext, Adapter, Assets, Balances, Cooldown, Event as MockEvent, ExistentialDeposit,
+    Fees, Oracle, Origin, Quorum, Security, Sunrise, SwapLimitByAccount, System, Test, Tidefi,
+    TidefiStaking,
   },
   pallet::*,
 };
@@ -29,8 +30,9 @@ use frame_support::{
 use pallet_assets::{Account, Error as AssetsError};
 use pallet_balances::Error as BalancesError;
 use pallet_oracle::{AccountSwaps, Error as OracleError};
+use pallet_security::{RateLimit, Subsystem};
 use pallet_sunrise::Error as SunriseError;
-use sp_runtime::{traits::BadOrigin, Permill};
+use sp_runtime::{traits::BadOrigin, Percent, Permill};
 use std::str::FromStr;
 use tidefi_primitives::{
   pallet::{FeesExt, OracleExt, SunriseExt},
@@ -449,6 +451,31 @@ mod transfer {
       });
     }
 
+    #[test]
+    fn currency_is_paused() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, 10 * ONE_TDFY)
+          .create_temp_asset_and_metadata()
+          .mint_temp(ALICE_ACCOUNT_ID, 10 * ONE_TEMP);
+        assert_ok!(Security::set_currency_status(
+          Origin::root(),
+          TEMP_CURRENCY_ID,
+          true
+        ));
+
+        assert_noop!(
+          Tidefi::transfer(
+            Origin::signed(context.sender),
+            context.receiver,
+            TEMP_CURRENCY_ID,
+            context.amount
+          ),
+          Error::<Test>::CurrencyPaused
+        );
+      });
+    }
+
     #[test]
     fn non_tdfy_fund_sender_does_not_exist() {
       new_test_ext().execute_with(|| {
@@ -610,6 +637,67 @@ mod withdrawal {
       });
     }
 
+    #[test]
+    fn withdrawals_are_paused() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, 10 * ONE_TDFY)
+          .create_temp_asset_and_metadata()
+          .mint_temp(ALICE_ACCOUNT_ID, 10 * ONE_TEMP);
+        assert_ok!(Security::set_subsystem_status(
+          Origin::root(),
+          Subsystem::Withdrawals,
+          true
+        ));
+
+        assert_noop!(
+          Tidefi::withdrawal(
+            Origin::signed(context.sender),
+            TEMP_CURRENCY_ID,
+            context.amount,
+            context.external_address.clone(),
+          ),
+          Error::<Test>::WithdrawalsPaused
+        );
+      });
+    }
+
+    #[test]
+    fn rate_limit_is_exceeded() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, 10 * ONE_TDFY)
+          .create_temp_asset_and_metadata()
+          .mint_temp(ALICE_ACCOUNT_ID, 10 * ONE_TEMP);
+        assert_ok!(Security::set_rate_limit(
+          Origin::root(),
+          Subsystem::Withdrawals,
+          TEMP_CURRENCY_ID,
+          Some(RateLimit {
+            period: 100,
+            global_cap: 10 * context.amount,
+            account_cap: context.amount,
+          })
+        ));
+        assert_ok!(Tidefi::withdrawal(
+          Origin::signed(context.sender),
+          TEMP_CURRENCY_ID,
+          context.amount,
+          context.external_address.clone(),
+        ));
+
+        assert_noop!(
+          Tidefi::withdrawal(
+            Origin::signed(context.sender),
+            TEMP_CURRENCY_ID,
+            1,
+            context.external_address.clone(),
+          ),
+          Error::<Test>::WithdrawalRateLimitExceeded
+        );
+      });
+    }
+
     #[test]
     fn asset_is_tdfy() {
       new_test_ext().execute_with(|| {
@@ -826,6 +914,64 @@ mod swap {
       });
     }
 
+    #[test]
+    fn swaps_are_paused() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ONE_TDFY)
+          .mint_tdfy(BOB_ACCOUNT_ID, 20 * ONE_TDFY)
+          .create_temp_asset_and_metadata()
+          .mint_temp(BOB_ACCOUNT_ID, 10_000 * ONE_TEMP);
+        assert_ok!(Security::set_subsystem_status(
+          Origin::root(),
+          Subsystem::Swaps,
+          true
+        ));
+
+        assert_noop!(
+          Tidefi::swap(
+            Origin::signed(context.sender),
+            CurrencyId::Tdfy,
+            10 * ONE_TDFY,
+            TEMP_CURRENCY_ID,
+            200 * ONE_TEMP,
+            SwapType::Limit,
+            None
+          ),
+          Error::<Test>::SwapsPaused
+        );
+      });
+    }
+
+    #[test]
+    fn currency_is_paused() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(ALICE_ACCOUNT_ID, ONE_TDFY)
+          .mint_tdfy(BOB_ACCOUNT_ID, 20 * ONE_TDFY)
+          .create_temp_asset_and_metadata()
+          .mint_temp(BOB_ACCOUNT_ID, 10_000 * ONE_TEMP);
+        assert_ok!(Security::set_currency_status(
+          Origin::root(),
+          TEMP_CURRENCY_ID,
+          true
+        ));
+
+        assert_noop!(
+          Tidefi::swap(
+            Origin::signed(context.sender),
+            CurrencyId::Tdfy,
+            10 * ONE_TDFY,
+            TEMP_CURRENCY_ID,
+            200 * ONE_TEMP,
+            SwapType::Limit,
+            None
+          ),
+          Error::<Test>::CurrencyPaused
+        );
+      });
+    }
+
     #[test]
     fn asset_is_disabled() {
       new_test_ext().execute_with(|| {
@@ -1607,3 +1753,165 @@ mod claim_sunrise_rewards {
     }
   }
 }
+
+mod claim_all_sunrise_rewards {
+  use super::*;
+
+  #[test]
+  fn succeeds() {
+    new_test_ext().execute_with(|| {
+      let context = Context::default()
+        .mint_tdfy(Sunrise::account_id(), 1_000 * ONE_TDFY)
+        .mint_tdfy(CHARLIE_ACCOUNT_ID, 1_000 * ONE_TDFY)
+        .set_active_era(4, 1)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 1, ONE_TDFY)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 2, 2 * ONE_TDFY)
+        // previous era, the cooldown isn't completed
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 3, 4 * ONE_TDFY);
+
+      let rewards_claimer_balance_before = Adapter::balance(CurrencyId::Tdfy, &CHARLIE_ACCOUNT_ID);
+
+      assert_ok!(Tidefi::claim_all_sunrise_rewards(
+        context.rewards_claimer,
+        None
+      ));
+
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &CHARLIE_ACCOUNT_ID),
+        rewards_claimer_balance_before.saturating_add(3 * ONE_TDFY)
+      );
+      assert_eq!(Sunrise::sunrise_rewards(CHARLIE_ACCOUNT_ID, 1), 0);
+      assert_eq!(Sunrise::sunrise_rewards(CHARLIE_ACCOUNT_ID, 2), 0);
+      assert_eq!(
+        Sunrise::sunrise_rewards(CHARLIE_ACCOUNT_ID, 3),
+        4 * ONE_TDFY
+      );
+
+      System::assert_has_event(MockEvent::Sunrise(pallet_sunrise::Event::SunriseClaimed {
+        era_index: 2,
+        account_id: CHARLIE_ACCOUNT_ID,
+        reward: 2 * ONE_TDFY,
+      }));
+    });
+  }
+
+  #[test]
+  fn stakes_claimed_rewards() {
+    new_test_ext().execute_with(|| {
+      let context = Context::default()
+        .mint_tdfy(Sunrise::account_id(), 1_000 * ONE_TDFY)
+        .mint_tdfy(CHARLIE_ACCOUNT_ID, 1_000 * ONE_TDFY)
+        .set_active_era(4, 1)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 1, ONE_TDFY)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 2, 2 * ONE_TDFY);
+      pallet_tidefi_stake::StakingPeriodRewards::<Test>::put(
+        BoundedVec::try_from(vec![(150, Percent::from_parts(1))]).unwrap(),
+      );
+
+      let rewards_claimer_balance_before = Adapter::balance(CurrencyId::Tdfy, &CHARLIE_ACCOUNT_ID);
+
+      assert_ok!(Tidefi::claim_all_sunrise_rewards(
+        context.rewards_claimer,
+        Some(150)
+      ));
+
+      // the rewards are staked
+      assert_eq!(
+        Adapter::balance(CurrencyId::Tdfy, &CHARLIE_ACCOUNT_ID),
+        rewards_claimer_balance_before
+      );
+      let stakes = TidefiStaking::account_stakes(CHARLIE_ACCOUNT_ID);
+      assert_eq!(stakes.len(), 1);
+      assert_eq!(stakes[0].initial_balance, 3 * ONE_TDFY);
+      assert_eq!(stakes[0].duration, 150);
+    });
+  }
+
+  mod fails_when {
+    use super::*;
+
+    #[test]
+    fn not_signed() {
+      new_test_ext().execute_with(|| {
+        Context::default()
+          .mint_tdfy(Sunrise::account_id(), 1_000 * ONE_TDFY)
+          .set_active_era(4, 1)
+          .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 1, ONE_TDFY);
+
+        assert_noop!(
+          Tidefi::claim_all_sunrise_rewards(Origin::none(), None),
+          BadOrigin
+        );
+      });
+    }
+
+    #[test]
+    fn no_rewards_available() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(Sunrise::account_id(), 1_000 * ONE_TDFY)
+          .mint_tdfy(CHARLIE_ACCOUNT_ID, 1_000 * ONE_TDFY)
+          .set_active_era(4, 1)
+          .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 3, ONE_TDFY);
+
+        assert_noop!(
+          Tidefi::claim_all_sunrise_rewards(context.rewards_claimer, None),
+          SunriseError::<Test>::NoRewardsAvailable
+        );
+      });
+    }
+
+    #[test]
+    fn invalid_stake_duration() {
+      new_test_ext().execute_with(|| {
+        let context = Context::default()
+          .mint_tdfy(Sunrise::account_id(), 1_000 * ONE_TDFY)
+          .mint_tdfy(CHARLIE_ACCOUNT_ID, 1_000 * ONE_TDFY)
+          .set_active_era(4, 1)
+          .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 1, ONE_TDFY);
+
+        assert_noop!(
+          Tidefi::claim_all_sunrise_rewards(context.rewards_claimer, Some(42)),
+          pallet_tidefi_stake::Error::<Test>::InvalidDuration
+        );
+      });
+    }
+  }
+}
+
+mod get_account_sunrise_rewards {
+  use super::*;
+
+  #[test]
+  fn returns_claimable_block_by_era() {
+    new_test_ext().execute_with(|| {
+      Context::default()
+        .set_active_era(4, 1)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 3, 4 * ONE_TDFY)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 1, ONE_TDFY)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 4, 8 * ONE_TDFY)
+        .set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 2, 0);
+
+      assert_eq!(
+        Tidefi::get_account_sunrise_rewards(&CHARLIE_ACCOUNT_ID),
+        vec![
+          (1, ONE_TDFY, Some(1)),
+          (3, 4 * ONE_TDFY, Some(1 + Cooldown::get())),
+          (4, 8 * ONE_TDFY, None),
+        ]
+      );
+    });
+  }
+
+  #[test]
+  fn returns_no_claimable_block_without_active_era() {
+    new_test_ext().execute_with(|| {
+      Context::default().set_sunrise_rewards(CHARLIE_ACCOUNT_ID, 1, ONE_TDFY);
+
+      assert_eq!(
+        Tidefi::get_account_sunrise_rewards(&CHARLIE_ACCOUNT_ID),
+        vec![(1, ONE_TDFY, None)]
+      );
+    });
+  }
+}
@@ -26,6 +26,7 @@ pub trait WeightInfo {
    fn swap() -> Weight;
    fn transfer() -> Weight;
    fn claim_sunrise_rewards() -> Weight;
+   fn claim_all_sunrise_rewards(e: u32, ) -> Weight;
 }
 
 /// Weights for `pallet_tidefi` using the Substrate node and recommended hardware.
@@ -51,4 +52,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
       .saturating_add(T::DbWeight::get().reads(6_u64))
       .saturating_add(T::DbWeight::get().writes(5_u64))
    }
+   fn claim_all_sunrise_rewards(e: u32, ) -> Weight {
+      48_700_000_u64
+      .saturating_add(22_100_000_u64.saturating_mul(e as Weight))
+      .saturating_add(T::DbWeight::get().reads(12_u64))
+      .saturating_add(T::DbWeight::get().reads(3_u64.saturating_mul(e as Weight)))
+      .saturating_add(T::DbWeight::get().writes(8_u64))
+      .saturating_add(T::DbWeight::get().writes(3_u64.saturating_mul(e as Weight)))
+   }
 }
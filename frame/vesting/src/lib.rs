@@ -73,6 +73,43 @@ pub use weights::WeightInfo;
 
 pub const VESTING_LOCK_ID: LockIdentifier = *b"ormlvest";
 
+/// Interface used by other pallets to transfer funds released through a vesting schedule.
+pub trait VestedTransfer<AccountId, BlockNumber, Balance: MaxEncodedLen + HasCompact> {
+  /// The minimum amount transferred with a vesting schedule.
+  fn min_vested_transfer() -> Balance;
+
+  /// Whether a new vesting schedule can be added to `who`, without exceeding the maximum number
+  /// of schedules.
+  fn can_add_vesting_schedule(who: &AccountId) -> bool;
+
+  /// Transfer the total amount of the `schedule` from `from` to `to`, locked until released.
+  fn vested_transfer(
+    from: &AccountId,
+    to: &AccountId,
+    schedule: VestingSchedule<BlockNumber, Balance>,
+  ) -> DispatchResult;
+}
+
+impl<AccountId, BlockNumber, Balance: MaxEncodedLen + HasCompact + Zero>
+  VestedTransfer<AccountId, BlockNumber, Balance> for ()
+{
+  fn min_vested_transfer() -> Balance {
+    Zero::zero()
+  }
+
+  fn can_add_vesting_schedule(_who: &AccountId) -> bool {
+    false
+  }
+
+  fn vested_transfer(
+    _from: &AccountId,
+    _to: &AccountId,
+    _schedule: VestingSchedule<BlockNumber, Balance>,
+  ) -> DispatchResult {
+    Err(DispatchError::Other("Vested transfers are not supported"))
+  }
+}
+
 /// The vesting schedule.
 ///
 /// Benefits would be granted gradually, `per_period` amount every `period`
@@ -425,6 +462,37 @@ impl<T: Config> Pallet<T> {
   }
 }
 
+impl<T: Config> VestedTransfer<T::AccountId, T::BlockNumber, BalanceOf<T>> for Pallet<T> {
+  fn min_vested_transfer() -> BalanceOf<T> {
+    T::MinVestedTransfer::get()
+  }
+
+  fn can_add_vesting_schedule(who: &T::AccountId) -> bool {
+    // the completed schedules are removed on the next transfer
+    let now = T::BlockNumberProvider::current_block_number();
+    let active_schedules = Self::vesting_schedules(who)
+      .iter()
+      .filter(|schedule| !schedule.locked_amount(now).is_zero())
+      .count();
+    active_schedules < T::MaxVestingSchedules::get() as usize
+  }
+
+  fn vested_transfer(
+    from: &T::AccountId,
+    to: &T::AccountId,
+    schedule: VestingScheduleOf<T>,
+  ) -> DispatchResult {
+    Self::do_vested_transfer(from, to, schedule.clone())?;
+
+    Self::deposit_event(Event::VestingScheduleAdded {
+      from: from.clone(),
+      to: to.clone(),
+      vesting_schedule: schedule,
+    });
+    Ok(())
+  }
+}
+
 /// Returns `Ok(total_total)` if valid schedule, or error.
 fn ensure_valid_vesting_schedule<T: Config>(
   schedule: &VestingScheduleOf<T>,
@@ -19,11 +19,13 @@ use crate::{
     AccountId, AuthorityDiscoveryId, Balance, Block, CurrencyId, EpochDuration, GrandpaId, Index,
     BABE_GENESIS_EPOCH_CONFIG,
   },
-  AssetRegistry, AuthorityDiscovery, Babe, Executive, Grandpa, Historical, InherentDataExt,
-  Runtime, SessionKeys, System, TidefiStaking, TransactionPayment, VERSION,
+  AssetRegistry, AuthorityDiscovery, Babe, Executive, Fees, Grandpa, Historical, InherentDataExt,
+  Runtime, Security, SessionKeys, Sunrise, System, TidefiStaking, TransactionPayment, VERSION,
 };
 use frame_support::traits::KeyOwnerProofSystem;
 use pallet_grandpa::{fg_primitives, AuthorityList as GrandpaAuthorityList};
+use pallet_sunrise::{SunriseOnboardingRefund, SunriseRewardsClaim};
+use pallet_tidefi_rpc_runtime_api::{AssetChainInfo, FeeInfo, SunrisePoolsInfo, SunriseRewardInfo};
 use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
 use sp_api::{impl_runtime_apis, ApisVec};
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
@@ -36,7 +38,9 @@ use sp_runtime::{
 };
 use sp_std::prelude::*;
 use sp_version::RuntimeVersion;
-use tidefi_primitives::{BalanceInfo, BlockNumber, CurrencyBalance, CurrencyMetadata, Stake};
+use tidefi_primitives::{
+  ActiveEraInfo, BalanceInfo, BlockNumber, CurrencyBalance, CurrencyMetadata, EraIndex, Hash, Stake,
+};
 
 // Work around the issue that RUNTIME_API_VERSIONS is not public.
 pub(crate) const PRUNTIME_API_VERSIONS: ApisVec = RUNTIME_API_VERSIONS;
@@ -223,8 +227,21 @@ impl_runtime_apis! {
 
    // Tidefi Custom API
    impl pallet_tidefi_rpc_runtime_api::TidefiApi<Block, AccountId> for Runtime {
-     fn get_assets() -> Result<Vec<(CurrencyId, CurrencyMetadata<Vec<u8>>)>, DispatchError> {
-       AssetRegistry::get_assets()
+     fn get_assets() -> Result<Vec<(CurrencyId, CurrencyMetadata<Vec<u8>>, Option<AssetChainInfo>)>, DispatchError> {
+       Ok(AssetRegistry::get_assets()?
+         .into_iter()
+         .map(|(currency_id, metadata)| {
+           let chain_info = AssetRegistry::get_chain_info(currency_id).map(|chain_info| AssetChainInfo {
+             origin_chain: chain_info.origin_chain,
+             contract_address: chain_info.contract_address,
+             minimum_deposit: BalanceInfo { amount: chain_info.minimum_deposit },
+             minimum_withdrawal: BalanceInfo { amount: chain_info.minimum_withdrawal },
+             withdrawal_fee: BalanceInfo { amount: chain_info.withdrawal_fee },
+             confirmations_required: chain_info.confirmations_required,
+           });
+           (currency_id, metadata, chain_info)
+         })
+         .collect())
      }
      fn get_account_balance(account_id: AccountId, asset_id: CurrencyId) -> Result<CurrencyBalance<BalanceInfo>, DispatchError> {
        AssetRegistry::get_account_balance(&account_id, asset_id)
@@ -232,9 +249,54 @@ impl_runtime_apis! {
      fn get_account_balances(account_id: AccountId) -> Result<Vec<(CurrencyId, CurrencyBalance<BalanceInfo>)>, DispatchError> {
        AssetRegistry::get_account_balances(&account_id)
      }
-     fn get_account_stakes(account_id: AccountId) -> Result<Vec<(CurrencyId, Stake<BalanceInfo, BlockNumber>)>, DispatchError> {
+     fn get_account_stakes(account_id: AccountId) -> Result<Vec<(CurrencyId, Stake<BalanceInfo, BlockNumber>, Option<AccountId>)>, DispatchError> {
        Ok(TidefiStaking::get_account_stakes(&account_id))
      }
+     fn get_unstake_queue_position(stake_id: Hash) -> Result<Option<(u32, BlockNumber)>, DispatchError> {
+       Ok(TidefiStaking::get_unstake_queue_position(stake_id))
+     }
+     fn estimate_stake_rewards(currency_id: CurrencyId, amount: Balance, duration: BlockNumber) -> Result<BalanceInfo, DispatchError> {
+       TidefiStaking::estimate_stake_rewards(currency_id, amount, duration).map(|amount| BalanceInfo { amount })
+     }
+     fn get_account_stakes_rewards(account_id: AccountId) -> Result<Vec<(Hash, BalanceInfo)>, DispatchError> {
+       Ok(TidefiStaking::estimate_account_stakes_rewards(&account_id))
+     }
+     fn get_era_fees(era_index: EraIndex) -> Result<Vec<(CurrencyId, FeeInfo)>, DispatchError> {
+       Ok(Fees::get_era_fees(era_index).into_iter().map(|(currency_id, fee)| (currency_id, fee.into())).collect())
+     }
+     fn get_account_fees(account_id: AccountId, start_era: EraIndex, end_era: EraIndex) -> Result<Vec<(EraIndex, Vec<(CurrencyId, FeeInfo)>)>, DispatchError> {
+       Fees::get_account_fees(&account_id, start_era, end_era).map(|account_fees| {
+         account_fees
+           .into_iter()
+           .map(|(era_index, fees)| (era_index, fees.into_iter().map(|(currency_id, fee)| (currency_id, fee.into())).collect()))
+           .collect()
+       })
+     }
+     fn get_current_era() -> Result<Option<ActiveEraInfo<BlockNumber>>, DispatchError> {
+       Ok(Fees::current_era())
+     }
+     fn get_sunrise_rewards(account_id: AccountId) -> Result<Vec<SunriseRewardInfo>, DispatchError> {
+       Ok(
+         pallet_tidefi::Pallet::<Runtime>::get_account_sunrise_rewards(&account_id)
+           .into_iter()
+           .map(|(era_index, reward, claimable_at)| SunriseRewardInfo {
+             era_index,
+             reward: BalanceInfo { amount: reward },
+             claimable_at,
+             last_claimable_era: Sunrise::last_claimable_era(era_index),
+           })
+           .collect()
+       )
+     }
+     fn get_sunrise_pools() -> Result<SunrisePoolsInfo, DispatchError> {
+       Ok(SunrisePoolsInfo::new(Sunrise::sunrise_pools().into_inner(), Sunrise::pools_left_over(), Sunrise::onboarding()))
+     }
+     fn get_onboarding_allowance(account_id: AccountId) -> Result<BalanceInfo, DispatchError> {
+       Ok(BalanceInfo { amount: Sunrise::remaining_onboarding_allowance(&account_id) })
+     }
+     fn get_request_id(account_id: AccountId, nonce: Index, index: u32) -> Result<Hash, DispatchError> {
+       Ok(Security::extrinsic_unique_id(&account_id, nonce, index))
+     }
    }
 
    #[cfg(feature = "try-runtime")]
@@ -295,6 +357,8 @@ impl_runtime_apis! {
        list_benchmark!(list, extra, pallet_quorum, crate::Quorum);
        list_benchmark!(list, extra, pallet_oracle, crate::Oracle);
        list_benchmark!(list, extra, pallet_asset_registry, crate::AssetRegistry);
+       list_benchmark!(list, extra, pallet_fees, crate::Fees);
+       list_benchmark!(list, extra, pallet_sunrise, crate::Sunrise);
        list_benchmark!(list, extra, frame_election_provider_support, ElectionProviderBench::<Runtime>);
 
        let storage_info = crate::AllPalletsWithSystem::storage_info();
@@ -370,6 +434,8 @@ impl_runtime_apis! {
            add_benchmark!(params, batches, pallet_quorum, crate::Quorum);
            add_benchmark!(params, batches, pallet_oracle, crate::Oracle);
            add_benchmark!(params, batches, pallet_asset_registry, crate::AssetRegistry);
+           add_benchmark!(params, batches, pallet_fees, crate::Fees);
+           add_benchmark!(params, batches, pallet_sunrise, crate::Sunrise);
            add_benchmark!(params, batches, pallet_preimage, crate::Preimage);
            add_benchmark!(params, batches, frame_election_provider_support, ElectionProviderBench::<Runtime>);
 
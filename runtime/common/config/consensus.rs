@@ -151,7 +151,10 @@ impl pallet_session::Config for Runtime {
   type ValidatorIdOf = pallet_staking::StashOf<Self>;
   type ShouldEndSession = Babe;
   type NextSessionRotation = Babe;
-  type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, Staking>;
+  type SessionManager = pallet_fees::FeesSessionManager<
+    Self,
+    pallet_session::historical::NoteHistoricalRoot<Self, Staking>,
+  >;
   type SessionHandler = <SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
   type Keys = SessionKeys;
   type WeightInfo = crate::weights::pallet_session::WeightInfo<Runtime>;
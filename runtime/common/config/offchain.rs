@@ -56,6 +56,7 @@ where
       frame_system::CheckNonce::<Runtime>::from(nonce),
       frame_system::CheckWeight::<Runtime>::new(),
       pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+      pallet_security::CheckExtrinsicSigner::<Runtime>::new(),
     );
     let raw_payload = SignedPayload::new(call, extra)
       .map_err(|e| {
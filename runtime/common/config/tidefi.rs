@@ -15,19 +15,23 @@
 // along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-  constants::currency::{deposit, Adapter, TDFY},
-  types::{AccountId, AssetId, Balance, BlockNumber, SessionIndex},
+  constants::{
+    currency::{deposit, Adapter, TDFY},
+    time::{HOURS, MINUTES},
+  },
+  types::{AccountId, AssetId, Balance, BlockNumber, EraIndex, SessionIndex},
   AssetRegistry, AssetRegistryPalletId, Balances, CouncilCollectiveInstance, Event, Fees,
-  FeesPalletId, Oracle, OraclePalletId, Origin, Quorum, QuorumPalletId, Runtime, Security, Sunrise,
-  SunrisePalletId, TidefiStaking, TidefiStakingPalletId, Timestamp,
+  FeesPalletId, Oracle, OraclePalletId, Origin, Quorum, QuorumPalletId, Runtime, Security, Staking,
+  Sunrise, SunrisePalletId, TechnicalCollectiveInstance, TidefiStaking, TidefiStakingPalletId,
+  Timestamp, TreasuryPalletId, Vesting,
 };
 
 use frame_support::{
   parameter_types,
-  traits::{ConstU128, EitherOfDiverse, EnsureOrigin},
+  traits::{ConstU128, EitherOfDiverse, EnsureOrigin, Get},
 };
 use frame_system::{EnsureRoot, RawOrigin};
-use sp_runtime::{traits::AccountIdConversion, FixedU128, Permill};
+use sp_runtime::{traits::AccountIdConversion, FixedU128, Percent, Permill};
 
 parameter_types! {
   pub const ApprovalDeposit: Balance = TDFY;
@@ -51,12 +55,32 @@ parameter_types! {
   pub const BlocksPerSession: BlockNumber = 50;
   // Staking: Number of blocks to wait before unstaking when we force-unstake.
   pub const BlocksForceUnstake: BlockNumber = 14_400;
+  // Staking: Percentage of the force-unstake fee refunded when a queued unstake is cancelled.
+  pub const CancelUnstakeFeeRefund: Percent = Percent::from_percent(50);
+  // Staking: Force-unstake fees are sent to the fees pallet
+  pub UnstakeFeeDestination: Option<AccountId> = Some(FeesPalletId::get().into_account_truncating());
+  // Staking: Number of past sessions used to estimate the staking rewards
+  // ~ 1 day
+  pub const RewardsProjectionSessions: SessionIndex = 288;
+  // Fees: Share of the session fees paid to the stakers, covers the highest staking reward (5%)
+  pub const StakersFeeShare: Percent = Percent::from_percent(5);
   // 0.25%
   pub const FeeAmount: Permill = Permill::from_parts(2500);
   // 0.10%
   pub const MarketMakerFeeAmount: Permill = Permill::from_parts(1000);
   // 0.05%
   pub const MarketMakerLimitFeeAmount: Permill = Permill::from_parts(500);
+  // Fees: Number of eras used to compute the trading volume for the volume fee tiers
+  // ~ 30 days
+  pub const VolumeTierEras: EraIndex = 720;
+  // Fees: Number of eras of fees history kept, the sunrise rewards of an era can be claimed
+  // after the `Cooldown` ~ 90 days
+  pub const EraArchive: EraIndex = 2_160;
+  // Fees: Sessions and eras driven by `BlocksPerSession` and `SessionsPerEra`, when `false`,
+  // or by `pallet_session` and the validators eras, when `true`. The eras counts above are sized
+  // for 1h eras, switching to the validators eras requires to rescale them and migrate the
+  // active era and session
+  pub const UseFeesSessionManager: bool = false;
   // Maximum pending burned possible in queue
   pub const BurnedCap: u32 = 1000;
   // Maximum proposals in queue for the quorum, to limit the vector size and optimization
@@ -82,6 +106,26 @@ parameter_types! {
   pub const MaximumRewardPerSwap: Balance = 100_000_000_000_000_000;
   // Rebates applied to left-over pool
   pub const LeftoverSwapRebates: FixedU128 = FixedU128::from_inner(500_000_000_000_000_000);
+  // Sunrise Pool: Maximum rewards an account can be allocated for the swaps of an era
+  pub const MaximumRewardPerAccountPerEra: Balance = 1_000_000 * TDFY;
+  // Sunrise Pool: Assets acquired through a swap have to be held ~ 10 minutes before their sale
+  // can be rewarded
+  pub const MinimumHoldingPeriod: BlockNumber = 10 * MINUTES;
+  // Sunrise Pool: Maximum onboarding rebates refunded to an account over its lifetime
+  pub const MaximumOnboardingRefundPerAccount: Balance = 1_000 * TDFY;
+  // Sunrise Pool: Number of eras the rewards can be claimed, after the `Cooldown` ~ 180 days
+  pub const RewardsClaimWindow: EraIndex = 4_320;
+  // Sunrise Pool: Expiry notice of the unclaimed rewards ~ 7 days
+  pub const RewardsExpiryNotice: EraIndex = 168;
+  // Sunrise Pool: Expired rewards are returned to the pools left-over
+  pub const ExpiredRewardsDestination: Option<AccountId> = None;
+  // Sunrise Pool: Claimed rewards are paid as a lump sum after the `Cooldown`, set to
+  // `Some((period, period_count))` to release them through vesting schedules instead
+  pub const SunriseRewardsVesting: Option<(BlockNumber, u32)> = None;
+  // Security: Number of blocks an emergency pause lasts, unless extended by root ~ 6 hours
+  pub const EmergencyPauseDuration: BlockNumber = 6 * HOURS;
+  // Security: Maximum length of the emergency pause reason
+  pub const PauseReasonLimit: u32 = 256;
 }
 
 pub struct EnsureRootOrAssetRegistry;
@@ -139,6 +183,7 @@ impl pallet_tidefi::Config for Runtime {
   type Oracle = Oracle;
   type Fees = Fees;
   type Sunrise = Sunrise;
+  type Staking = TidefiStaking;
   type Security = Security;
   // Wrapped currency
   type CurrencyTidefi = Adapter<AccountId>;
@@ -155,11 +200,20 @@ impl pallet_tidefi_stake::Config for Runtime {
   type StakeAccountCap = StakeAccountCap;
   type UnstakeQueueCap = UnstakeQueueCap;
   type BlocksForceUnstake = BlocksForceUnstake;
+  type CancelUnstakeFeeRefund = CancelUnstakeFeeRefund;
+  type UnstakeFeeDestination = UnstakeFeeDestination;
+  type RewardsProjectionSessions = RewardsProjectionSessions;
   // Asset registry
   type AssetRegistry = AssetRegistry;
   type Security = Security;
+  type Sunrise = Sunrise;
+  type Fees = Fees;
   type StakingRewardCap = StakingRewardCap;
   type WeightInfo = crate::weights::pallet_tidefi_stake::WeightInfo<Runtime>;
+  type ForceOrigin = EitherOfDiverse<
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollectiveInstance, 2, 3>,
+  >;
 }
 
 impl pallet_quorum::Config for Runtime {
@@ -172,6 +226,11 @@ impl pallet_quorum::Config for Runtime {
   type Sunrise = Sunrise;
   // Asset registry
   type AssetRegistry = AssetRegistry;
+  // Review of the deposits exceeding the rate limits
+  type MintReviewOrigin = EitherOfDiverse<
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollectiveInstance, 1, 2>,
+  >;
   type ProposalsCap = ProposalsCap;
   type BurnedCap = BurnedCap;
   type ProposalLifetime = ProposalLifetime;
@@ -199,6 +258,15 @@ impl pallet_oracle::Config for Runtime {
 
 impl pallet_security::Config for Runtime {
   type Event = Event;
+  type PauseOrigin = EitherOfDiverse<
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollectiveInstance, 1, 2>,
+  >;
+  type EmergencyOrigin =
+    pallet_collective::EnsureProportionMoreThan<AccountId, TechnicalCollectiveInstance, 1, 2>;
+  type EmergencyInitiator = ();
+  type EmergencyPauseDuration = EmergencyPauseDuration;
+  type PauseReasonLimit = PauseReasonLimit;
   type WeightInfo = crate::weights::pallet_security::WeightInfo<Runtime>;
 }
 
@@ -207,22 +275,45 @@ impl pallet_asset_registry::Config for Runtime {
   type AssetRegistryPalletId = AssetRegistryPalletId;
   // Wrapped currency
   type CurrencyTidefi = Adapter<AccountId>;
+  type StringLimit = StringLimit;
   type WeightInfo = crate::weights::pallet_asset_registry::WeightInfo<Runtime>;
 }
 
+/// Active era of the validators staking
+pub struct StakingActiveEra;
+impl Get<Option<EraIndex>> for StakingActiveEra {
+  fn get() -> Option<EraIndex> {
+    Staking::active_era().map(|active_era| active_era.index)
+  }
+}
+
+/// Active era of the fees
+pub struct FeesActiveEra;
+impl Get<Option<EraIndex>> for FeesActiveEra {
+  fn get() -> Option<EraIndex> {
+    pallet_fees::ActiveEra::<Runtime>::get().map(|active_era| active_era.index)
+  }
+}
+
 impl pallet_fees::Config for Runtime {
   type Event = Event;
   type FeesPalletId = FeesPalletId;
+  type TreasuryPalletId = TreasuryPalletId;
   type CurrencyTidefi = Adapter<AccountId>;
   type UnixTime = Timestamp;
   type SessionsPerEra = SessionsPerEra;
   type SessionsArchive = SessionsArchive;
   type BlocksPerSession = BlocksPerSession;
+  type UseSessionManager = UseFeesSessionManager;
+  type ValidatorEra = StakingActiveEra;
   type Staking = TidefiStaking;
   // Swap fees
   type FeeAmount = FeeAmount;
   type MarketMakerFeeAmount = MarketMakerFeeAmount;
   type MarketMakerLimitFeeAmount = MarketMakerLimitFeeAmount;
+  type VolumeTierEras = VolumeTierEras;
+  type EraArchive = EraArchive;
+  type StakersFeeShare = StakersFeeShare;
   // Security utils
   type Security = Security;
   // Sunrise interface
@@ -231,6 +322,7 @@ impl pallet_fees::Config for Runtime {
     EnsureRoot<AccountId>,
     pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollectiveInstance, 2, 3>,
   >;
+  type WeightInfo = crate::weights::pallet_fees::WeightInfo<Runtime>;
 }
 
 impl pallet_sunrise::Config for Runtime {
@@ -241,4 +333,19 @@ impl pallet_sunrise::Config for Runtime {
   type Cooldown = Cooldown;
   type MaximumRewardPerSwap = MaximumRewardPerSwap;
   type LeftoverSwapRebates = LeftoverSwapRebates;
+  type MaximumRewardPerAccountPerEra = MaximumRewardPerAccountPerEra;
+  type MinimumHoldingPeriod = MinimumHoldingPeriod;
+  type LinkedAccounts = crate::impls::LinkedAccounts<Runtime>;
+  type MaximumOnboardingRefundPerAccount = MaximumOnboardingRefundPerAccount;
+  type CurrentEra = FeesActiveEra;
+  type RewardsClaimWindow = RewardsClaimWindow;
+  type RewardsExpiryNotice = RewardsExpiryNotice;
+  type ExpiredRewardsDestination = ExpiredRewardsDestination;
+  type RewardsVesting = SunriseRewardsVesting;
+  type Vesting = Vesting;
+  type ForceOrigin = EitherOfDiverse<
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollectiveInstance, 2, 3>,
+  >;
+  type WeightInfo = crate::weights::pallet_sunrise::WeightInfo<Runtime>;
 }
@@ -61,6 +61,31 @@ where
   }
 }
 
+/// Accounts linked through a proxy or sharing the same identity, their trades together are not
+/// rewarded by the sunrise pools.
+pub struct LinkedAccounts<R>(sp_std::marker::PhantomData<R>);
+impl<R> pallet_sunrise::LinkedAccounts<R::AccountId> for LinkedAccounts<R>
+where
+  R: pallet_proxy::Config + pallet_identity::Config,
+{
+  fn are_linked(account_id: &R::AccountId, other: &R::AccountId) -> bool {
+    // either account can be a proxy of the other
+    if pallet_proxy::Pallet::<R>::find_proxy(account_id, other, None).is_ok()
+      || pallet_proxy::Pallet::<R>::find_proxy(other, account_id, None).is_ok()
+    {
+      return true;
+    }
+
+    // sub-accounts are linked to their super account and to each other
+    let super_of = |who: &R::AccountId| {
+      pallet_identity::Pallet::<R>::super_of(who)
+        .map(|(super_account, _)| super_account)
+        .unwrap_or_else(|| who.clone())
+    };
+    super_of(account_id) == super_of(other)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
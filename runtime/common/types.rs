@@ -35,8 +35,8 @@ pub use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 pub use sp_runtime::traits::BlakeTwo256;
 
 pub use tidefi_primitives::{
-  AccountId, AccountIndex, AssetId, Balance, BlockNumber, CurrencyId, Hash, Index, Moment,
-  SessionIndex, Signature,
+  AccountId, AccountIndex, AssetId, Balance, BlockNumber, CurrencyId, EraIndex, Hash, Index,
+  Moment, SessionIndex, Signature,
 };
 
 pub type NegativeImbalance<T> = <pallet_balances::Pallet<T> as Currency<
@@ -115,6 +115,7 @@ pub type SignedExtra = (
   frame_system::CheckNonce<Runtime>,
   frame_system::CheckWeight<Runtime>,
   pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+  pallet_security::CheckExtrinsicSigner<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
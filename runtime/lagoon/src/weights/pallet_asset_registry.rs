@@ -51,4 +51,13 @@ impl<T: frame_system::Config> pallet_asset_registry::WeightInfo for WeightInfo<T
 		(20_244_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: AssetRegistry AssetRegistryAccountId (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: AssetRegistry AssetsChainInfo (r:0 w:1)
+	fn set_chain_info() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }
@@ -0,0 +1,63 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Placeholder weights for `pallet_fees`
+//!
+//! PLACEHOLDER: THESE WEIGHTS ARE HAND-WRITTEN, NOT BENCHMARK OUTPUT.
+//! Replace this file with the benchmark output before the release:
+//! `--chain=lagoon-dev --pallet=pallet_fees`
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_fees`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_fees::WeightInfo for WeightInfo<T> {
+	// Storage: Fees FeeDistribution (r:0 w:1)
+	fn set_fee_distribution() -> Weight {
+		(17_104_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Fees SwapFees (r:0 w:1)
+	fn set_swap_fees() -> Weight {
+		(16_955_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Fees PairSwapFees (r:0 w:2)
+	fn set_pair_swap_fees() -> Weight {
+		(19_871_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Fees VolumeFeeTiers (r:0 w:1)
+	fn set_volume_fee_tiers() -> Weight {
+		(18_640_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Fees PayFeesInTdfy (r:0 w:1)
+	fn set_pay_fees_in_tdfy() -> Weight {
+		(17_822_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Fees TdfyFeeDiscount (r:0 w:1)
+	fn set_tdfy_fee_discount() -> Weight {
+		(15_930_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
@@ -109,4 +109,15 @@ impl<T: frame_system::Config> pallet_quorum::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(k as Weight)))
 			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(k as Weight)))
 	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Quorum HeldMints (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Sunrise OnboardingRefunded (r:1 w:0)
+	fn review_held_mint() -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
 }
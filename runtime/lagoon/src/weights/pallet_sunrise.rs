@@ -0,0 +1,83 @@
+// Copyright 2021-2022 Semantic Network Ltd.
+// This file is part of Tidechain.
+
+// Tidechain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tidechain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tidechain.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Placeholder weights for `pallet_sunrise`
+//!
+//! PLACEHOLDER: THESE WEIGHTS ARE HAND-WRITTEN, NOT BENCHMARK OUTPUT.
+//! Replace this file with the benchmark output before the release:
+//! `--chain=lagoon-dev --pallet=pallet_sunrise`
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_sunrise`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_sunrise::WeightInfo for WeightInfo<T> {
+	// Storage: Sunrise Pools (r:1 w:1)
+	// Storage: Sunrise PoolsLeftOverBalance (r:1 w:0)
+	// Storage: Sunrise Onboarding (r:1 w:0)
+	// Storage: Sunrise PendingRewards (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	fn create_pool() -> Weight {
+		(30_512_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Sunrise Pools (r:1 w:1)
+	// Storage: Sunrise PoolsLeftOverBalance (r:1 w:0)
+	// Storage: Sunrise Onboarding (r:1 w:0)
+	// Storage: Sunrise PendingRewards (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	fn top_up_pool() -> Weight {
+		(29_847_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Sunrise Pools (r:1 w:1)
+	fn modify_pool() -> Weight {
+		(21_736_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Sunrise Pools (r:1 w:1)
+	// Storage: Sunrise PoolsLeftOverBalance (r:1 w:1)
+	fn close_pool() -> Weight {
+		(23_904_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Sunrise Pools (r:1 w:0)
+	// Storage: Sunrise PoolsLeftOverBalance (r:1 w:0)
+	// Storage: Sunrise PendingRewards (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	// Storage: Sunrise Onboarding (r:0 w:1)
+	fn set_onboarding_rebates() -> Weight {
+		(27_615_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Sunrise PoolsLeftOverBalance (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn sweep_pools_left_over() -> Weight {
+		(44_381_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+}
@@ -88,4 +88,27 @@ impl<T: frame_system::Config> pallet_tidefi::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Fees ActiveEra (r:1 w:0)
+	// Storage: Security CurrentBlockCount (r:1 w:0)
+	// Storage: Sunrise Rewards (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Sunrise PendingRewards (r:1 w:1)
+	// Storage: TidefiStaking StakingPeriodRewards (r:1 w:0)
+	// Storage: TidefiStaking StakingCurrencyMeta (r:1 w:0)
+	// Storage: Security Nonce (r:1 w:1)
+	// Storage: System ParentHash (r:1 w:0)
+	// Storage: TidefiStaking StakingPool (r:1 w:1)
+	// Storage: TidefiStaking InterestCompoundLastSession (r:1 w:0)
+	// Storage: TidefiStaking AccountStakes (r:1 w:1)
+	// Storage: TidefiStaking CounterForAccountStakes (r:1 w:1)
+	// Storage: Sunrise RewardsByEra (r:0 w:1)
+	fn claim_all_sunrise_rewards(e: u32, ) -> Weight {
+		(71_406_000 as Weight)
+			.saturating_add((38_915_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(e as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(e as Weight)))
+	}
 }
@@ -63,15 +63,79 @@ impl<T: frame_system::Config> pallet_tidefi_stake::WeightInfo for WeightInfo<T>
 			.saturating_add(T::DbWeight::get().reads(13 as Weight))
 			.saturating_add(T::DbWeight::get().writes(8 as Weight))
 	}
-	// Storage: TidefiStaking AccountStakes (r:1 w:0)
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: TidefiStaking StakingPeriodRewards (r:1 w:0)
+	// Storage: TidefiStaking StakingCurrencyMeta (r:1 w:0)
+	// Storage: Security Nonce (r:1 w:1)
+	// Storage: System ParentHash (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: TidefiStaking StakingPool (r:1 w:1)
+	// Storage: Security CurrentBlockCount (r:1 w:0)
+	// Storage: TidefiStaking AccountStakes (r:1 w:1)
+	// Storage: TidefiStaking InterestCompoundLastSession (r:1 w:0)
+	// Storage: TidefiStaking CounterForAccountStakes (r:1 w:1)
+	// Storage: TidefiStaking StakeRewardRecipients (r:0 w:1)
+	// Storage: TidefiStaking ApprovedStakeFunders (r:1 w:0)
+	fn stake_for() -> Weight {
+		(100_870_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(14 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: TidefiStaking ApprovedStakeFunders (r:0 w:1)
+	fn approve_stake_funder() -> Weight {
+		(24_315_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: TidefiStaking AccountStakes (r:2 w:2)
 	// Storage: Security CurrentBlockCount (r:1 w:0)
 	// Storage: TidefiStaking UnstakeFee (r:1 w:0)
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: TidefiStaking UnstakeQueue (r:1 w:1)
+	// Storage: TidefiStaking StakeReceipts (r:1 w:1)
 	fn unstake() -> Weight {
 		(76_036_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(7 as Weight))
-			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: TidefiStaking AccountStakes (r:2 w:2)
+	// Storage: Security CurrentBlockCount (r:1 w:0)
+	// Storage: TidefiStaking UnstakeFee (r:1 w:0)
+	// Storage: Sunrise ExchangeRateInTdfy (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: TidefiStaking UnstakeQueue (r:1 w:1)
+	// Storage: TidefiStaking StakeReceipts (r:1 w:1)
+	fn unstake_with_tdfy_fee() -> Weight {
+		(81_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: TidefiStaking AccountStakes (r:1 w:1)
+	// Storage: TidefiStaking UnstakeQueue (r:1 w:1)
+	// Storage: TidefiStaking QueuedUnstakeFees (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn cancel_unstake() -> Weight {
+		(73_480_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Assets Asset (r:1 w:0)
+	// Storage: AssetRegistry AssetsChainInfo (r:1 w:0)
+	// Storage: TidefiStaking StakingPool (r:1 w:0)
+	// Storage: TidefiStaking ReceiptStakedCurrencies (r:1 w:2)
+	// Storage: TidefiStaking ReceiptCurrencies (r:1 w:1)
+	fn set_receipt_currency() -> Weight {
+		(38_160_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
 }
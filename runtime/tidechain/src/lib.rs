@@ -248,13 +248,13 @@ construct_runtime!(
         Security: pallet_security::{Pallet, Call, Config, Storage, Event<T>} = 54,
 
         // Fees module
-        Fees: pallet_fees::{Pallet, Config<T>, Storage, Event<T>} = 55,
+        Fees: pallet_fees::{Pallet, Call, Config<T>, Storage, Event<T>} = 55,
 
         // Asset registry module
         AssetRegistry: pallet_asset_registry::{Pallet, Call, Config<T>, Storage, Event<T>} = 56,
 
         // Sunrise module
-        Sunrise: pallet_sunrise::{Pallet, Config<T>, Storage, Event<T>} = 57,
+        Sunrise: pallet_sunrise::{Pallet, Call, Config<T>, Storage, Event<T>} = 57,
 
         // Vesting module
         Vesting: pallet_vesting::{Pallet, Storage, Call, Event<T>, Config<T>} = 58,
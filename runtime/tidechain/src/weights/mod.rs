@@ -32,6 +32,7 @@ pub mod pallet_collective;
 pub mod pallet_democracy;
 pub mod pallet_election_provider_multi_phase;
 pub mod pallet_elections_phragmen;
+pub mod pallet_fees;
 pub mod pallet_grandpa;
 pub mod pallet_identity;
 pub mod pallet_im_online;
@@ -46,6 +47,7 @@ pub mod pallet_scheduler;
 pub mod pallet_security;
 pub mod pallet_session;
 pub mod pallet_staking;
+pub mod pallet_sunrise;
 pub mod pallet_tidefi;
 pub mod pallet_tidefi_stake;
 pub mod pallet_timestamp;
@@ -51,4 +51,37 @@ impl<T: frame_system::Config> pallet_security::WeightInfo for WeightInfo<T> {
 		(16_878_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Security PausedSubsystems (r:0 w:1)
+	fn set_subsystem_status() -> Weight {
+		(16_878_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Security PausedCurrencies (r:0 w:1)
+	fn set_currency_status() -> Weight {
+		(16_878_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Security ChainStatus (r:1 w:1)
+	// Storage: Security ActiveEmergencyPause (r:0 w:1)
+	fn emergency_pause() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Security ActiveEmergencyPause (r:1 w:1)
+	fn extend_emergency_pause() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// PLACEHOLDER: hand-written, not benchmark output. Replace with the benchmarked weight.
+	// Storage: Security RateLimits (r:0 w:1)
+	fn set_rate_limit() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }